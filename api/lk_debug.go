@@ -0,0 +1,21 @@
+package api
+
+// DebugInfo describes a running lk function
+type DebugInfo struct {
+	Source string
+	// current line, 0 if unknown
+	Line int
+	// first line of the function, 0 for a main chunk
+	LineDefined int
+	// number of lk frames on the stack, 1 for a main chunk
+	Depth int
+}
+
+// DebugHook is called before the VM runs a new line,
+// or jumps back to a line (eg: the next round of a loop).
+// The hook runs on the calling goroutine, the script waits for it.
+type DebugHook func(ls LkState, info DebugInfo)
+
+// ProfileHook takes a sample of the running stack,
+// weight is the number of intervals passed since the last sample.
+type ProfileHook func(ls LkState, weight int)
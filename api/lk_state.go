@@ -1,5 +1,7 @@
 package api
 
+import "time"
+
 type GoFunction func(LkState) int
 
 func LkUpvalueIndex(i int) int {
@@ -99,10 +101,17 @@ type BasicAPI interface {
 	Status() LkStatus
 	IsYieldable() bool
 	GetStack() bool // debug
+	PushLocals()
+	/* debug functions */
+	SetDebugHook(hook DebugHook)
+	GetInfo(level int) (DebugInfo, bool)
+	GetLocal(level, n int) string
+	SetProfiler(interval time.Duration, hook ProfileHook) (stop func())
 
 	// isRepl: is in repl mode.
 	// 如果处于 repl，则只输出最后的栈的情况
 	CatchAndPrint(isRepl bool)
+	PrintError(err any, isRepl bool)
 }
 
 type FuncReg map[string]GoFunction
@@ -139,6 +148,8 @@ type AuxLib interface {
 	GetMetafield(obj int, e string) LkType
 	CallMeta(obj int, e string) bool
 	OpenLibs()
+	// Close runs shutdown hooks of opened libs, later calls do nothing
+	Close()
 	RequireF(modname string, openf GoFunction, glb bool)
 	NewLib(l FuncReg)
 	NewLibTable(l FuncReg)
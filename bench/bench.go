@@ -0,0 +1,184 @@
+package bench
+
+import (
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/lollipopkit/gommon/log"
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+	"github.com/lollipopkit/lk/state"
+)
+
+// Global functions with this prefix are treated as benchmarks
+const FuncPrefix = "bench_"
+
+type Config struct {
+	Warmup     int
+	Iterations int
+	// Baseline json file to compare with, optional
+	Baseline string
+	// Where to save results of this run, optional
+	Save string
+	// Allowed p50 regression against baseline, in percent
+	Threshold float64
+	// p50 differences below it (ns) are noise, never a regression
+	Noise int64
+}
+
+// Durations are in nanoseconds
+type Result struct {
+	Mean int64 `json:"mean"`
+	P50  int64 `json:"p50"`
+	P99  int64 `json:"p99"`
+}
+
+// Run executes the file at `path`, then times every global function
+// whose name starts with `bench_`.
+// It returns false if any benchmark regressed past the threshold.
+func Run(path string, cfg Config) bool {
+	ls := state.New()
+	defer ls.CatchAndPrint(false)
+	ls.OpenLibs()
+	if ls.LoadFile(path) != LK_OK {
+		log.Red("[bench] " + ls.ToString(-1))
+		os.Exit(1)
+	}
+	ls.Call(0, 0)
+
+	names := benchFuncs(ls)
+	if len(names) == 0 {
+		log.Yellow("[bench] no function named '%s*' in %s", FuncPrefix, path)
+		return true
+	}
+
+	results := make(map[string]Result, len(names))
+	for _, name := range names {
+		results[name] = measure(ls, name, cfg)
+	}
+
+	var baseline map[string]Result
+	if cfg.Baseline != "" {
+		baseline = loadBaseline(cfg.Baseline)
+	}
+	ok := report(names, results, baseline, cfg)
+
+	if cfg.Save != "" {
+		data, err := Json.MarshalIndent(results, "", "  ")
+		if err != nil {
+			log.Red("[bench] marshal results failed: " + err.Error())
+			os.Exit(1)
+		}
+		if err := os.WriteFile(cfg.Save, data, 0644); err != nil {
+			log.Red("[bench] write results failed: " + err.Error())
+			os.Exit(1)
+		}
+	}
+	return ok
+}
+
+func benchFuncs(ls LkState) []string {
+	names := []string{}
+	ls.PushGlobalTable()
+	ls.PushNil()
+	for ls.Next(-2) {
+		name, ok := ls.ToStringX(-2)
+		if ok && strings.HasPrefix(name, FuncPrefix) && ls.IsFunction(-1) {
+			names = append(names, name)
+		}
+		ls.Pop(1)
+	}
+	ls.Pop(1)
+	sort.Strings(names)
+	return names
+}
+
+func measure(ls LkState, name string, cfg Config) Result {
+	for i := 0; i < cfg.Warmup; i++ {
+		callGlobal(ls, name)
+	}
+
+	samples := make([]int64, cfg.Iterations)
+	var sum int64
+	for i := range samples {
+		start := time.Now()
+		callGlobal(ls, name)
+		samples[i] = time.Since(start).Nanoseconds()
+		sum += samples[i]
+	}
+	sort.Slice(samples, func(i, j int) bool { return samples[i] < samples[j] })
+
+	return Result{
+		Mean: sum / int64(len(samples)),
+		P50:  percentile(samples, 50),
+		P99:  percentile(samples, 99),
+	}
+}
+
+func callGlobal(ls LkState, name string) {
+	ls.GetGlobal(name)
+	ls.Call(0, 0)
+}
+
+// samples must be sorted
+func percentile(samples []int64, p int) int64 {
+	idx := (len(samples)*p+99)/100 - 1
+	if idx < 0 {
+		idx = 0
+	}
+	return samples[idx]
+}
+
+func loadBaseline(path string) map[string]Result {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		log.Red("[bench] can't read baseline: " + err.Error())
+		os.Exit(1)
+	}
+	baseline := map[string]Result{}
+	if err := Json.Unmarshal(data, &baseline); err != nil {
+		log.Red("[bench] invalid baseline: " + err.Error())
+		os.Exit(1)
+	}
+	return baseline
+}
+
+func report(names []string, results, baseline map[string]Result, cfg Config) bool {
+	regressions := 0
+	fmt.Printf("%-24s %12s %12s %12s\n", "name", "mean", "p50", "p99")
+	for _, name := range names {
+		r := results[name]
+		line := fmt.Sprintf("%-24s %12s %12s %12s", name,
+			time.Duration(r.Mean), time.Duration(r.P50), time.Duration(r.P99))
+
+		base, found := baseline[name]
+		if !found || base.P50 <= 0 {
+			if baseline != nil {
+				line += "     (new)"
+			}
+			fmt.Println(line)
+			continue
+		}
+		delta := float64(r.P50-base.P50) / float64(base.P50) * 100
+		line += fmt.Sprintf(" %+7.2f%%", delta)
+		if delta > cfg.Threshold && r.P50-base.P50 > cfg.Noise {
+			regressions++
+			log.Red(line)
+		} else {
+			log.Green(line)
+		}
+	}
+
+	for name := range baseline {
+		if _, ok := results[name]; !ok {
+			log.Yellow("%-24s (removed)", name)
+		}
+	}
+	if regressions > 0 {
+		log.Red("[bench] %d regressed more than %.1f%%", regressions, cfg.Threshold)
+	}
+	return regressions == 0
+}
@@ -0,0 +1,84 @@
+package bench
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/state"
+)
+
+// Each body runs `Loop` times inside a numeric for,
+// `empty` is the cost of the loop itself, subtracted from the others.
+var opcodeFamilies = map[string]string{
+	"empty":     "",
+	"arith":     "x = i + 1 - 2 * 3",
+	"arith_flt": "x = i / 2.5",
+	"cmp":       "x = i < 100",
+	"cmp_eq":    "x = i == 100",
+	"index":     "x = t[1]",
+	"access":    "x = t.name",
+	"set":       "t[1] = i",
+	"upval":     "x = up",
+	"global":    "x = G",
+	"call":      "f()",
+	"method":    "t:m()",
+	"concat":    "x = s + s",
+	"len":       "x = #t",
+}
+
+// Loop is the iterations of op in one call of a generated function
+const Loop = 1000
+
+// OpcodeSource generates a script with one `bench_op_*` function per family
+func OpcodeSource() string {
+	var b strings.Builder
+	b.WriteString("G = 1\nshy fn f() {}\nup := 1\ns := 'ab'\n")
+	b.WriteString("t := {1, 2, 'name': 'lk', 'm': fn(self) {}}\n")
+	for _, name := range opcodeNames() {
+		fmt.Fprintf(&b, "\nfn %sop_%s() {\n    x := nil\n    for i = 1, %d {\n        %s\n    }\n}\n",
+			FuncPrefix, name, Loop, opcodeFamilies[name])
+	}
+	return b.String()
+}
+
+func opcodeNames() []string {
+	names := make([]string, 0, len(opcodeFamilies))
+	for name := range opcodeFamilies {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// RunOpcodes times each opcode family, and prints ns/op
+// with the cost of an empty loop subtracted.
+func RunOpcodes(cfg Config) {
+	ls := state.New()
+	defer ls.CatchAndPrint(false)
+	ls.OpenLibs()
+	if ls.Load([]byte(OpcodeSource()), "opcodes", "t") != LK_OK {
+		panic(ls.ToString(-1))
+	}
+	ls.Call(0, 0)
+
+	results := map[string]Result{}
+	for _, name := range opcodeNames() {
+		results[name] = measure(ls, FuncPrefix+"op_"+name, cfg)
+	}
+
+	empty := results["empty"].P50
+	fmt.Printf("%-12s %12s\n", "family", "ns/op")
+	for _, name := range opcodeNames() {
+		if name == "empty" {
+			fmt.Printf("%-12s %12.2f (loop)\n", name, float64(empty)/Loop)
+			continue
+		}
+		ns := float64(results[name].P50-empty) / Loop
+		if ns < 0 {
+			ns = 0
+		}
+		fmt.Printf("%-12s %12.2f\n", name, ns)
+	}
+}
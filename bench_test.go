@@ -4,6 +4,8 @@ import (
 	"os"
 	"strings"
 	"testing"
+
+	"github.com/lollipopkit/lk/state"
 )
 
 const (
@@ -13,6 +15,20 @@ const (
 var (
 	skipTestList = []string{
 		"http_listen.lk",
+		"http_serve.lk",
+	}
+	// need libs or os fns left out by `-tags lk_minimal`
+	fullBuildList = []string{
+		"app.lk",
+		"config.lk",
+		"csv.lk",
+		"http_form.lk",
+		"http_req.lk",
+		"i18n.lk",
+		"kv.lk",
+		"os.lk",
+		"shell.lk",
+		"udp.lk",
 	}
 )
 
@@ -35,6 +51,9 @@ func TestMain(m *testing.M) {
 		if files[idx].IsDir() || contains(skipTestList, name) || !strings.HasSuffix(name, ".lk") {
 			continue
 		}
+		if !state.HasLib("http") && contains(fullBuildList, name) {
+			continue
+		}
 		println("=== " + name + " ===")
 		runVM("test/" + name)
 	}
@@ -46,3 +65,19 @@ func BenchmarkRun(b *testing.B) {
 		runVM(f)
 	}
 }
+
+// Call-heavy script, where allocating call frames dominates
+func BenchmarkCalls(b *testing.B) {
+	ls := state.New()
+	ls.OpenLibs()
+	if ls.DoString("fn fib(n) {\n if n < 2 {\n rt n\n }\n rt fib(n - 1) + fib(n - 2)\n}", "calls") {
+		b.Fatal(ls.ToString(-1))
+	}
+	b.ReportAllocs()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		if ls.DoString("fib(20)", "calls") {
+			b.Fatal(ls.ToString(-1))
+		}
+	}
+}
@@ -0,0 +1,21 @@
+package binchunk
+
+import (
+	"testing"
+
+	"github.com/lollipopkit/lk/consts"
+)
+
+func FuzzLoad(f *testing.F) {
+	f.Add([]byte(`{"si":"` + consts.SIGNATURE + `","m":"","p":{"s":"x","c":[1,2],"cs":[1,"a",null]}}`))
+	f.Add([]byte(`{"si":"` + consts.SIGNATURE + `","p":null}`))
+	f.Fuzz(func(t *testing.T, data []byte) {
+		proto, err := Load(data)
+		if err != nil || proto == nil {
+			return
+		}
+		if _, err := proto.Dump(""); err != nil {
+			t.Fatalf("loaded chunk can't be dumped: %v", err)
+		}
+	})
+}
@@ -28,12 +28,26 @@ type FloatExp struct {
 	Float float64
 }
 
+// 12.34d
+type DecimalExp struct {
+	Line int
+	// Digits without `d`, eg: 12.34
+	Dec string
+}
+
 // LiteralString
 type StringExp struct {
 	Line int
 	Str  string
 }
 
+// "a ${b} c", unescaped Lits around Exps, len(Lits) is len(Exps) + 1
+type InterpExp struct {
+	Line int
+	Lits []string
+	Exps []Exp
+}
+
 // unop exp
 type UnopExp struct {
 	Line int // line of operator
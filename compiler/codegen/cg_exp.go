@@ -28,8 +28,12 @@ func cgExp(fi *funcInfo, node Exp, a, n int) {
 		fi.emitLoadK(exp.Line, a, exp.Int)
 	case *FloatExp:
 		fi.emitLoadK(exp.Line, a, exp.Float)
+	case *DecimalExp:
+		fi.emitLoadDec(exp.Line, a, exp.Dec)
 	case *StringExp:
 		fi.emitLoadK(exp.Line, a, exp.Str)
+	case *InterpExp:
+		cgInterpExp(fi, exp, a)
 	case *ParensExp:
 		cgExp(fi, exp.Exp, a, 1)
 	case *VarargExp:
@@ -53,6 +57,27 @@ func cgExp(fi *funcInfo, node Exp, a, n int) {
 	}
 }
 
+// r[a] := str(r[b]) + ... + str(r[c]), r[b..c] are lits and exps in order
+func cgInterpExp(fi *funcInfo, node *InterpExp, a int) {
+	b, n := fi.usedRegs, 0
+	for i, lit := range node.Lits {
+		if lit != "" {
+			fi.emitLoadK(node.Line, fi.allocReg(), lit)
+			n++
+		}
+		if i < len(node.Exps) {
+			cgExp(fi, node.Exps[i], fi.allocReg(), 1)
+			n++
+		}
+	}
+	if n == 0 {
+		fi.emitLoadK(node.Line, a, "")
+		return
+	}
+	fi.freeRegs(n)
+	fi.emitABC(node.Line, OP_CONCAT, a, b, b+n-1)
+}
+
 func cgVarargExp(fi *funcInfo, node *VarargExp, a, n int) {
 	if !fi.isVararg {
 		panic("cannot use '...' outside a vararg function")
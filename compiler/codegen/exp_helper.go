@@ -31,8 +31,12 @@ func lineOf(exp Exp) int {
 		return x.Line
 	case *FloatExp:
 		return x.Line
+	case *DecimalExp:
+		return x.Line
 	case *StringExp:
 		return x.Line
+	case *InterpExp:
+		return x.Line
 	case *VarargExp:
 		return x.Line
 	case *NameExp:
@@ -68,8 +72,12 @@ func lastLineOf(exp Exp) int {
 		return x.Line
 	case *FloatExp:
 		return x.Line
+	case *DecimalExp:
+		return x.Line
 	case *StringExp:
 		return x.Line
+	case *InterpExp:
+		return x.Line
 	case *VarargExp:
 		return x.Line
 	case *NameExp:
@@ -327,6 +327,15 @@ func (self *funcInfo) emitLoadK(line, a int, k interface{}) {
 	}
 }
 
+// r[a] = decimal(kst[bx])
+func (self *funcInfo) emitLoadDec(line, a int, dec string) {
+	idx := self.indexOfConstant(dec)
+	if idx > MAXARG_Bx {
+		panic("too many constants for a decimal literal")
+	}
+	self.emitABx(line, OP_LOADDEC, a, idx)
+}
+
 // r[a], r[a+1], ..., r[a+b-2] = vararg
 func (self *funcInfo) emitVararg(line, a, n int) {
 	self.emitABC(line, OP_VARARG, a, n+1, 0)
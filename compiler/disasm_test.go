@@ -0,0 +1,37 @@
+package compiler
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/lollipopkit/lk/vm"
+)
+
+func TestDisasm(t *testing.T) {
+	const src = `shy sum = 0
+for i = 1, 3 {
+    sum = sum + i
+}
+shy f = fn(x) {
+    rt x + 'done'
+}
+print(f(sum))
+`
+	var sb strings.Builder
+	vm.Disasm(&sb, Compile(src, "disasm.lk"))
+	out := sb.String()
+	for _, want := range []string{
+		"fn main <disasm.lk:0,0>",
+		"L1:",
+		"; to L",
+		`; "done"`,
+		"; fn main.0",
+		"fn main.0 <disasm.lk:5,7> params=1",
+		"locals (",
+		"upvalues (",
+	} {
+		if !strings.Contains(out, want) {
+			t.Errorf("missing %q in:\n%s", want, out)
+		}
+	}
+}
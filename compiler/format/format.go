@@ -0,0 +1,172 @@
+package format
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/lollipopkit/lk/compiler/parser"
+)
+
+const indentStr = "    "
+
+// scanner states carried across lines
+const (
+	stateCode = iota
+	stateRawStr
+	stateLongComment
+	stateShortStr // after a `\` newline inside a quoted string
+)
+
+// Format re-indents lk source by bracket depth.
+// Comments, strings and the order of tokens are kept as is:
+//   - each line is indented by 4 spaces per open bracket,
+//     brackets opened on the same line only count once
+//   - trailing spaces are trimmed, blank lines are collapsed to one
+//   - lines inside raw strings and long comments are not touched
+//
+// src must parse, otherwise the syntax error is returned.
+func Format(src, name string) (out string, err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("%v", r)
+		}
+	}()
+	parser.Parse(src, name)
+
+	f := &formatter{empty: true}
+	lines := strings.Split(strings.ReplaceAll(src, "\r\n", "\n"), "\n")
+	if strings.HasPrefix(lines[0], "#!") {
+		f.write(strings.TrimRight(lines[0], " \t"))
+		lines = lines[1:]
+	}
+	for _, line := range lines {
+		f.line(line)
+	}
+	return f.buf.String(), nil
+}
+
+type formatter struct {
+	buf strings.Builder
+	// indent of lines inside each open bracket
+	stack []int
+	// indent of the current line
+	indent int
+	state  int
+	quote  byte
+	// backticks closing the raw string
+	fence  string
+	blanks int
+	// nothing but blank lines so far
+	empty bool
+}
+
+func (f *formatter) line(line string) {
+	if f.state != stateCode {
+		/* continue a multi-line token verbatim */
+		f.write(line)
+		f.scan(line)
+		return
+	}
+
+	code := strings.TrimSpace(line)
+	if code == "" {
+		f.blanks++
+		return
+	}
+
+	/* leading closers belong to the outer level */
+	closers := 0
+	for closers < len(code) && strings.IndexByte("})]", code[closers]) >= 0 {
+		closers++
+	}
+	f.indent = 0
+	if n := len(f.stack) - closers; n > 0 {
+		f.indent = f.stack[n-1]
+	}
+
+	prefix := strings.Repeat(indentStr, f.indent)
+	f.scan(code)
+	if f.state == stateCode {
+		f.write(prefix + code)
+	} else {
+		/* trailing spaces are part of the string or comment */
+		f.write(prefix + strings.TrimLeft(line, " \t"))
+	}
+}
+
+func (f *formatter) write(line string) {
+	if f.blanks > 0 && !f.empty {
+		f.buf.WriteString("\n")
+	}
+	f.blanks = 0
+	f.empty = false
+	f.buf.WriteString(line + "\n")
+}
+
+// scan tracks brackets, strings and comments of s
+func (f *formatter) scan(s string) {
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch f.state {
+		case stateRawStr:
+			if strings.HasPrefix(s[i:], f.fence) {
+				f.state = stateCode
+				i += len(f.fence) - 1
+			}
+			continue
+		case stateLongComment:
+			if c == '*' && i+1 < len(s) && s[i+1] == '/' {
+				f.state = stateCode
+				i++
+			}
+			continue
+		case stateShortStr:
+			if c == '\\' {
+				if i == len(s)-1 {
+					return /* escaped newline, still in the string */
+				}
+				i++
+			} else if c == f.quote {
+				f.state = stateCode
+			}
+			continue
+		}
+
+		switch c {
+		case '`':
+			/* ``` fenced strings can hold single backticks */
+			n := len(s[i:]) - len(strings.TrimLeft(s[i:], "`"))
+			if n == 2 {
+				i++ /* empty raw string */
+				continue
+			}
+			if n < 3 {
+				n = 1
+			}
+			f.fence = s[i : i+n]
+			f.state = stateRawStr
+			i += n - 1
+		case '\'', '"':
+			f.quote = c
+			f.state = stateShortStr
+		case '/':
+			if i+1 < len(s) && s[i+1] == '/' {
+				return
+			}
+			if i+1 < len(s) && s[i+1] == '*' {
+				f.state = stateLongComment
+				i++
+			}
+		case '{', '(', '[':
+			f.stack = append(f.stack, f.indent+1)
+		case '}', ')', ']':
+			if len(f.stack) > 0 {
+				f.stack = f.stack[:len(f.stack)-1]
+			}
+		}
+	}
+	if f.state == stateShortStr {
+		/* a quoted string ends with its line */
+		f.state = stateCode
+	}
+}
@@ -0,0 +1,85 @@
+package format
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestFormat(t *testing.T) {
+	cases := []struct {
+		name, src, want string
+	}{
+		{
+			"indent",
+			"if a {\nb()\n  } elif c {\n\t\td()\n}\n",
+			"if a {\n    b()\n} elif c {\n    d()\n}\n",
+		},
+		{
+			"brackets on one line indent once",
+			"app.run({\n'a': fn() {\nrt 1\n},\n})\n",
+			"app.run({\n    'a': fn() {\n        rt 1\n    },\n})\n",
+		},
+		{
+			"blank lines and trailing spaces",
+			"\n\na := 1   \n\n\n\nb := 2\n\n",
+			"a := 1\n\nb := 2\n",
+		},
+		{
+			"comments and strings",
+			"x := '{' // {\n  /* {\n   (  */\ny := \"}\"\n",
+			"x := '{' // {\n/* {\n   (  */\ny := \"}\"\n",
+		},
+		{
+			"raw strings are kept",
+			"if a {\ns := `\n  {  \n`\n}\n",
+			"if a {\n    s := `\n  {  \n`\n}\n",
+		},
+		{
+			"fenced strings hold backticks",
+			"if a {\ns := ```\n  `{`  \n  ```\nb := ``\n}\n",
+			"if a {\n    s := ```\n  `{`  \n  ```\n    b := ``\n}\n",
+		},
+		{
+			"shebang",
+			"#!/usr/bin/env lk\nprint(#{1})\n",
+			"#!/usr/bin/env lk\nprint(#{1})\n",
+		},
+	}
+	for _, c := range cases {
+		got, err := Format(c.src, c.name)
+		if err != nil {
+			t.Errorf("%s: %v", c.name, err)
+			continue
+		}
+		if got != c.want {
+			t.Errorf("%s:\ngot:\n%s\nwant:\n%s", c.name, got, c.want)
+		}
+	}
+}
+
+func TestFormatSyntaxError(t *testing.T) {
+	if _, err := Format("if {", "bad"); err == nil {
+		t.Error("want syntax error")
+	}
+}
+
+// Formatting is idempotent on the test scripts
+func TestFormatIdempotent(t *testing.T) {
+	files, _ := filepath.Glob("../../test/*.lk")
+	for _, file := range files {
+		src, err := os.ReadFile(file)
+		if err != nil {
+			t.Fatal(err)
+		}
+		once, err := Format(string(src), file)
+		if err != nil {
+			t.Errorf("%s: %v", file, err)
+			continue
+		}
+		twice, _ := Format(once, file)
+		if once != twice {
+			t.Errorf("%s: formatting is not idempotent", file)
+		}
+	}
+}
@@ -0,0 +1,117 @@
+package compiler
+
+import (
+	"flag"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/lollipopkit/lk/binchunk"
+	"github.com/lollipopkit/lk/compiler/parser"
+	"github.com/lollipopkit/lk/compiler/printer"
+	"github.com/lollipopkit/lk/vm"
+)
+
+// go test ./compiler -update
+var update = flag.Bool("update", false, "rewrite golden files")
+
+// TestGolden snapshots the printed source and bytecode of each
+// testdata/golden/*.lk into a .golden file next to it.
+// Golden files are only written with -update, review them in the diff.
+func TestGolden(t *testing.T) {
+	files, err := filepath.Glob("testdata/golden/*.lk")
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, file := range files {
+		file := file
+		t.Run(filepath.Base(file), func(t *testing.T) {
+			src, err := os.ReadFile(file)
+			if err != nil {
+				t.Fatal(err)
+			}
+			got := snapshot(string(src), filepath.Base(file))
+
+			golden := strings.TrimSuffix(file, ".lk") + ".golden"
+			if *update {
+				if err := os.WriteFile(golden, []byte(got), 0644); err != nil {
+					t.Fatal(err)
+				}
+				return
+			}
+			want, err := os.ReadFile(golden)
+			if os.IsNotExist(err) {
+				t.Fatalf("missing %s, run with -update", golden)
+			}
+			if err != nil {
+				t.Fatal(err)
+			}
+			if got != string(want) {
+				t.Errorf("%s changed, run with -update if it's expected:\n%s",
+					golden, lineDiff(string(want), got))
+			}
+		})
+	}
+}
+
+func snapshot(src, name string) string {
+	var b strings.Builder
+	b.WriteString("== source ==\n")
+	b.WriteString(printer.Print(parser.Parse(src, name)))
+	b.WriteString("\n== bytecode ==\n")
+	listProto(&b, Compile(src, name), "main")
+	return b.String()
+}
+
+func listProto(b *strings.Builder, proto *binchunk.Prototype, name string) {
+	fmt.Fprintf(b, "%s <%d,%d> params=%d vararg=%d stack=%d\n", name,
+		proto.LineDefined, proto.LastLineDefined,
+		proto.NumParams, proto.IsVararg, proto.MaxStackSize)
+	for pc, code := range proto.Code {
+		i := vm.Instruction(code)
+		var args string
+		switch i.OpMode() {
+		case vm.IABC:
+			a, bb, c := i.ABC()
+			args = fmt.Sprintf("%d %d %d", a, bb, c)
+		case vm.IABx:
+			a, bx := i.ABx()
+			args = fmt.Sprintf("%d %d", a, bx)
+		case vm.IAsBx:
+			a, sbx := i.AsBx()
+			args = fmt.Sprintf("%d %d", a, sbx)
+		case vm.IAx:
+			args = fmt.Sprintf("%d", i.Ax())
+		}
+		line := uint32(0)
+		if pc < len(proto.LineInfo) {
+			line = proto.LineInfo[pc]
+		}
+		fmt.Fprintf(b, "\t%d\t[%d]\t%s\t%s\n", pc+1, line, strings.TrimSpace(i.OpName()), args)
+	}
+	fmt.Fprintf(b, "constants %v\n", proto.Constants)
+	for idx, p := range proto.Protos {
+		listProto(b, p, fmt.Sprintf("%s.%d", name, idx))
+	}
+}
+
+// lineDiff shows lines only in want (-) or got (+), in order
+func lineDiff(want, got string) string {
+	ws, gs := strings.Split(want, "\n"), strings.Split(got, "\n")
+	var b strings.Builder
+	for i := 0; i < len(ws) || i < len(gs); i++ {
+		var w, g string
+		if i < len(ws) {
+			w = ws[i]
+		}
+		if i < len(gs) {
+			g = gs[i]
+		}
+		if w != g {
+			fmt.Fprintf(&b, "%d:\n-\t%s\n+\t%s\n", i+1, w, g)
+		}
+	}
+	return b.String()
+}
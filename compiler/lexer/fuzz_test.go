@@ -0,0 +1,41 @@
+package lexer
+
+import (
+	"os"
+	"path/filepath"
+	"runtime"
+	"testing"
+)
+
+// addCorpus seeds fuzzers with the scripts under test/
+func addCorpus(f *testing.F) {
+	files, _ := filepath.Glob("../../test/*.lk")
+	for _, file := range files {
+		if data, err := os.ReadFile(file); err == nil {
+			f.Add(string(data))
+		}
+	}
+}
+
+// Syntax errors are reported by panicking with a string,
+// any runtime error (index out of range, nil deref...) is a bug.
+func FuzzLexer(f *testing.F) {
+	addCorpus(f)
+	f.Add("a := 'x\\u{1F600}' ~/ 0x1p4 // c\n/* c */ #!/x")
+	f.Fuzz(func(t *testing.T, src string) {
+		defer func() {
+			if r := recover(); r != nil {
+				if _, ok := r.(runtime.Error); ok {
+					t.Fatalf("lexer crashed on %q: %v", src, r)
+				}
+			}
+		}()
+		lexer := NewLexer(src, "fuzz")
+		for i := 0; i <= len(src)+1; i++ {
+			if _, kind, _ := lexer.NextToken(); kind == TOKEN_EOF {
+				return
+			}
+		}
+		t.Fatalf("lexer doesn't reach EOF on %q", src)
+	})
+}
@@ -6,6 +6,8 @@ import (
 	"regexp"
 	"strconv"
 	"strings"
+
+	"github.com/lollipopkit/lk/decimal"
 )
 
 // var reSpaces = regexp.MustCompile(`^\s+`)
@@ -31,6 +33,11 @@ func NewLexer(chunk, chunkName string) *Lexer {
 	return &Lexer{chunk, chunkName, 1, "", 0, 0}
 }
 
+// Sub returns a lexer of src, an expr inside a string on line
+func (self *Lexer) Sub(src string, line int) *Lexer {
+	return &Lexer{chunk: src, chunkName: self.chunkName, line: line}
+}
+
 func (self *Lexer) Line() int {
 	return self.line
 }
@@ -231,14 +238,40 @@ func (self *Lexer) NextToken() (line, kind int, token string) {
 			return self.line, TOKEN_OP_QUESTION, "?"
 		}
 	case '\'', '"':
+		/* only "double quoted" strs are interpolated */
+		if self.chunk[0] == '"' {
+			str := reShortStr.FindString(self.chunk)
+			if end := interpStrEnd(self.chunk); end > 0 {
+				str = self.chunk[:end+1]
+			}
+			if hasInterp(str) {
+				line := self.line
+				self.next(len(str))
+				self.line += len(reNewLine.FindAllString(str, -1))
+				return line, TOKEN_STRING_INTERP, str[1 : len(str)-1]
+			}
+		}
 		return self.line, TOKEN_STRING, self.scanShortString()
 	case '`':
+		if n := len(self.chunk) - len(strings.TrimLeft(self.chunk, "`")); n >= 3 {
+			line := self.line
+			return line, TOKEN_STRING, self.scanFencedString(n)
+		}
 		return self.line, TOKEN_STRING, self.scanRawString()
 	}
 
 	c := self.chunk[0]
 	if c == '.' || isDigit(c) {
 		token := self.scanNumber()
+		/* decimal literal: 12.34d, `d` of hex literals is a digit */
+		isHex := strings.HasPrefix(token, "0x") || strings.HasPrefix(token, "0X")
+		if !isHex && self.test("d") && (len(self.chunk) == 1 || !isIdentChar(self.chunk[1])) {
+			if _, err := decimal.Parse(token); err != nil {
+				self.error("malformed decimal %sd", token)
+			}
+			self.next(1)
+			return self.line, TOKEN_DECIMAL, token
+		}
 		return self.line, TOKEN_NUMBER, token
 	}
 	if c == '_' || isLetter(c) {
@@ -348,6 +381,134 @@ func (self *Lexer) scanShortString() string {
 	return ""
 }
 
+// scanFencedString reads a raw string between runs of n backticks, n >= 3,
+// so it can hold single backticks. Like a heredoc, a newline right after
+// the opening fence is dropped, and if the closing fence is on its own line,
+// its indentation is removed from every line:
+//
+//	sql := ```
+//	    select *
+//	    from t
+//	    ```
+//
+// is 'select *\nfrom t\n'.
+func (self *Lexer) scanFencedString(n int) string {
+	fence := strings.Repeat("`", n)
+	self.next(n)
+	end := strings.Index(self.chunk, fence)
+	if end < 0 {
+		self.error("unfinished string")
+	}
+	str := self.chunk[:end]
+	self.line += len(reNewLine.FindAllString(str, -1))
+	self.next(end + n)
+
+	if strings.HasPrefix(str, "\r\n") {
+		str = str[2:]
+	} else {
+		str = strings.TrimPrefix(str, "\n")
+	}
+	last := strings.LastIndexByte(str, '\n')
+	if indent := str[last+1:]; last >= 0 && strings.TrimLeft(indent, " \t") == "" {
+		lines := strings.SplitAfter(str[:last+1], "\n")
+		for i := range lines {
+			lines[i] = strings.TrimPrefix(lines[i], indent)
+		}
+		str = strings.Join(lines, "")
+	}
+	return str
+}
+
+// hasInterp reports whether str has `${` not escaped by `\$`
+func hasInterp(str string) bool {
+	for i := 0; i < len(str)-1; i++ {
+		switch {
+		case str[i] == '\\':
+			i++
+		case str[i] == '$' && str[i+1] == '{':
+			return true
+		}
+	}
+	return false
+}
+
+// SplitInterp splits the body of TOKEN_STRING_INTERP into literal parts,
+// escaped, and the source of `${}` exprs between them.
+// len(lits) is always len(exprs) + 1.
+func (self *Lexer) SplitInterp(str string) (lits, exprs []string) {
+	start := 0
+	for i := 0; i < len(str)-1; i++ {
+		if str[i] == '\\' {
+			i++
+			continue
+		}
+		if str[i] != '$' || str[i+1] != '{' {
+			continue
+		}
+		end := interpEnd(str, i+2)
+		if end < 0 {
+			self.error("unfinished ${ in string")
+		}
+		lits = append(lits, self.escape(str[start:i]))
+		exprs = append(exprs, str[i+2:end])
+		start = end + 1
+		i = end
+	}
+	return append(lits, self.escape(str[start:])), exprs
+}
+
+// interpStrEnd is the index of `"` closing the str at the start of chunk,
+// `${}` exprs are skipped, so they can hold "double quoted" strs. -1 if none
+func interpStrEnd(chunk string) int {
+	for i := 1; i < len(chunk); i++ {
+		switch c := chunk[i]; {
+		case c == '\\':
+			i++
+			if i < len(chunk) && chunk[i] == 'z' {
+				for i+1 < len(chunk) && isWhiteSpace(chunk[i+1]) {
+					i++
+				}
+			}
+		case c == '\n':
+			return -1
+		case c == '$' && i+1 < len(chunk) && chunk[i+1] == '{':
+			if i = interpEnd(chunk, i+2); i < 0 {
+				return -1
+			}
+		case c == '"':
+			return i
+		}
+	}
+	return -1
+}
+
+// interpEnd is the index of `}` closing the expr from i, -1 if none
+func interpEnd(str string, i int) int {
+	depth := 0
+	var quote byte
+	for ; i < len(str); i++ {
+		c := str[i]
+		switch {
+		case quote != 0:
+			if c == '\\' {
+				i++
+			} else if c == quote {
+				quote = 0
+			}
+		case c == '\'' || c == '"' || c == '`':
+			quote = c
+		case c == '{':
+			depth++
+		case c == '}':
+			if depth == 0 {
+				return i
+			}
+			depth--
+		}
+	}
+	return -1
+}
+
 func (self *Lexer) scanRawString() string {
 	self.next(1)
 	openIdx := strings.Index(self.chunk, "`")
@@ -419,6 +580,10 @@ func (self *Lexer) escape(str string) string {
 			buf.WriteByte('\\')
 			str = str[2:]
 			continue
+		case '$':
+			buf.WriteByte('$')
+			str = str[2:]
+			continue
 		case '0', '1', '2', '3', '4', '5', '6', '7', '8', '9': // \ddd
 			if found := reDecEscapeSeq.FindString(str); found != "" {
 				d, _ := strconv.ParseInt(found[1:], 8, 32)
@@ -475,6 +640,10 @@ func isDigit(c byte) bool {
 	return c >= '0' && c <= '9'
 }
 
+func isIdentChar(c byte) bool {
+	return c == '_' || isLetter(c) || isDigit(c)
+}
+
 func isLetter(c byte) bool {
 	return c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z'
 }
@@ -0,0 +1,79 @@
+package lexer
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestSplitInterp(t *testing.T) {
+	cases := []struct {
+		src   string
+		lits  []string
+		exprs []string
+	}{
+		{`a ${b} c`, []string{"a ", " c"}, []string{"b"}},
+		{`${a}${b}`, []string{"", "", ""}, []string{"a", "b"}},
+		{`\${a} ${ {'}': 1} }\n`, []string{"${a} ", "\n"}, []string{` {'}': 1} `}},
+	}
+	for _, c := range cases {
+		lits, exprs := NewLexer("", "test").SplitInterp(c.src)
+		if !reflect.DeepEqual(lits, c.lits) || !reflect.DeepEqual(exprs, c.exprs) {
+			t.Errorf("%s: got %q %q", c.src, lits, exprs)
+		}
+	}
+}
+
+func TestInterpToken(t *testing.T) {
+	lexer := NewLexer(`'a' "b ${c}" 'd \${e}' 'f ${g}'`, "test")
+	for _, want := range []int{TOKEN_STRING, TOKEN_STRING_INTERP, TOKEN_STRING, TOKEN_STRING, TOKEN_EOF} {
+		if _, kind, token := lexer.NextToken(); kind != want {
+			t.Errorf("%s: got kind %d, want %d", token, kind, want)
+		}
+	}
+}
+
+func TestInterpNestedQuote(t *testing.T) {
+	lexer := NewLexer(`"a ${ m["}"] } ${ "${b}" }" 'c'`, "test")
+	if _, kind, token := lexer.NextToken(); kind != TOKEN_STRING_INTERP || token != `a ${ m["}"] } ${ "${b}" }` {
+		t.Errorf("got %d %q", kind, token)
+	}
+	if _, kind, token := lexer.NextToken(); kind != TOKEN_STRING || token != "c" {
+		t.Errorf("got %d %q", kind, token)
+	}
+}
+
+func TestDecimalToken(t *testing.T) {
+	cases := []struct {
+		src   string
+		kind  int
+		token string
+	}{
+		{"12.34d", TOKEN_DECIMAL, "12.34"},
+		{"1d", TOKEN_DECIMAL, "1"},
+		{"0xd", TOKEN_NUMBER, "0xd"},
+		{"0xFD", TOKEN_NUMBER, "0xFD"},
+		{"0xad", TOKEN_NUMBER, "0xad"},
+	}
+	for _, c := range cases {
+		_, kind, token := NewLexer(c.src, "test").NextToken()
+		if kind != c.kind || token != c.token {
+			t.Errorf("%s: got %d %q", c.src, kind, token)
+		}
+	}
+}
+
+func TestFencedString(t *testing.T) {
+	cases := map[string]string{
+		"```a`b```":              "a`b",
+		"```\n  x\n    y\n  ```": "x\n  y\n",
+		"````\n```\n````":        "```\n",
+		"```\n  x\n  y```":       "  x\n  y",
+		"``":                     "",
+	}
+	for src, want := range cases {
+		_, kind, token := NewLexer(src, "test").NextToken()
+		if kind != TOKEN_STRING || token != want {
+			t.Errorf("%q: got %q", src, token)
+		}
+	}
+}
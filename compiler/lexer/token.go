@@ -84,6 +84,10 @@ const (
 	TOKEN_OP_DEC
 	// ??=
 	TOKEN_OP_NILCOALESCING_EQ
+	// "a ${b}", the token is the source between quotes
+	TOKEN_STRING_INTERP
+	// 12.34d, the token is the number without `d`
+	TOKEN_DECIMAL
 )
 
 var tokenOpEq = map[int]int{
@@ -171,6 +175,8 @@ var tokenNames = map[int]string{
 	TOKEN_OP_INC:           "++",
 	TOKEN_OP_DEC:           "--",
 	TOKEN_OP_NILCOALESCING_EQ: "??=",
+	TOKEN_STRING_INTERP:       "interpolated string",
+	TOKEN_DECIMAL:             "decimal literal",
 }
 
 func tokenName(token int) string {
@@ -200,3 +206,35 @@ var keywords = map[string]int{
 	"while": TOKEN_KW_WHILE,
 	"class": TOKEN_KW_CLASS,
 }
+
+func TokenName(token int) string {
+	return tokenName(token)
+}
+
+func IsKeyword(s string) bool {
+	_, ok := keywords[s]
+	return ok
+}
+
+// IsIdentifier reports whether s can be used as a Name
+func IsIdentifier(s string) bool {
+	if s == "" || IsKeyword(s) || isDigit(s[0]) {
+		return false
+	}
+	for i := 0; i < len(s); i++ {
+		if s[i] != '_' && !isLetter(s[i]) && !isDigit(s[i]) {
+			return false
+		}
+	}
+	return true
+}
+
+// TokenKind is the reverse of TokenName
+func TokenKind(name string) (int, bool) {
+	for kind := range tokenNames {
+		if tokenNames[kind] == name {
+			return kind, true
+		}
+	}
+	return 0, false
+}
@@ -0,0 +1,28 @@
+package parser
+
+import (
+	"os"
+	"path/filepath"
+	"runtime"
+	"testing"
+)
+
+func FuzzParse(f *testing.F) {
+	files, _ := filepath.Glob("../../test/*.lk")
+	for _, file := range files {
+		if data, err := os.ReadFile(file); err == nil {
+			f.Add(string(data))
+		}
+	}
+	f.Add("a := b ? c : d ?? e\nfn f(...) => ...")
+	f.Fuzz(func(t *testing.T, src string) {
+		defer func() {
+			if r := recover(); r != nil {
+				if _, ok := r.(runtime.Error); ok {
+					t.Fatalf("parser crashed on %q: %v", src, r)
+				}
+			}
+		}()
+		Parse(src, "fuzz")
+	})
+}
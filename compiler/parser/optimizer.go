@@ -52,14 +52,27 @@ func optimizeBitwiseBinaryOp(exp *BinopExp) Exp {
 func optimizeArithBinaryOp(exp *BinopExp) Exp {
 	if x, ok := exp.Left.(*IntegerExp); ok {
 		if y, ok := exp.Right.(*IntegerExp); ok {
+			/* overflows are left to the vm, which promotes them to bigints */
 			switch exp.Op {
 			case TOKEN_OP_ADD:
-				return &IntegerExp{exp.Line, x.Int + y.Int}
+				if !utils.AddOverflows(x.Int, y.Int) {
+					return &IntegerExp{exp.Line, x.Int + y.Int}
+				}
+				return exp
 			case TOKEN_OP_SUB:
-				return &IntegerExp{exp.Line, x.Int - y.Int}
+				if !utils.SubOverflows(x.Int, y.Int) {
+					return &IntegerExp{exp.Line, x.Int - y.Int}
+				}
+				return exp
 			case TOKEN_OP_MUL:
-				return &IntegerExp{exp.Line, x.Int * y.Int}
+				if !utils.MulOverflows(x.Int, y.Int) {
+					return &IntegerExp{exp.Line, x.Int * y.Int}
+				}
+				return exp
 			case TOKEN_OP_IDIV:
+				if utils.IFloorDivOverflows(x.Int, y.Int) {
+					return exp
+				}
 				if y.Int != 0 {
 					return &IntegerExp{exp.Line, utils.IFloorDiv(x.Int, y.Int)}
 				}
@@ -125,8 +138,10 @@ func optimizeUnaryOp(exp *UnopExp) Exp {
 func optimizeUnm(exp *UnopExp) Exp {
 	switch x := exp.Unop.(type) { // utils?
 	case *IntegerExp:
-		x.Int = -x.Int
-		return x
+		if x.Int != math.MinInt64 {
+			x.Int = -x.Int
+			return x
+		}
 	case *FloatExp:
 		if x.Float != 0 {
 			x.Float = -x.Float
@@ -140,7 +155,7 @@ func optimizeNot(exp *UnopExp) Exp {
 	switch exp.Unop.(type) {
 	case *NilExp, *FalseExp: // false
 		return &TrueExp{exp.Line}
-	case *TrueExp, *IntegerExp, *FloatExp, *StringExp: // true
+	case *TrueExp, *IntegerExp, *FloatExp, *DecimalExp, *StringExp: // true
 		return &FalseExp{exp.Line}
 	default:
 		return exp
@@ -171,7 +186,7 @@ func isFalse(exp Exp) bool {
 
 func isTrue(exp Exp) bool {
 	switch exp.(type) {
-	case *TrueExp, *IntegerExp, *FloatExp, *StringExp:
+	case *TrueExp, *IntegerExp, *FloatExp, *DecimalExp, *StringExp:
 		return true
 	default:
 		return false
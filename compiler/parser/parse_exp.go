@@ -221,8 +221,13 @@ func parseExp0(lexer *Lexer) Exp {
 	case TOKEN_STRING: // LiteralString
 		line, _, token := lexer.NextToken()
 		return &StringExp{line, token}
+	case TOKEN_STRING_INTERP:
+		return parseInterpExp(lexer)
 	case TOKEN_NUMBER: // Numeral
 		return parseNumberExp(lexer)
+	case TOKEN_DECIMAL:
+		line, _, token := lexer.NextToken()
+		return &DecimalExp{line, token}
 	case TOKEN_SEP_LCURLY: // tableconstructor
 		return parseTableConstructorExp(lexer)
 	case TOKEN_KW_FUNCTION: // functiondef
@@ -233,6 +238,19 @@ func parseExp0(lexer *Lexer) Exp {
 	}
 }
 
+// "a ${b} c"
+func parseInterpExp(lexer *Lexer) Exp {
+	line, _, token := lexer.NextToken()
+	lits, srcs := lexer.SplitInterp(token)
+	exps := make([]Exp, 0, len(srcs))
+	for _, src := range srcs {
+		sub := lexer.Sub(src, line)
+		exps = append(exps, parseExp(sub))
+		sub.NextTokenOfKind(TOKEN_EOF)
+	}
+	return &InterpExp{line, lits, exps}
+}
+
 func parseNumberExp(lexer *Lexer) Exp {
 	line, _, token := lexer.NextToken()
 	if i, ok := utils.ParseInteger(token); ok {
@@ -68,7 +68,7 @@ func _finishPrefixExp(lexer *Lexer, exp Exp) Exp {
 				return exp
 			}
 			exp = &TableAccessExp{line, exp, &IntegerExp{line, val}}
-		case TOKEN_SEP_LPAREN, TOKEN_STRING, TOKEN_SEP_COLON: // prefixexp args
+		case TOKEN_SEP_LPAREN, TOKEN_STRING, TOKEN_STRING_INTERP, TOKEN_SEP_COLON: // prefixexp args
 			exp = _finishFuncCallExp(lexer, exp)
 		default:
 			return exp
@@ -105,6 +105,8 @@ func _parseArgs(lexer *Lexer) (args []Exp) {
 		lexer.NextTokenOfKind(TOKEN_SEP_RPAREN)
 	case TOKEN_SEP_LCURLY: // ‘{’ [fieldlist] ‘}’
 		args = []Exp{parseTableConstructorExp(lexer)}
+	case TOKEN_STRING_INTERP:
+		args = []Exp{parseInterpExp(lexer)}
 	default: // LiteralString
 		line, str := lexer.NextTokenOfKind(TOKEN_STRING)
 		args = []Exp{&StringExp{line, str}}
@@ -0,0 +1,431 @@
+package printer
+
+import (
+	"math"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/compiler/ast"
+	. "github.com/lollipopkit/lk/compiler/lexer"
+)
+
+const indentStr = "    "
+
+/* operator precedence, higher binds tighter */
+const (
+	precTernary = iota
+	precOr
+	precAnd
+	precCompare
+	precBor
+	precBxor
+	precBand
+	precShift
+	precAdd
+	precMul
+	precUnary
+	precPow
+	precAtom
+)
+
+type printer struct {
+	buf    strings.Builder
+	indent int
+}
+
+// Print turns a parsed block back into lk source.
+// Comments are not kept, and syntax sugar is printed in its desugared form.
+func Print(block *Block) string {
+	p := &printer{}
+	p.stats(block)
+	return p.buf.String()
+}
+
+// PrintNode prints a block, a statement or an expression.
+func PrintNode(node any) string {
+	if block, ok := node.(*Block); ok {
+		return Print(block)
+	}
+	p := &printer{}
+	p.stat(node)
+	return p.buf.String()
+}
+
+func (p *printer) write(s ...string) {
+	for i := range s {
+		p.buf.WriteString(s[i])
+	}
+}
+
+func (p *printer) newLine() {
+	p.buf.WriteByte('\n')
+	for i := 0; i < p.indent; i++ {
+		p.buf.WriteString(indentStr)
+	}
+}
+
+func (p *printer) stats(block *Block) {
+	for i := range block.Stats {
+		if i > 0 {
+			p.newLine()
+		}
+		p.stat(block.Stats[i])
+	}
+	if block.RetExps != nil {
+		if len(block.Stats) > 0 {
+			p.newLine()
+		}
+		p.write("rt")
+		if len(block.RetExps) > 0 {
+			p.write(" ")
+			p.exps(block.RetExps)
+		}
+	}
+}
+
+// `{` block `}`
+func (p *printer) body(block *Block) {
+	p.write("{")
+	if len(block.Stats) == 0 && block.RetExps == nil {
+		p.write("}")
+		return
+	}
+	p.indent++
+	p.newLine()
+	p.stats(block)
+	p.indent--
+	p.newLine()
+	p.write("}")
+}
+
+func (p *printer) stat(stat Stat) {
+	switch s := stat.(type) {
+	case *EmptyStat:
+		p.write(";")
+	case *BreakStat:
+		p.write("break")
+	case *FuncCallExp:
+		p.exp(s)
+	case *IfStat:
+		for i := range s.Exps {
+			if i == 0 {
+				p.write("if ")
+			} else if _, ok := s.Exps[i].(*TrueExp); ok && i == len(s.Exps)-1 {
+				p.write(" else ")
+				p.body(s.Blocks[i])
+				break
+			} else {
+				p.write(" elif ")
+			}
+			p.exp(s.Exps[i])
+			p.write(" ")
+			p.body(s.Blocks[i])
+		}
+	case *WhileStat:
+		p.write("while ")
+		p.exp(s.Exp)
+		p.write(" ")
+		p.body(s.Block)
+	case *ForNumStat:
+		p.write("for ", s.VarName, " = ")
+		p.exp(s.InitExp)
+		p.write(", ")
+		p.exp(s.LimitExp)
+		if step, ok := s.StepExp.(*IntegerExp); !ok || step.Int != 1 {
+			p.write(", ")
+			p.exp(s.StepExp)
+		}
+		p.write(" ")
+		p.body(s.Block)
+	case *ForInStat:
+		p.write("for ", strings.Join(s.NameList, ", "), " in ")
+		p.exps(unwrapIter(s.ExpList))
+		p.write(" ")
+		p.body(s.Block)
+	case *AssignStat:
+		p.exps(s.VarList)
+		p.write(" = ")
+		p.exps(s.ExpList)
+	case *LocalVarDeclStat:
+		p.write("shy ", strings.Join(s.NameList, ", "))
+		if len(s.ExpList) > 0 {
+			p.write(" = ")
+			p.exps(s.ExpList)
+		}
+	case *LocalFuncDefStat:
+		p.write("shy fn ", s.Name)
+		p.funcBody(s.Exp)
+	default:
+		p.exp(s)
+	}
+}
+
+// The parser wraps a single exp of `for in` with `iter()`,
+// print the original one so it won't be wrapped twice.
+func unwrapIter(exps []Exp) []Exp {
+	if len(exps) != 1 {
+		return exps
+	}
+	fc, ok := exps[0].(*FuncCallExp)
+	if !ok || fc.NameExp != nil || len(fc.Args) != 1 {
+		return exps
+	}
+	if name, ok := fc.PrefixExp.(*NameExp); ok && name.Name == "iter" {
+		return fc.Args
+	}
+	return exps
+}
+
+func (p *printer) exps(exps []Exp) {
+	for i := range exps {
+		if i > 0 {
+			p.write(", ")
+		}
+		p.exp(exps[i])
+	}
+}
+
+func (p *printer) exp(exp Exp) {
+	switch e := exp.(type) {
+	case *NilExp:
+		p.write("nil")
+	case *TrueExp:
+		p.write("true")
+	case *FalseExp:
+		p.write("false")
+	case *VarargExp:
+		p.write("...")
+	case *IntegerExp:
+		if e.Int == math.MinInt64 {
+			p.write("(-9223372036854775807 - 1)")
+		} else {
+			p.write(strconv.FormatInt(e.Int, 10))
+		}
+	case *FloatExp:
+		p.write(formatFloat(e.Float))
+	case *DecimalExp:
+		p.write(e.Dec + "d")
+	case *StringExp:
+		p.write(Quote(e.Str))
+	case *InterpExp:
+		p.write(`"`)
+		for i, lit := range e.Lits {
+			p.write(quoteInterp(lit))
+			if i < len(e.Exps) {
+				p.write("${", p.sub(e.Exps[i], 0, false), "}")
+			}
+		}
+		p.write(`"`)
+	case *UnopExp:
+		p.write(TokenName(e.Op))
+		if e.Op == TOKEN_OP_NOT {
+			p.write(" ")
+		}
+		s := p.sub(e.Unop, precUnary, false)
+		if strings.HasPrefix(s, "-") || strings.HasPrefix(s, "~") {
+			s = "(" + s + ")"
+		}
+		p.write(s)
+	case *BinopExp:
+		prec := binopPrec(e.Op)
+		rightAssoc := e.Op == TOKEN_OP_POW
+		p.write(p.sub(e.Left, prec, rightAssoc))
+		p.write(" ", TokenName(e.Op), " ")
+		p.write(p.sub(e.Right, prec, !rightAssoc))
+	case *TernaryExp:
+		p.write(p.sub(e.Cond, precOr, false), " ? ")
+		p.write(p.sub(e.True, precOr, false), " : ")
+		p.write(p.sub(e.False, precOr, false))
+	case *TableConstructorExp:
+		p.table(e)
+	case *FuncDefExp:
+		p.write("fn")
+		p.funcBody(e)
+	case *NameExp:
+		p.write(e.Name)
+	case *ParensExp:
+		p.write("(")
+		p.exp(e.Exp)
+		p.write(")")
+	case *TableAccessExp:
+		p.prefix(e.PrefixExp)
+		if key, ok := e.KeyExp.(*StringExp); ok && IsIdentifier(key.Str) {
+			p.write(".", key.Str)
+		} else {
+			p.write("[")
+			p.exp(e.KeyExp)
+			p.write("]")
+		}
+	case *FuncCallExp:
+		p.prefix(e.PrefixExp)
+		if e.NameExp != nil {
+			p.write(":", e.NameExp.Str)
+		}
+		p.write("(")
+		p.exps(e.Args)
+		p.write(")")
+	default:
+		panic("printer: unknown exp type")
+	}
+}
+
+// sub prints an operand, wrapping it with parens if it binds looser than its parent.
+func (p *printer) sub(exp Exp, parent int, strict bool) string {
+	sp := &printer{indent: p.indent}
+	sp.exp(exp)
+	s := sp.buf.String()
+	prec := expPrec(exp)
+	if prec < parent || (strict && prec == parent) {
+		return "(" + s + ")"
+	}
+	return s
+}
+
+// Only names, calls, accesses and parens can be called or indexed directly
+func (p *printer) prefix(exp Exp) {
+	switch exp.(type) {
+	case *NameExp, *ParensExp, *TableAccessExp, *FuncCallExp:
+		p.exp(exp)
+	default:
+		p.write("(")
+		p.exp(exp)
+		p.write(")")
+	}
+}
+
+func (p *printer) funcBody(fd *FuncDefExp) {
+	params := append([]string{}, fd.ParList...)
+	if fd.IsVararg {
+		params = append(params, "...")
+	}
+	p.write("(", strings.Join(params, ", "), ") ")
+	p.body(fd.Block)
+}
+
+func (p *printer) table(tb *TableConstructorExp) {
+	if len(tb.ValExps) == 0 {
+		p.write("{}")
+		return
+	}
+	p.write("{")
+	p.indent++
+	for i := range tb.ValExps {
+		p.newLine()
+		switch key := tb.KeyExps[i].(type) {
+		case nil:
+		case *StringExp:
+			p.write(Quote(key.Str), ": ")
+		default:
+			p.write("[")
+			p.exp(key)
+			p.write("]: ")
+		}
+		p.exp(tb.ValExps[i])
+		p.write(",")
+	}
+	p.indent--
+	p.newLine()
+	p.write("}")
+}
+
+func expPrec(exp Exp) int {
+	switch e := exp.(type) {
+	case *TernaryExp:
+		return precTernary
+	case *BinopExp:
+		return binopPrec(e.Op)
+	case *UnopExp:
+		return precUnary
+	case *IntegerExp:
+		if e.Int < 0 {
+			return precUnary
+		}
+	case *FloatExp:
+		if e.Float < 0 || math.Signbit(e.Float) {
+			return precUnary
+		}
+	}
+	return precAtom
+}
+
+func binopPrec(op int) int {
+	switch op {
+	case TOKEN_OP_OR:
+		return precOr
+	case TOKEN_OP_AND:
+		return precAnd
+	case TOKEN_OP_LT, TOKEN_OP_GT, TOKEN_OP_LE,
+		TOKEN_OP_GE, TOKEN_OP_NE, TOKEN_OP_EQ:
+		return precCompare
+	case TOKEN_OP_BOR:
+		return precBor
+	case TOKEN_OP_BXOR:
+		return precBxor
+	case TOKEN_OP_BAND:
+		return precBand
+	case TOKEN_OP_SHL, TOKEN_OP_SHR:
+		return precShift
+	case TOKEN_OP_ADD, TOKEN_OP_SUB:
+		return precAdd
+	case TOKEN_OP_MUL, TOKEN_OP_DIV, TOKEN_OP_IDIV, TOKEN_OP_MOD:
+		return precMul
+	case TOKEN_OP_POW:
+		return precPow
+	}
+	panic("printer: unknown binary operator")
+}
+
+func formatFloat(f float64) string {
+	switch {
+	case math.IsInf(f, 1):
+		return "(1 / 0)"
+	case math.IsInf(f, -1):
+		return "(-1 / 0)"
+	case math.IsNaN(f):
+		return "(0 / 0)"
+	}
+	s := strconv.FormatFloat(f, 'g', -1, 64)
+	if !strings.ContainsAny(s, ".e") {
+		s += ".0" // keep it a float
+	}
+	return s
+}
+
+// quoteInterp escapes a literal part of a "double quoted" interpolated string
+func quoteInterp(s string) string {
+	q := Quote(s)
+	q = strings.ReplaceAll(q[1:len(q)-1], `\'`, "'")
+	q = strings.ReplaceAll(q, `"`, `\"`)
+	return strings.ReplaceAll(q, "${", `\${`)
+}
+
+// Quote returns a single quoted lk string literal of s.
+func Quote(s string) string {
+	var sb strings.Builder
+	sb.WriteByte('\'')
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch c {
+		case '\'':
+			sb.WriteString(`\'`)
+		case '\\':
+			sb.WriteString(`\\`)
+		case '\n':
+			sb.WriteString(`\n`)
+		case '\r':
+			sb.WriteString(`\r`)
+		case '\t':
+			sb.WriteString(`\t`)
+		default:
+			if c < 0x20 || c == 0x7F {
+				sb.WriteString(`\x`)
+				sb.WriteString(strconv.FormatInt(int64(c>>4), 16))
+				sb.WriteString(strconv.FormatInt(int64(c&0xF), 16))
+			} else {
+				sb.WriteByte(c)
+			}
+		}
+	}
+	sb.WriteByte('\'')
+	return sb.String()
+}
@@ -0,0 +1,91 @@
+package compiler
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/lollipopkit/lk/binchunk"
+	"github.com/lollipopkit/lk/vm"
+)
+
+// compiled code of every test script passes the verifier
+func TestVerifyCompiled(t *testing.T) {
+	files, err := filepath.Glob("../test/*.lk")
+	if err != nil {
+		t.Fatal(err)
+	}
+	golden, _ := filepath.Glob("testdata/golden/*.lk")
+	for _, file := range append(files, golden...) {
+		src, err := os.ReadFile(file)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if err := vm.Verify(Compile(string(src), file)); err != nil {
+			t.Errorf("%s: %v", file, err)
+		}
+	}
+}
+
+func TestVerifyBroken(t *testing.T) {
+	const src = `shy a = 1
+shy f = fn(x) {
+    rt x + a
+}
+print(f(2))
+`
+	cases := map[string]struct {
+		want    string
+		corrupt func(p *binchunk.Prototype)
+	}{
+		"constant": {"constant", func(p *binchunk.Prototype) {
+			p.Constants = nil
+		}},
+		"register": {"register", func(p *binchunk.Prototype) {
+			p.MaxStackSize = 0
+		}},
+		"jump": {"jump to", func(p *binchunk.Prototype) {
+			p.Code = append([]uint32{uint32(vm.OP_JMP) | uint32(vm.MAXARG_sBx+100)<<14}, p.Code...)
+			p.LineInfo = append([]uint32{1}, p.LineInfo...)
+		}},
+		"proto": {"proto", func(p *binchunk.Prototype) {
+			p.Protos = nil
+		}},
+		"upvalue": {"upvalue", func(p *binchunk.Prototype) {
+			p.Protos[0].Upvalues[0].Idx = 200
+		}},
+		"opcode": {"invalid opcode", func(p *binchunk.Prototype) {
+			p.Code[0] = 0x3F
+		}},
+		"no return": {"RETURN", func(p *binchunk.Prototype) {
+			p.Code = p.Code[:len(p.Code)-1]
+			p.LineInfo = nil
+		}},
+	}
+	for name, c := range cases {
+		p := Compile(src, "broken.lk")
+		if err := vm.Verify(p); err != nil {
+			t.Fatalf("valid chunk rejected: %v", err)
+		}
+		c.corrupt(p)
+		err := vm.Verify(p)
+		if err == nil || !strings.Contains(err.Error(), c.want) {
+			t.Errorf("%s: want error with %q, got %v", name, c.want, err)
+		}
+	}
+}
+
+func TestVerifyErrorFields(t *testing.T) {
+	p := Compile("shy f = fn() { rt 'a' }\nprint(f())\n", "fields.lk")
+	p.Protos[0].Constants = nil
+	err := vm.Verify(p)
+	var ve *vm.VerifyError
+	if !errors.As(err, &ve) {
+		t.Fatalf("want *vm.VerifyError, got %T: %v", err, err)
+	}
+	if ve.Proto != "main.fn0" || ve.Kind != vm.VerifyConstant || ve.Op != "LOADK" || ve.PC < 0 {
+		t.Errorf("got %+v", ve)
+	}
+}
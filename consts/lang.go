@@ -4,3 +4,10 @@ const (
 	VERSION   = "0.3.0"
 	SIGNATURE = "LANG_LK"
 )
+
+const (
+	// Field of tables created by `secret()`, holds a closure returning the value
+	SecretField = "__secret"
+	// How secrets are rendered in print / str / json
+	Redacted = "<redacted>"
+)
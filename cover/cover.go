@@ -0,0 +1,150 @@
+package cover
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/lollipopkit/gommon/res"
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/binchunk"
+	"github.com/lollipopkit/lk/compiler"
+	"github.com/lollipopkit/lk/vm"
+)
+
+// Coverage counts how many times each line runs
+type Coverage struct {
+	// source -> line -> hits
+	hits map[string]map[int]int
+}
+
+// Start counts lines run by ls, it takes the debug hook of ls
+func Start(ls LkState) *Coverage {
+	c := &Coverage{hits: map[string]map[int]int{}}
+	ls.SetDebugHook(c.hook)
+	return c
+}
+
+func (c *Coverage) hook(_ LkState, info DebugInfo) {
+	lines := c.hits[info.Source]
+	if lines == nil {
+		lines = map[int]int{}
+		c.hits[info.Source] = lines
+	}
+	lines[info.Line]++
+}
+
+// FileCoverage is the result of one source file
+type FileCoverage struct {
+	Source string
+	// executable line -> hits
+	Lines map[int]int
+	// lines of the source, nil if it can't be read
+	Code []string
+}
+
+// Covered is the number of lines run at least once
+func (f *FileCoverage) Covered() int {
+	n := 0
+	for _, hits := range f.Lines {
+		if hits > 0 {
+			n++
+		}
+	}
+	return n
+}
+
+// Files returns coverage of every source that ran, sorted by path.
+// Lines that never ran are found by compiling the source again.
+func (c *Coverage) Files() []*FileCoverage {
+	files := []*FileCoverage{}
+	for source, hits := range c.hits {
+		f := &FileCoverage{Source: source, Lines: map[int]int{}}
+		if data, err := os.ReadFile(source); err == nil {
+			f.Code = strings.Split(string(data), "\n")
+			for line := range executableLines(string(data), source) {
+				f.Lines[line] = 0
+			}
+		}
+		for line, n := range hits {
+			f.Lines[line] = n
+		}
+		files = append(files, f)
+	}
+	sort.Slice(files, func(i, j int) bool {
+		return files[i].Source < files[j].Source
+	})
+	return files
+}
+
+// executableLines are lines with code, except the implicit return
+// at the end of each function.
+func executableLines(src, name string) (lines map[int]bool) {
+	lines = map[int]bool{}
+	defer func() {
+		/* a syntax error, only hits are reported */
+		recover()
+	}()
+	var walk func(p *binchunk.Prototype)
+	walk = func(p *binchunk.Prototype) {
+		last := len(p.Code) - 1
+		for pc, line := range p.LineInfo {
+			if pc == last && vm.Instruction(p.Code[pc]).Opcode() == vm.OP_RETURN {
+				continue
+			}
+			lines[int(line)] = true
+		}
+		for _, sub := range p.Protos {
+			walk(sub)
+		}
+	}
+	walk(compiler.Compile(src, name))
+	return lines
+}
+
+// WriteReport writes a summary and every line with its hits,
+// lines that never ran are red.
+func (c *Coverage) WriteReport(w io.Writer, color bool) {
+	for _, f := range c.Files() {
+		total := len(f.Lines)
+		percent := 100.0
+		if total > 0 {
+			percent = float64(f.Covered()) * 100 / float64(total)
+		}
+		fmt.Fprintf(w, "%s: %d/%d lines (%.1f%%)\n", f.Source, f.Covered(), total, percent)
+		for i, code := range f.Code {
+			hits, ok := f.Lines[i+1]
+			switch {
+			case !ok:
+				fmt.Fprintf(w, "%6s | %s\n", "", code)
+			case hits == 0 && color:
+				fmt.Fprintf(w, "%s%6d | %s%s\n", res.RED, 0, code, res.NOCOLOR)
+			default:
+				fmt.Fprintf(w, "%6d | %s\n", hits, code)
+			}
+		}
+	}
+}
+
+// WriteLcov writes the lcov tracefile format, read by genhtml and most CI
+func (c *Coverage) WriteLcov(w io.Writer) error {
+	for _, f := range c.Files() {
+		lines := make([]int, 0, len(f.Lines))
+		for line := range f.Lines {
+			lines = append(lines, line)
+		}
+		sort.Ints(lines)
+		var b strings.Builder
+		fmt.Fprintf(&b, "TN:\nSF:%s\n", f.Source)
+		for _, line := range lines {
+			fmt.Fprintf(&b, "DA:%d,%d\n", line, f.Lines[line])
+		}
+		fmt.Fprintf(&b, "LF:%d\nLH:%d\nend_of_record\n", len(lines), f.Covered())
+		if _, err := io.WriteString(w, b.String()); err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,58 @@
+package cover
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/lollipopkit/lk/state"
+)
+
+const script = `shy f = fn(n) {
+    if n > 100 {
+        rt 0
+    }
+    rt n * 2
+}
+for i = 1, 3 {
+    f(i)
+}
+`
+
+func TestCoverage(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "c.lk")
+	if err := os.WriteFile(path, []byte(script), 0644); err != nil {
+		t.Fatal(err)
+	}
+	ls := state.New()
+	ls.OpenLibs()
+	ls.LoadFile(path)
+	c := Start(ls)
+	ls.Call(0, 0)
+
+	files := c.Files()
+	if len(files) != 1 {
+		t.Fatalf("want 1 file, got %d", len(files))
+	}
+	lines := files[0].Lines
+	for line, want := range map[int]int{2: 3, 3: 0, 5: 3} {
+		if lines[line] != want {
+			t.Errorf("line %d: want %d hits, got %d", line, want, lines[line])
+		}
+	}
+	if _, ok := lines[9]; ok {
+		t.Errorf("line 9 is not executable")
+	}
+
+	var sb strings.Builder
+	if err := c.WriteLcov(&sb); err != nil {
+		t.Fatal(err)
+	}
+	out := sb.String()
+	for _, want := range []string{"SF:" + path, "DA:3,0", "DA:5,3", "end_of_record"} {
+		if !strings.Contains(out, want) {
+			t.Errorf("lcov has no %q:\n%s", want, out)
+		}
+	}
+}
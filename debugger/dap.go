@@ -0,0 +1,480 @@
+package debugger
+
+import (
+	"bufio"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+	"github.com/lollipopkit/lk/state"
+)
+
+// scripts run on one thread
+const dapThreadID = 1
+
+// a request, response or event of the Debug Adapter Protocol
+type dapMessage struct {
+	Seq     int    `json:"seq"`
+	Type    string `json:"type"`
+	Command string `json:"command,omitempty"`
+	Event   string `json:"event,omitempty"`
+	// response only
+	RequestSeq int    `json:"request_seq,omitempty"`
+	Success    *bool  `json:"success,omitempty"`
+	Message    string `json:"message,omitempty"`
+	Body       any    `json:"body,omitempty"`
+}
+
+type dapRequest struct {
+	Seq       int            `json:"seq"`
+	Command   string         `json:"command"`
+	Arguments map[string]any `json:"arguments"`
+}
+
+// dapServer speaks DAP over in / out, the script runs on its own goroutine
+// and waits in the hook while stopped.
+type dapServer struct {
+	// guards stepper, it's read by the hook
+	mu sync.Mutex
+	stepper
+	in  *bufio.Reader
+	out io.Writer
+	wmu sync.Mutex
+	seq int
+
+	program     string
+	stopOnEntry bool
+	// first stop is reported as `entry`
+	started bool
+	running bool
+	paused  bool
+	pausing bool
+	// fns run on the script goroutine while stopped, true resumes the script
+	cmds chan func(LkState) bool
+	// closed when the script ends
+	done chan struct{}
+}
+
+// ServeDAP serves the Debug Adapter Protocol on in / out until `disconnect`.
+// If out is os.Stdout, output of the script is sent as `output` events.
+func ServeDAP(in io.Reader, out io.Writer) error {
+	if out == io.Writer(os.Stdout) {
+		r, w, err := os.Pipe()
+		if err != nil {
+			return err
+		}
+		os.Stdout = w
+		defer func() {
+			w.Close()
+			os.Stdout = out.(*os.File)
+		}()
+		s := newDapServer(in, out)
+		go s.forwardOutput(r)
+		return s.serve()
+	}
+	return newDapServer(in, out).serve()
+}
+
+func newDapServer(in io.Reader, out io.Writer) *dapServer {
+	return &dapServer{
+		stepper: stepper{
+			breaks: map[string]map[int]bool{},
+			mode:   modeContinue,
+		},
+		in:   bufio.NewReader(in),
+		out:  out,
+		cmds: make(chan func(LkState) bool),
+		done: make(chan struct{}),
+	}
+}
+
+func (s *dapServer) serve() error {
+	for {
+		req, err := s.read()
+		if err == io.EOF {
+			s.stop()
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+		if s.handle(req) {
+			return nil
+		}
+	}
+}
+
+// handle answers req, returns true on `disconnect`
+func (s *dapServer) handle(req *dapRequest) bool {
+	args := req.Arguments
+	switch req.Command {
+	case "initialize":
+		s.respond(req, map[string]any{
+			"supportsConfigurationDoneRequest": true,
+		})
+		s.event("initialized", nil)
+	case "launch":
+		s.program, _ = args["program"].(string)
+		s.stopOnEntry, _ = args["stopOnEntry"].(bool)
+		if s.program == "" {
+			s.fail(req, "launch: `program` is required")
+			return false
+		}
+		s.respond(req, nil)
+	case "setBreakpoints":
+		s.setBreakpoints(req)
+	case "configurationDone":
+		s.respond(req, nil)
+		s.launch()
+	case "threads":
+		s.respond(req, map[string]any{
+			"threads": []any{map[string]any{"id": dapThreadID, "name": "main"}},
+		})
+	case "stackTrace":
+		s.whileStopped(req, s.stackTrace)
+	case "scopes":
+		frame := _intArg(args, "frameId")
+		s.respond(req, map[string]any{
+			"scopes": []any{map[string]any{
+				"name":               "Locals",
+				"variablesReference": frame + 1,
+				"expensive":          false,
+			}},
+		})
+	case "variables":
+		s.whileStopped(req, s.variables)
+	case "continue":
+		s.resume(req, modeContinue, map[string]any{"allThreadsContinued": true})
+	case "next":
+		s.resume(req, modeNext, nil)
+	case "stepIn":
+		s.resume(req, modeStep, nil)
+	case "stepOut":
+		s.resume(req, modeOut, nil)
+	case "pause":
+		s.mu.Lock()
+		s.mode = modeStep
+		s.pausing = true
+		s.mu.Unlock()
+		s.respond(req, nil)
+	case "disconnect", "terminate":
+		s.stop()
+		s.respond(req, nil)
+		return req.Command == "disconnect"
+	default:
+		s.fail(req, "unsupported command: "+req.Command)
+	}
+	return false
+}
+
+func (s *dapServer) setBreakpoints(req *dapRequest) {
+	source, _ := req.Arguments["source"].(map[string]any)
+	path, _ := source["path"].(string)
+	bps, _ := req.Arguments["breakpoints"].([]any)
+	result := []any{}
+	s.mu.Lock()
+	delete(s.breaks, filepath.Clean(path))
+	for _, bp := range bps {
+		bp, _ := bp.(map[string]any)
+		line := _intArg(bp, "line")
+		s.Break(path, line)
+		result = append(result, map[string]any{"verified": true, "line": line})
+	}
+	s.mu.Unlock()
+	s.respond(req, map[string]any{"breakpoints": result})
+}
+
+// launch runs the program on a new goroutine
+func (s *dapServer) launch() {
+	s.mu.Lock()
+	if s.stopOnEntry {
+		s.mode = modeStep
+	}
+	s.running = true
+	s.mu.Unlock()
+	go func() {
+		defer close(s.done)
+		err := s.run()
+		code := 0
+		if err != nil {
+			code = 1
+			s.event("output", map[string]any{"category": "stderr", "output": err.Error() + "\n"})
+		}
+		s.event("exited", map[string]any{"exitCode": code})
+		s.event("terminated", nil)
+	}()
+}
+
+func (s *dapServer) run() (err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("%v", r)
+		}
+	}()
+	ls := state.New()
+	defer ls.Close()
+	ls.OpenLibs()
+	if ls.LoadFile(s.program) != LK_OK {
+		return errors.New(ls.ToString(-1))
+	}
+	ls.SetDebugHook(s.hook)
+	if ls.PCall(0, 0, 0) != LK_OK {
+		s.mu.Lock()
+		quit := s.quit
+		s.mu.Unlock()
+		if quit {
+			return nil
+		}
+		return errors.New(ls.ToString2(-1))
+	}
+	return nil
+}
+
+// hook runs on the script goroutine
+func (s *dapServer) hook(ls LkState, info DebugInfo) {
+	s.mu.Lock()
+	quit := s.quit
+	stop, atBreak := s.shouldStop(info)
+	reason := "step"
+	switch {
+	case atBreak:
+		reason = "breakpoint"
+	case s.pausing:
+		reason = "pause"
+	case !s.started:
+		reason = "entry"
+	}
+	s.started = true
+	if stop {
+		s.pausing = false
+		s.paused = true
+	}
+	s.mu.Unlock()
+	if quit {
+		panic(quitting{})
+	}
+	if !stop {
+		return
+	}
+
+	s.event("stopped", map[string]any{
+		"reason":            reason,
+		"threadId":          dapThreadID,
+		"allThreadsStopped": true,
+	})
+	for cmd := range s.cmds {
+		if cmd(ls) {
+			break
+		}
+	}
+
+	s.mu.Lock()
+	quit = s.quit
+	s.mu.Unlock()
+	if quit {
+		panic(quitting{})
+	}
+}
+
+// whileStopped answers req on the script goroutine by f
+func (s *dapServer) whileStopped(req *dapRequest, f func(LkState, map[string]any) any) {
+	ok := s._send(func(ls LkState) bool {
+		s.respond(req, f(ls, req.Arguments))
+		return false
+	})
+	if !ok {
+		s.fail(req, "the script is running")
+	}
+}
+
+func (s *dapServer) resume(req *dapRequest, mode int, body any) {
+	s.respond(req, body)
+	s._send(func(ls LkState) bool {
+		s.mu.Lock()
+		s.mode = mode
+		if info, ok := ls.GetInfo(0); ok {
+			s.depth = info.Depth
+		}
+		s.mu.Unlock()
+		return true
+	})
+}
+
+// stop ends the script, waiting for it if it runs
+func (s *dapServer) stop() {
+	s.mu.Lock()
+	s.quit = true
+	running := s.running
+	s.mu.Unlock()
+	if !running {
+		return
+	}
+	/* wake the hook if it waits */
+	for {
+		select {
+		case s.cmds <- func(LkState) bool { return true }:
+		case <-s.done:
+			return
+		}
+	}
+}
+
+// _send runs f in the hook, false if the script isn't stopped
+func (s *dapServer) _send(f func(LkState) bool) bool {
+	s.mu.Lock()
+	paused := s.paused
+	s.mu.Unlock()
+	if !paused {
+		return false
+	}
+	finished := make(chan struct{})
+	s.cmds <- func(ls LkState) bool {
+		defer close(finished)
+		resume := f(ls)
+		if resume {
+			s.mu.Lock()
+			s.paused = false
+			s.mu.Unlock()
+		}
+		return resume
+	}
+	<-finished
+	return true
+}
+
+func (s *dapServer) stackTrace(ls LkState, _ map[string]any) any {
+	frames := []any{}
+	for level := 0; ; level++ {
+		info, ok := ls.GetInfo(level)
+		if !ok {
+			break
+		}
+		name := "main chunk"
+		if info.LineDefined > 0 {
+			name = fmt.Sprintf("fn at line %d", info.LineDefined)
+		}
+		frames = append(frames, map[string]any{
+			"id":     level,
+			"name":   name,
+			"line":   info.Line,
+			"column": 1,
+			"source": map[string]any{
+				"name": filepath.Base(info.Source),
+				"path": info.Source,
+			},
+		})
+	}
+	return map[string]any{"stackFrames": frames, "totalFrames": len(frames)}
+}
+
+func (s *dapServer) variables(ls LkState, args map[string]any) any {
+	level := _intArg(args, "variablesReference") - 1
+	vars := []any{}
+	for n := 1; ; n++ {
+		name := ls.GetLocal(level, n)
+		if name == "" {
+			break
+		}
+		if !strings.HasPrefix(name, "(") {
+			vars = append(vars, map[string]any{
+				"name":               name,
+				"value":              _repr(ls, -1),
+				"type":               ls.TypeName2(-1),
+				"variablesReference": 0,
+			})
+		}
+		ls.Pop(1)
+	}
+	return map[string]any{"variables": vars}
+}
+
+func (s *dapServer) forwardOutput(r io.Reader) {
+	buf := make([]byte, 4096)
+	for {
+		n, err := r.Read(buf)
+		if n > 0 {
+			s.event("output", map[string]any{"category": "stdout", "output": string(buf[:n])})
+		}
+		if err != nil {
+			return
+		}
+	}
+}
+
+// read reads a `Content-Length` framed request
+func (s *dapServer) read() (*dapRequest, error) {
+	length := -1
+	for {
+		line, err := s.in.ReadString('\n')
+		if err != nil {
+			return nil, err
+		}
+		line = strings.TrimSpace(line)
+		if line == "" {
+			break
+		}
+		if v, ok := strings.CutPrefix(line, "Content-Length:"); ok {
+			length, _ = strconv.Atoi(strings.TrimSpace(v))
+		}
+	}
+	if length < 0 {
+		return nil, errors.New("dap: missing Content-Length")
+	}
+	data := make([]byte, length)
+	if _, err := io.ReadFull(s.in, data); err != nil {
+		return nil, err
+	}
+	req := &dapRequest{}
+	if err := Json.Unmarshal(data, req); err != nil {
+		return nil, fmt.Errorf("dap: %v", err)
+	}
+	return req, nil
+}
+
+func (s *dapServer) respond(req *dapRequest, body any) {
+	success := true
+	s.write(&dapMessage{
+		Type:       "response",
+		RequestSeq: req.Seq,
+		Command:    req.Command,
+		Success:    &success,
+		Body:       body,
+	})
+}
+
+func (s *dapServer) fail(req *dapRequest, msg string) {
+	success := false
+	s.write(&dapMessage{
+		Type:       "response",
+		RequestSeq: req.Seq,
+		Command:    req.Command,
+		Success:    &success,
+		Message:    msg,
+	})
+}
+
+func (s *dapServer) event(name string, body any) {
+	s.write(&dapMessage{Type: "event", Event: name, Body: body})
+}
+
+func (s *dapServer) write(msg *dapMessage) {
+	s.wmu.Lock()
+	defer s.wmu.Unlock()
+	s.seq++
+	msg.Seq = s.seq
+	data, _ := Json.Marshal(msg)
+	fmt.Fprintf(s.out, "Content-Length: %d\r\n\r\n%s", len(data), data)
+}
+
+// _intArg reads a json number of args
+func _intArg(args map[string]any, key string) int {
+	f, _ := args[key].(float64)
+	return int(f)
+}
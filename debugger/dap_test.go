@@ -0,0 +1,123 @@
+package debugger
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"testing"
+
+	. "github.com/lollipopkit/lk/json"
+)
+
+type dapClient struct {
+	t   *testing.T
+	w   io.Writer
+	r   *bufio.Reader
+	seq int
+}
+
+func (c *dapClient) send(cmd string, args map[string]any) {
+	c.seq++
+	data, _ := Json.Marshal(map[string]any{
+		"seq": c.seq, "type": "request", "command": cmd, "arguments": args,
+	})
+	fmt.Fprintf(c.w, "Content-Length: %d\r\n\r\n%s", len(data), data)
+}
+
+// expect reads messages until the response or event named `name`
+func (c *dapClient) expect(name string) map[string]any {
+	for {
+		length := 0
+		for {
+			line, err := c.r.ReadString('\n')
+			if err != nil {
+				c.t.Fatalf("waiting for %s: %v", name, err)
+			}
+			line = strings.TrimSpace(line)
+			if line == "" {
+				break
+			}
+			length, _ = strconv.Atoi(strings.TrimPrefix(line, "Content-Length: "))
+		}
+		data := make([]byte, length)
+		io.ReadFull(c.r, data)
+		msg := map[string]any{}
+		Json.Unmarshal(data, &msg)
+		if msg["command"] == name || msg["event"] == name {
+			if msg["type"] == "response" && msg["success"] != true {
+				c.t.Fatalf("%s failed: %v", name, msg["message"])
+			}
+			body, _ := msg["body"].(map[string]any)
+			return body
+		}
+	}
+}
+
+func TestDAP(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "dbg.lk")
+	if err := os.WriteFile(path, []byte(script), 0644); err != nil {
+		t.Fatal(err)
+	}
+	inR, inW := io.Pipe()
+	outR, outW := io.Pipe()
+	errCh := make(chan error, 1)
+	go func() { errCh <- ServeDAP(inR, outW) }()
+	c := &dapClient{t: t, w: inW, r: bufio.NewReader(outR)}
+
+	c.send("initialize", map[string]any{"adapterID": "lk"})
+	c.expect("initialize")
+	c.expect("initialized")
+	c.send("launch", map[string]any{"program": path})
+	c.expect("launch")
+	c.send("setBreakpoints", map[string]any{
+		"source":      map[string]any{"path": path},
+		"breakpoints": []any{map[string]any{"line": 3}},
+	})
+	bps := c.expect("setBreakpoints")["breakpoints"].([]any)
+	if len(bps) != 1 {
+		t.Fatalf("breakpoints: %v", bps)
+	}
+	c.send("configurationDone", nil)
+	c.expect("configurationDone")
+
+	if reason := c.expect("stopped")["reason"]; reason != "breakpoint" {
+		t.Errorf("stopped for %v", reason)
+	}
+	c.send("stackTrace", map[string]any{"threadId": 1})
+	frames := c.expect("stackTrace")["stackFrames"].([]any)
+	if len(frames) != 2 || frames[0].(map[string]any)["line"] != float64(3) {
+		t.Errorf("stack frames: %v", frames)
+	}
+	c.send("variables", map[string]any{"variablesReference": 1})
+	vars := c.expect("variables")["variables"].([]any)
+	got := []string{}
+	for _, v := range vars {
+		v := v.(map[string]any)
+		got = append(got, fmt.Sprintf("%v=%v", v["name"], v["value"]))
+	}
+	if strings.Join(got, " ") != "a=1 b=2 c=3" {
+		t.Errorf("variables: %v", got)
+	}
+
+	c.send("next", map[string]any{"threadId": 1})
+	c.expect("next")
+	c.expect("stopped")
+	c.send("stackTrace", map[string]any{"threadId": 1})
+	frames = c.expect("stackTrace")["stackFrames"].([]any)
+	if len(frames) != 1 || frames[0].(map[string]any)["line"] != float64(7) {
+		t.Errorf("after next: %v", frames)
+	}
+
+	c.send("continue", map[string]any{"threadId": 1})
+	c.expect("continue")
+	c.expect("terminated")
+	c.send("disconnect", nil)
+	c.expect("disconnect")
+	if err := <-errCh; err != nil {
+		t.Error(err)
+	}
+}
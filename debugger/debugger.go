@@ -0,0 +1,313 @@
+package debugger
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/state"
+)
+
+// what to do before the next stop
+const (
+	modeStep     = iota // stop at the next line
+	modeNext            // stop at the next line of this or an outer function
+	modeOut             // stop once this function returns
+	modeContinue        // stop at breakpoints only
+)
+
+const helpMsg = `Commands:
+  s, step           run to the next line, entering calls
+  n, next           run to the next line of this function
+  o, out            run until this function returns
+  c, continue       run to the next breakpoint
+  b [file:]line     set a breakpoint, list them without args
+  d [file:]line     delete a breakpoint
+  l, locals         print locals of the current function
+  p name            print a local
+  bt                print the call stack
+  q, quit           stop the script`
+
+// stepper decides where the script stops, shared by the CLI and DAP
+type stepper struct {
+	// cleaned source path -> lines
+	breaks map[string]map[int]bool
+	mode   int
+	// depth of the frame `next` or `out` was asked in
+	depth int
+	quit  bool
+}
+
+// Debugger is a line debugger driven by commands read from `in`
+type Debugger struct {
+	stepper
+	in  *bufio.Scanner
+	out io.Writer
+	// source path -> lines, for printing
+	sources map[string][]string
+}
+
+// quitting unwinds the script when the user quits
+type quitting struct{}
+
+func New(in io.Reader, out io.Writer) *Debugger {
+	return &Debugger{
+		stepper: stepper{
+			breaks: map[string]map[int]bool{},
+			mode:   modeStep,
+		},
+		in:      bufio.NewScanner(in),
+		out:     out,
+		sources: map[string][]string{},
+	}
+}
+
+// Run debugs the script at path, it pauses before the first line.
+// Returns the error of the script, nil if it ends or the user quits.
+func Run(path string, in io.Reader, out io.Writer) (err error) {
+	d := New(in, out)
+	ls := state.New()
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("%v", r)
+		}
+	}()
+	defer ls.Close()
+	ls.OpenLibs()
+	if ls.LoadFile(path) != LK_OK {
+		return fmt.Errorf("%s", ls.ToString(-1))
+	}
+	ls.SetDebugHook(d.Hook)
+	if ls.PCall(0, 0, 0) != LK_OK {
+		if d.quit {
+			return nil
+		}
+		return fmt.Errorf("%s", ls.ToString2(-1))
+	}
+	fmt.Fprintln(out, "script finished")
+	return nil
+}
+
+// Break sets a breakpoint at file:line
+func (s *stepper) Break(file string, line int) {
+	file = filepath.Clean(file)
+	if s.breaks[file] == nil {
+		s.breaks[file] = map[int]bool{}
+	}
+	s.breaks[file][line] = true
+}
+
+// shouldStop tells whether to stop before the line of info,
+// atBreak is true if it's only because of a breakpoint.
+func (s *stepper) shouldStop(info DebugInfo) (stop, atBreak bool) {
+	switch s.mode {
+	case modeStep:
+		stop = true
+	case modeNext:
+		stop = info.Depth <= s.depth
+	case modeOut:
+		stop = info.Depth < s.depth
+	}
+	if !stop && s.hasBreak(info.Source, info.Line) {
+		return true, true
+	}
+	return stop, false
+}
+
+func (s *stepper) hasBreak(source string, line int) bool {
+	source = filepath.Clean(source)
+	for file, lines := range s.breaks {
+		if !lines[line] {
+			continue
+		}
+		if file == source || strings.HasSuffix(source, string(filepath.Separator)+file) {
+			return true
+		}
+	}
+	return false
+}
+
+// Hook is an api.DebugHook
+func (d *Debugger) Hook(ls LkState, info DebugInfo) {
+	if d.quit {
+		panic(quitting{})
+	}
+	stop, atBreak := d.shouldStop(info)
+	if !stop {
+		return
+	}
+	if atBreak {
+		fmt.Fprintf(d.out, "breakpoint at %s:%d\n", info.Source, info.Line)
+	}
+	d.printLine(info)
+	d.prompt(ls, info)
+	if d.quit {
+		panic(quitting{})
+	}
+}
+
+// prompt reads commands until one resumes the script
+func (d *Debugger) prompt(ls LkState, info DebugInfo) {
+	for {
+		fmt.Fprint(d.out, "(lk) ")
+		if !d.in.Scan() {
+			/* no more input: run to the end */
+			fmt.Fprintln(d.out)
+			d.mode = modeContinue
+			d.breaks = map[string]map[int]bool{}
+			return
+		}
+		fields := strings.Fields(d.in.Text())
+		if len(fields) == 0 {
+			continue
+		}
+		arg := ""
+		if len(fields) > 1 {
+			arg = fields[1]
+		}
+		switch fields[0] {
+		case "s", "step":
+			d.mode = modeStep
+			return
+		case "n", "next":
+			d.mode, d.depth = modeNext, info.Depth
+			return
+		case "o", "out":
+			d.mode, d.depth = modeOut, info.Depth
+			return
+		case "c", "continue":
+			d.mode = modeContinue
+			return
+		case "b", "break":
+			d.cmdBreak(arg, info, true)
+		case "d", "delete":
+			d.cmdBreak(arg, info, false)
+		case "l", "locals":
+			d.printLocals(ls, "")
+		case "p", "print":
+			d.printLocals(ls, arg)
+		case "bt", "backtrace":
+			d.printBacktrace(ls)
+		case "q", "quit":
+			d.quit = true
+			return
+		case "h", "help":
+			fmt.Fprintln(d.out, helpMsg)
+		default:
+			fmt.Fprintf(d.out, "unknown command '%s', try 'help'\n", fields[0])
+		}
+	}
+}
+
+func (d *Debugger) cmdBreak(arg string, info DebugInfo, set bool) {
+	if arg == "" {
+		if !set {
+			fmt.Fprintln(d.out, "usage: d [file:]line")
+			return
+		}
+		d.listBreaks()
+		return
+	}
+	file, lineStr := info.Source, arg
+	if i := strings.LastIndexByte(arg, ':'); i >= 0 {
+		file, lineStr = arg[:i], arg[i+1:]
+	}
+	line, err := strconv.Atoi(lineStr)
+	if err != nil || line < 1 {
+		fmt.Fprintf(d.out, "invalid line '%s'\n", lineStr)
+		return
+	}
+	if set {
+		d.Break(file, line)
+		fmt.Fprintf(d.out, "breakpoint set at %s:%d\n", file, line)
+		return
+	}
+	delete(d.breaks[filepath.Clean(file)], line)
+	fmt.Fprintf(d.out, "breakpoint deleted at %s:%d\n", file, line)
+}
+
+func (d *Debugger) listBreaks() {
+	list := []string{}
+	for file, lines := range d.breaks {
+		for line := range lines {
+			list = append(list, fmt.Sprintf("%s:%d", file, line))
+		}
+	}
+	if len(list) == 0 {
+		fmt.Fprintln(d.out, "no breakpoints")
+		return
+	}
+	sort.Strings(list)
+	fmt.Fprintln(d.out, strings.Join(list, "\n"))
+}
+
+// printLocals prints locals of the current function, only `name` if given
+func (d *Debugger) printLocals(ls LkState, name string) {
+	found := false
+	for n := 1; ; n++ {
+		local := ls.GetLocal(0, n)
+		if local == "" {
+			break
+		}
+		/* internal locals like `(for state)` */
+		if strings.HasPrefix(local, "(") || (name != "" && local != name) {
+			ls.Pop(1)
+			continue
+		}
+		found = true
+		fmt.Fprintf(d.out, "%s = %s\n", local, _repr(ls, -1))
+		ls.Pop(1)
+	}
+	if !found {
+		if name != "" {
+			fmt.Fprintf(d.out, "no local named '%s'\n", name)
+		} else {
+			fmt.Fprintln(d.out, "no locals")
+		}
+	}
+}
+
+func (d *Debugger) printBacktrace(ls LkState) {
+	for level := 0; ; level++ {
+		info, ok := ls.GetInfo(level)
+		if !ok {
+			return
+		}
+		fn := "main chunk"
+		if info.LineDefined > 0 {
+			fn = fmt.Sprintf("fn at line %d", info.LineDefined)
+		}
+		fmt.Fprintf(d.out, "#%d %s:%d in %s\n", level, info.Source, info.Line, fn)
+	}
+}
+
+func (d *Debugger) printLine(info DebugInfo) {
+	lines, ok := d.sources[info.Source]
+	if !ok {
+		if data, err := os.ReadFile(info.Source); err == nil {
+			lines = strings.Split(string(data), "\n")
+		}
+		d.sources[info.Source] = lines
+	}
+	code := ""
+	if info.Line > 0 && info.Line <= len(lines) {
+		code = strings.TrimSpace(lines[info.Line-1])
+	}
+	fmt.Fprintf(d.out, "> %s:%d  %s\n", info.Source, info.Line, code)
+}
+
+// _repr is like ToString2, but strs are quoted
+func _repr(ls LkState, idx int) string {
+	if ls.Type(idx) == LK_TSTRING {
+		return strconv.Quote(ls.ToString(idx))
+	}
+	s := ls.ToString2(idx)
+	ls.Pop(1)
+	return s
+}
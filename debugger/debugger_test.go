@@ -0,0 +1,69 @@
+package debugger
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+const script = `fn add(a, b) {
+    c := a + b
+    rt c
+}
+x := 1
+y := add(x, 2)
+z := y * 2
+`
+
+func runScript(t *testing.T, cmds string) string {
+	path := filepath.Join(t.TempDir(), "dbg.lk")
+	if err := os.WriteFile(path, []byte(script), 0644); err != nil {
+		t.Fatal(err)
+	}
+	var out strings.Builder
+	if err := Run(path, strings.NewReader(cmds), &out); err != nil {
+		t.Fatal(err)
+	}
+	return out.String()
+}
+
+func TestBreakpoint(t *testing.T) {
+	out := runScript(t, "b 3\nc\nl\np b\nbt\nc\n")
+	want := []string{
+		"breakpoint at ",
+		"dbg.lk:3  rt c",
+		"a = 1\nb = 2\nc = 3\n",
+		"b = 2\n",
+		"#0 ",
+		"dbg.lk:3 in fn at line 1",
+		"#1 ",
+		"dbg.lk:6 in main chunk",
+		"script finished",
+	}
+	for _, w := range want {
+		if !strings.Contains(out, w) {
+			t.Errorf("output missing %q:\n%s", w, out)
+		}
+	}
+}
+
+func TestNextSkipsCalls(t *testing.T) {
+	out := runScript(t, "b 6\nc\nn\nq\n")
+	if !strings.Contains(out, "dbg.lk:7  z := y * 2") {
+		t.Errorf("next should stop at line 7:\n%s", out)
+	}
+	if strings.Contains(out, "dbg.lk:2") || strings.Contains(out, "script finished") {
+		t.Errorf("next entered add or quit didn't stop:\n%s", out)
+	}
+}
+
+func TestStepIntoCall(t *testing.T) {
+	out := runScript(t, "b 6\nc\ns\no\nq\n")
+	if !strings.Contains(out, "dbg.lk:2  c := a + b") {
+		t.Errorf("step should enter add:\n%s", out)
+	}
+	if !strings.Contains(out, "dbg.lk:7  z := y * 2") {
+		t.Errorf("out should return to line 7:\n%s", out)
+	}
+}
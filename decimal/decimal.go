@@ -0,0 +1,240 @@
+package decimal
+
+import (
+	"errors"
+	"math/big"
+	"strings"
+)
+
+// Decimal is an exact base 10 number, coef * 10^-scale.
+// Values are immutable, every operation returns a new one.
+type Decimal struct {
+	coef  *big.Int
+	scale int32
+}
+
+// DivPrecision is digits kept after the point by Div
+const DivPrecision = 16
+
+// Rounding modes of Round
+const (
+	HalfEven = "half_even"
+	HalfUp   = "half_up"
+	HalfDown = "half_down"
+	Up       = "up"   // away from zero
+	Down     = "down" // toward zero
+	Ceil     = "ceil"
+	Floor    = "floor"
+)
+
+var (
+	big1  = big.NewInt(1)
+	big10 = big.NewInt(10)
+)
+
+var errSyntax = errors.New("invalid decimal")
+
+// Parse reads `[-]digits[.digits]`
+func Parse(s string) (*Decimal, error) {
+	neg := strings.HasPrefix(s, "-")
+	s = strings.TrimPrefix(strings.TrimPrefix(s, "-"), "+")
+	intPart, frac, _ := strings.Cut(s, ".")
+	digits := intPart + frac
+	if digits == "" {
+		return nil, errSyntax
+	}
+	for _, c := range digits {
+		if c < '0' || c > '9' {
+			return nil, errSyntax
+		}
+	}
+	coef, _ := new(big.Int).SetString(digits, 10)
+	if neg {
+		coef.Neg(coef)
+	}
+	return &Decimal{coef, int32(len(frac))}, nil
+}
+
+func FromInt(i int64) *Decimal {
+	return &Decimal{big.NewInt(i), 0}
+}
+
+func FromBig(n *big.Int) *Decimal {
+	return &Decimal{new(big.Int).Set(n), 0}
+}
+
+func (d *Decimal) String() string {
+	s := new(big.Int).Abs(d.coef).String()
+	if d.scale > 0 {
+		if pad := int(d.scale) - len(s) + 1; pad > 0 {
+			s = strings.Repeat("0", pad) + s
+		}
+		s = s[:len(s)-int(d.scale)] + "." + s[len(s)-int(d.scale):]
+	}
+	if d.coef.Sign() < 0 {
+		s = "-" + s
+	}
+	return s
+}
+
+// MarshalJSON writes d as a string, so no precision is lost by readers
+func (d *Decimal) MarshalJSON() ([]byte, error) {
+	return []byte(`"` + d.String() + `"`), nil
+}
+
+func (d *Decimal) Scale() int32 {
+	return d.scale
+}
+
+func (d *Decimal) Sign() int {
+	return d.coef.Sign()
+}
+
+func (d *Decimal) Rat() *big.Rat {
+	return new(big.Rat).SetFrac(d.coef, pow10(d.scale))
+}
+
+func (d *Decimal) Float64() float64 {
+	f, _ := d.Rat().Float64()
+	return f
+}
+
+// rescale returns coef of d with scale, which is >= d.scale
+func (d *Decimal) rescale(scale int32) *big.Int {
+	return new(big.Int).Mul(d.coef, pow10(scale-d.scale))
+}
+
+// align returns coefs of d and e with the same scale
+func align(d, e *Decimal) (x, y *big.Int, scale int32) {
+	scale = d.scale
+	if e.scale > scale {
+		scale = e.scale
+	}
+	return d.rescale(scale), e.rescale(scale), scale
+}
+
+func (d *Decimal) Add(e *Decimal) *Decimal {
+	x, y, scale := align(d, e)
+	return &Decimal{x.Add(x, y), scale}
+}
+
+func (d *Decimal) Sub(e *Decimal) *Decimal {
+	x, y, scale := align(d, e)
+	return &Decimal{x.Sub(x, y), scale}
+}
+
+func (d *Decimal) Mul(e *Decimal) *Decimal {
+	return &Decimal{new(big.Int).Mul(d.coef, e.coef), d.scale + e.scale}
+}
+
+func (d *Decimal) Neg() *Decimal {
+	return &Decimal{new(big.Int).Neg(d.coef), d.scale}
+}
+
+// Div keeps DivPrecision digits after the point, rounded half even,
+// then drops trailing zeros beyond the scale of d and e.
+// It panics if e is zero.
+func (d *Decimal) Div(e *Decimal) *Decimal {
+	if e.Sign() == 0 {
+		panic("decimal division by zero")
+	}
+	x, y, _ := align(d, e)
+	x.Mul(x, pow10(DivPrecision))
+	q, r := x.QuoRem(x, y, new(big.Int))
+	r.Abs(r).Mul(r, big.NewInt(2))
+	if c := r.CmpAbs(y); c > 0 || c == 0 && q.Bit(0) == 1 {
+		if d.Sign() != e.Sign() {
+			q.Sub(q, big1)
+		} else {
+			q.Add(q, big1)
+		}
+	}
+	keep := d.scale
+	if e.scale > keep {
+		keep = e.scale
+	}
+	return (&Decimal{q, DivPrecision}).trim(keep)
+}
+
+// FloorDiv is the largest integer <= d / e.
+// It panics if e is zero.
+func (d *Decimal) FloorDiv(e *Decimal) *Decimal {
+	if e.Sign() == 0 {
+		panic("decimal division by zero")
+	}
+	x, y, _ := align(d, e)
+	q, m := x.QuoRem(x, y, new(big.Int))
+	if m.Sign() != 0 && m.Sign() != y.Sign() {
+		q.Sub(q, big1)
+	}
+	return &Decimal{q, 0}
+}
+
+// Mod is d - floor(d / e) * e, its sign follows e
+func (d *Decimal) Mod(e *Decimal) *Decimal {
+	return d.Sub(d.FloorDiv(e).Mul(e))
+}
+
+func (d *Decimal) Cmp(e *Decimal) int {
+	x, y, _ := align(d, e)
+	return x.Cmp(y)
+}
+
+// Round keeps places digits after the point.
+// mode is one of HalfEven, HalfUp, HalfDown, Up, Down, Ceil and Floor.
+func (d *Decimal) Round(places int32, mode string) *Decimal {
+	if places < 0 {
+		places = 0
+	}
+	if d.scale <= places {
+		return d
+	}
+	div := pow10(d.scale - places)
+	q, r := new(big.Int).QuoRem(d.coef, div, new(big.Int))
+	/* |2r| vs div decides half modes */
+	half := new(big.Int).Abs(r)
+	half.Mul(half, big.NewInt(2))
+	cmpHalf := half.Cmp(div)
+	away := false
+	switch mode {
+	case Up:
+		away = r.Sign() != 0
+	case Down:
+	case Ceil:
+		away = r.Sign() > 0
+	case Floor:
+		away = r.Sign() < 0
+	case HalfUp:
+		away = cmpHalf >= 0
+	case HalfDown:
+		away = cmpHalf > 0
+	default:
+		away = cmpHalf > 0 || cmpHalf == 0 && q.Bit(0) == 1
+	}
+	if away {
+		if d.coef.Sign() < 0 {
+			q.Sub(q, big1)
+		} else {
+			q.Add(q, big1)
+		}
+	}
+	return &Decimal{q, places}
+}
+
+// trim drops trailing zeros after the point, keeping at least scale digits
+func (d *Decimal) trim(scale int32) *Decimal {
+	coef, s := new(big.Int).Set(d.coef), d.scale
+	m := new(big.Int)
+	for s > scale {
+		q, r := new(big.Int).QuoRem(coef, big10, m)
+		if r.Sign() != 0 {
+			break
+		}
+		coef, s = q, s-1
+	}
+	return &Decimal{coef, s}
+}
+
+func pow10(n int32) *big.Int {
+	return new(big.Int).Exp(big10, big.NewInt(int64(n)), nil)
+}
@@ -0,0 +1,74 @@
+package decimal
+
+import "testing"
+
+func dec(t *testing.T, s string) *Decimal {
+	t.Helper()
+	d, err := Parse(s)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return d
+}
+
+func TestParseString(t *testing.T) {
+	for s, want := range map[string]string{
+		"12.34": "12.34", "-0.05": "-0.05", ".5": "0.5", "7.": "7", "+3.10": "3.10",
+	} {
+		if got := dec(t, s).String(); got != want {
+			t.Errorf("%s: got %s, want %s", s, got, want)
+		}
+	}
+	for _, s := range []string{"", "-", "1.2.3", "1e5", "abc"} {
+		if _, err := Parse(s); err == nil {
+			t.Errorf("%q: want error", s)
+		}
+	}
+}
+
+func TestArith(t *testing.T) {
+	a, b := dec(t, "10.50"), dec(t, "-3")
+	cases := map[string]*Decimal{
+		"7.50":    a.Add(b),
+		"13.50":   a.Sub(b),
+		"-31.50":  a.Mul(b),
+		"-3.50":   a.Div(b),
+		"-4":      a.FloorDiv(b),
+		"-1.50":   a.Mod(b),
+		"0.6667":  dec(t, "2").Div(dec(t, "3")).Round(4, HalfEven),
+		"-10.50":  a.Neg(),
+		"0.33333": dec(t, "1").Div(dec(t, "3")).Round(5, Down),
+	}
+	for want, got := range cases {
+		if got.String() != want {
+			t.Errorf("got %s, want %s", got, want)
+		}
+	}
+	if a.Cmp(dec(t, "10.5")) != 0 || b.Cmp(a) >= 0 {
+		t.Error("cmp")
+	}
+}
+
+func TestRound(t *testing.T) {
+	cases := []struct {
+		in, mode, want string
+	}{
+		{"2.5", HalfEven, "2"},
+		{"3.5", HalfEven, "4"},
+		{"-2.5", HalfEven, "-2"},
+		{"2.5", HalfUp, "3"},
+		{"-2.5", HalfUp, "-3"},
+		{"2.5", HalfDown, "2"},
+		{"2.1", Up, "3"},
+		{"-2.1", Up, "-3"},
+		{"2.9", Down, "2"},
+		{"-2.1", Ceil, "-2"},
+		{"-2.1", Floor, "-3"},
+		{"2.1", Ceil, "3"},
+	}
+	for _, c := range cases {
+		if got := dec(t, c.in).Round(0, c.mode).String(); got != c.want {
+			t.Errorf("round(%s, %s): got %s, want %s", c.in, c.mode, got, c.want)
+		}
+	}
+}
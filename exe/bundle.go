@@ -0,0 +1,175 @@
+package exe
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/lollipopkit/lk/compiler"
+	"github.com/lollipopkit/lk/compiler/lexer"
+	"github.com/lollipopkit/lk/project"
+	"github.com/lollipopkit/lk/utils"
+)
+
+// Suffix of bundles run by the interpreter
+const BundleSuffix = ".lkb"
+
+// Bundle compiles `src` and the modules it imports, transitively.
+// Imports are found by `import 'name'` with a str literal, and searched
+// like `pkg.path` and the project manifest `m` (if not nil) do.
+// Imports not found on disk, eg: libs or remote modules,
+// are left to be resolved when running.
+func Bundle(src string, m *project.Manifest) (p *Payload, err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			err = fmt.Errorf("%v", r)
+		}
+	}()
+	p = &Payload{Modules: map[string]string{}, Chunks: map[string][]byte{}}
+	data, err := os.ReadFile(src)
+	if err != nil {
+		return nil, err
+	}
+	p.Chunk, err = compiler.Compile(string(data), src).Dump(utils.Md5(data))
+	if err != nil {
+		return nil, err
+	}
+
+	queue := importsOf(string(data), src)
+	for len(queue) > 0 {
+		name := queue[0]
+		queue = queue[1:]
+		if _, ok := p.Modules[name]; ok {
+			continue
+		}
+		path := findModule(name, m)
+		if path == "" {
+			continue
+		}
+		data, err := os.ReadFile(path)
+		if err != nil {
+			return nil, err
+		}
+		chunk := data
+		if strings.HasSuffix(path, ".lk") {
+			queue = append(queue, importsOf(string(data), path)...)
+			if chunk, err = compiler.Compile(string(data), path).Dump(utils.Md5(data)); err != nil {
+				return nil, err
+			}
+		}
+		hash := fmt.Sprintf("%x", sha256.Sum256(chunk))
+		p.Modules[name] = hash
+		p.Chunks[hash] = chunk
+	}
+	return p, nil
+}
+
+// BundleWith is Bundle with files under `embeds`
+func BundleWith(src string, embeds []string, m *project.Manifest) (*Payload, error) {
+	p, err := Bundle(src, m)
+	if err != nil {
+		return nil, err
+	}
+	if p.Files, err = collectFiles(embeds); err != nil {
+		return nil, err
+	}
+	return p, nil
+}
+
+// importsOf are names of `import 'name'` and `import('name')` in src
+func importsOf(src, chunkName string) []string {
+	names := []string{}
+	l := lexer.NewLexer(src, chunkName)
+	for {
+		_, kind, token := l.NextToken()
+		if kind == lexer.TOKEN_EOF {
+			return names
+		}
+		if kind != lexer.TOKEN_IDENTIFIER || token != "import" {
+			continue
+		}
+		if l.LookAhead() == lexer.TOKEN_SEP_LPAREN {
+			l.NextToken()
+		}
+		if l.LookAhead() == lexer.TOKEN_STRING {
+			_, _, name := l.NextToken()
+			names = append(names, name)
+		}
+	}
+}
+
+// findModule is the file of module `name`, "" if not found
+func findModule(name string, m *project.Manifest) string {
+	candidates := []string{}
+	if m != nil {
+		candidates = m.Candidates(name)
+	}
+	base := filepath.FromSlash(name)
+	candidates = append(candidates, base+".lk", base+".lkc", filepath.Join(base, "init.lk"))
+	for _, path := range candidates {
+		if info, err := os.Stat(path); err == nil && !info.IsDir() {
+			return path
+		}
+	}
+	return ""
+}
+
+// WriteBundle writes p to `out`, to be run by `lk out.lkb`
+func WriteBundle(p *Payload, out string) error {
+	data, err := EncodePayload(p, true)
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(out, data, 0644)
+}
+
+func ReadBundle(path string) (*Payload, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	p, err := DecodePayload(data)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %v", path, err)
+	}
+	return p, nil
+}
+
+// List describes modules and files in p, one per line:
+// `module name  size  sha256`, sorted by name
+func (p *Payload) List() []string {
+	lines := []string{}
+	names := make([]string, 0, len(p.Modules))
+	for name := range p.Modules {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		hash := p.Modules[name]
+		lines = append(lines, fmt.Sprintf("module %-24s %8d  %s", name, len(p.Chunks[hash]), hash[:16]))
+	}
+	names = names[:0]
+	for name := range p.Files {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		data := p.Files[name]
+		hash := fmt.Sprintf("%x", sha256.Sum256(data))
+		lines = append(lines, fmt.Sprintf("file   %-24s %8d  %s", name, len(data), hash[:16]))
+	}
+	return lines
+}
+
+// Module returns the compiled chunk of bundled module `name`
+func (p *Payload) Module(name string) ([]byte, bool) {
+	hash, ok := p.Modules[name]
+	if !ok {
+		return nil, false
+	}
+	chunk, ok := p.Chunks[hash]
+	return chunk, ok
+}
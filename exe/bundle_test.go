@@ -0,0 +1,49 @@
+package exe
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/lollipopkit/lk/project"
+)
+
+func TestBundle(t *testing.T) {
+	dir := t.TempDir()
+	files := map[string]string{
+		project.FileName: `{"paths": ["."], "deps": {"u": {"path": "lib"}}}`,
+		"main.lk":        "u := import('u')\nl := import 'lib'\nimport('os')\nrt u.f() + l.f()",
+		"lib/init.lk":    "h := import('helper')\nrt {'f': fn() => h}",
+		"helper.lk":      "rt 1",
+	}
+	for name, src := range files {
+		path := filepath.Join(dir, name)
+		os.MkdirAll(filepath.Dir(path), 0755)
+		if err := os.WriteFile(path, []byte(src), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	m, err := project.Find(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	p, err := Bundle(filepath.Join(dir, "main.lk"), m)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, name := range []string{"u", "lib", "helper"} {
+		if _, ok := p.Module(name); !ok {
+			t.Errorf("module %s is not bundled", name)
+		}
+	}
+	if _, ok := p.Module("os"); ok {
+		t.Error("libs should be left to the runtime")
+	}
+	/* `u` and `lib` are the same file */
+	if len(p.Chunks) != 2 {
+		t.Errorf("%d chunks, want 2", len(p.Chunks))
+	}
+	if lines := p.List(); len(lines) != 3 {
+		t.Errorf("list: %v", lines)
+	}
+}
@@ -0,0 +1,161 @@
+package exe
+
+import (
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"io"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"sync"
+
+	"github.com/lollipopkit/lk/project"
+)
+
+// Layout of a self-contained executable:
+//
+//	| interpreter | payload | payload length (uint64 LE) | magic |
+//
+// See EncodePayload for the payload itself.
+const (
+	magic      = "\x00LKEXE\x00\x01"
+	trailerLen = 8 + len(magic)
+)
+
+// Name of the embedded chunk, used as chunkName when loading
+const ChunkName = "@exe"
+
+type Payload struct {
+	// Compiled chunk of the entry script
+	Chunk []byte `json:"c"`
+	// Embedded files, keyed by slash separated path
+	Files map[string][]byte `json:"f"`
+	// Imported modules -> sha256 of their chunks
+	Modules map[string]string `json:"m,omitempty"`
+	// Compiled modules by sha256, shared by names of the same file
+	Chunks map[string][]byte `json:"k,omitempty"`
+}
+
+var (
+	current     *Payload
+	currentErr  error
+	currentOnce sync.Once
+)
+
+// Current is the cached result of Read
+func Current() (*Payload, error) {
+	currentOnce.Do(func() {
+		current, currentErr = Read()
+	})
+	return current, currentErr
+}
+
+// Use makes p the current payload, for bundles run by the interpreter
+func Use(p *Payload) {
+	currentOnce.Do(func() {})
+	current, currentErr = p, nil
+}
+
+// Build bundles `src`, and writes a copy of the running interpreter
+// with the bundle and files under `embeds` appended to `out`.
+func Build(src, out string, embeds []string, m *project.Manifest) error {
+	p, err := BundleWith(src, embeds, m)
+	if err != nil {
+		return err
+	}
+	payload, err := EncodePayload(p, true)
+	if err != nil {
+		return err
+	}
+
+	self, err := os.Executable()
+	if err != nil {
+		return err
+	}
+	bin, err := os.ReadFile(self)
+	if err != nil {
+		return err
+	}
+	// Building from a built exe: strip its payload first
+	if n, ok := payloadLen(bin); ok && n <= len(bin)-trailerLen {
+		bin = bin[:len(bin)-trailerLen-n]
+	}
+
+	buf := bytes.NewBuffer(bin)
+	buf.Write(payload)
+	binary.Write(buf, binary.LittleEndian, uint64(len(payload)))
+	buf.WriteString(magic)
+	return os.WriteFile(out, buf.Bytes(), 0755)
+}
+
+// Read returns the payload of the running executable,
+// or nil if it's a plain interpreter.
+func Read() (*Payload, error) {
+	self, err := os.Executable()
+	if err != nil {
+		return nil, err
+	}
+	f, err := os.Open(self)
+	if err != nil {
+		return nil, err
+	}
+	defer f.Close()
+
+	info, err := f.Stat()
+	if err != nil {
+		return nil, err
+	}
+	if info.Size() < int64(trailerLen) {
+		return nil, nil
+	}
+	trailer := make([]byte, trailerLen)
+	if _, err := f.ReadAt(trailer, info.Size()-int64(trailerLen)); err != nil {
+		return nil, err
+	}
+	n, ok := payloadLen(trailer)
+	if !ok {
+		return nil, nil
+	}
+	if n > int(info.Size())-trailerLen {
+		return nil, errors.New("corrupted payload length")
+	}
+
+	data := make([]byte, n)
+	off := info.Size() - int64(trailerLen) - int64(n)
+	if _, err := f.ReadAt(data, off); err != nil && err != io.EOF {
+		return nil, err
+	}
+	return DecodePayload(data)
+}
+
+// payloadLen reads the trailer at the end of `data`
+func payloadLen(data []byte) (int, bool) {
+	if len(data) < trailerLen || string(data[len(data)-len(magic):]) != magic {
+		return 0, false
+	}
+	n := binary.LittleEndian.Uint64(data[len(data)-trailerLen:])
+	return int(n), true
+}
+
+// collectFiles reads all files under `paths`, each path can be a file or dir
+func collectFiles(paths []string) (map[string][]byte, error) {
+	files := map[string][]byte{}
+	for _, root := range paths {
+		err := filepath.WalkDir(root, func(path string, d fs.DirEntry, err error) error {
+			if err != nil || d.IsDir() {
+				return err
+			}
+			data, err := os.ReadFile(path)
+			if err != nil {
+				return err
+			}
+			files[filepath.ToSlash(filepath.Clean(path))] = data
+			return nil
+		})
+		if err != nil {
+			return nil, err
+		}
+	}
+	return files, nil
+}
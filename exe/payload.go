@@ -0,0 +1,112 @@
+package exe
+
+import (
+	"bytes"
+	"compress/gzip"
+	"crypto/sha256"
+	"errors"
+	"fmt"
+	"io"
+
+	. "github.com/lollipopkit/lk/json"
+)
+
+// Layout of an encoded payload:
+//
+//	| "LKPK" | version | flags | sha256 of json | json, gzipped if flagGzip |
+//
+// Version 1 payloads are bare json without a header, still decoded.
+const (
+	payloadMagic   = "LKPK"
+	payloadVersion = 2
+	headerLen      = len(payloadMagic) + 2 + sha256.Size
+
+	flagGzip = 1 << 0
+)
+
+// EncodePayload encodes p with a checksum, compressed if `compress`
+func EncodePayload(p *Payload, compress bool) ([]byte, error) {
+	data, err := Json.Marshal(p)
+	if err != nil {
+		return nil, err
+	}
+	sum := sha256.Sum256(data)
+	var flags byte
+	if compress {
+		flags |= flagGzip
+	}
+
+	buf := bytes.NewBufferString(payloadMagic)
+	buf.WriteByte(payloadVersion)
+	buf.WriteByte(flags)
+	buf.Write(sum[:])
+	if !compress {
+		buf.Write(data)
+		return buf.Bytes(), nil
+	}
+	w, err := gzip.NewWriterLevel(buf, gzip.BestCompression)
+	if err != nil {
+		return nil, err
+	}
+	if _, err := w.Write(data); err != nil {
+		return nil, err
+	}
+	if err := w.Close(); err != nil {
+		return nil, err
+	}
+	return buf.Bytes(), nil
+}
+
+// DecodePayload decodes payloads written by EncodePayload, or old bare json ones.
+// A payload whose checksum doesn't match, with a module chunk
+// not matching its sha256, or a module without its chunk, is rejected.
+func DecodePayload(data []byte) (*Payload, error) {
+	if !bytes.HasPrefix(data, []byte(payloadMagic)) {
+		var p Payload
+		if err := Json.Unmarshal(data, &p); err != nil {
+			return nil, err
+		}
+		return p.validated()
+	}
+	if len(data) < headerLen {
+		return nil, errors.New("truncated payload header")
+	}
+	version, flags := data[len(payloadMagic)], data[len(payloadMagic)+1]
+	if version != payloadVersion {
+		return nil, fmt.Errorf("unsupported payload version %d", version)
+	}
+	sum := data[len(payloadMagic)+2 : headerLen]
+	body := data[headerLen:]
+	if flags&flagGzip != 0 {
+		r, err := gzip.NewReader(bytes.NewReader(body))
+		if err != nil {
+			return nil, err
+		}
+		if body, err = io.ReadAll(r); err != nil {
+			return nil, err
+		}
+	}
+	if got := sha256.Sum256(body); !bytes.Equal(got[:], sum) {
+		return nil, errors.New("payload checksum mismatch")
+	}
+	var p Payload
+	if err := Json.Unmarshal(body, &p); err != nil {
+		return nil, err
+	}
+	return p.validated()
+}
+
+// validated returns p if every module refers to a chunk by its sha256
+func (p *Payload) validated() (*Payload, error) {
+	for hash, chunk := range p.Chunks {
+		if fmt.Sprintf("%x", sha256.Sum256(chunk)) != hash {
+			return nil, fmt.Errorf("checksum mismatch of chunk %s", hash)
+		}
+	}
+	for name, hash := range p.Modules {
+		if _, ok := p.Chunks[hash]; !ok {
+			return nil, fmt.Errorf("module %s: no chunk %q", name, hash)
+		}
+	}
+	return p, nil
+}
@@ -0,0 +1,71 @@
+package exe
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"reflect"
+	"strings"
+	"testing"
+
+	. "github.com/lollipopkit/lk/json"
+)
+
+func TestPayloadEncoding(t *testing.T) {
+	chunk := []byte(strings.Repeat(`{"c":[1,2,3]}`, 100))
+	hash := fmt.Sprintf("%x", sha256.Sum256(chunk))
+	p := &Payload{
+		Chunk:   chunk,
+		Files:   map[string][]byte{"a.txt": []byte("a")},
+		Modules: map[string]string{"m": hash},
+		Chunks:  map[string][]byte{hash: chunk},
+	}
+	plain, err := EncodePayload(p, false)
+	if err != nil {
+		t.Fatal(err)
+	}
+	packed, err := EncodePayload(p, true)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(packed) >= len(plain) {
+		t.Errorf("compressed %d bytes, plain %d", len(packed), len(plain))
+	}
+	old, _ := Json.Marshal(p)
+	for name, data := range map[string][]byte{"plain": plain, "gzip": packed, "v1": old} {
+		got, err := DecodePayload(data)
+		if err != nil {
+			t.Errorf("%s: %v", name, err)
+		} else if !reflect.DeepEqual(got, p) {
+			t.Errorf("%s: got %+v", name, got)
+		}
+	}
+
+	broken := append([]byte{}, plain...)
+	broken[len(broken)-2] ^= 1
+	if _, err := DecodePayload(broken); err == nil || !strings.Contains(err.Error(), "checksum") {
+		t.Errorf("corrupted payload: %v", err)
+	}
+	if _, err := DecodePayload(packed[:headerLen-1]); err == nil {
+		t.Error("truncated payload should fail")
+	}
+}
+
+func TestPayloadModules(t *testing.T) {
+	chunk := []byte("chunk")
+	hash := fmt.Sprintf("%x", sha256.Sum256(chunk))
+	cases := map[string]*Payload{
+		"short hash":    {Modules: map[string]string{"m": "ab"}, Chunks: map[string][]byte{hash: chunk}},
+		"empty hash":    {Modules: map[string]string{"m": ""}},
+		"missing chunk": {Modules: map[string]string{"m": hash}},
+		"bad chunk key": {Modules: map[string]string{"m": "ab"}, Chunks: map[string][]byte{"ab": chunk}},
+	}
+	for name, p := range cases {
+		old, _ := Json.Marshal(p)
+		plain, _ := EncodePayload(p, false)
+		for _, data := range [][]byte{old, plain} {
+			if _, err := DecodePayload(data); err == nil {
+				t.Errorf("%s: should fail", name)
+			}
+		}
+	}
+}
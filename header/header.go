@@ -0,0 +1,142 @@
+package header
+
+import (
+	"fmt"
+	"os"
+	"strconv"
+	"strings"
+
+	"github.com/lollipopkit/lk/consts"
+	"github.com/lollipopkit/lk/mods"
+	"github.com/lollipopkit/lk/state"
+)
+
+// Header lines start with this prefix, and must be placed
+// at the top of the file (after an optional shebang).
+//
+//	#!/usr/bin/env lk
+//	//! requires: http, version >= 0.3
+const Prefix = "//!"
+
+type Requirement struct {
+	// Module name, or "version"
+	Name string
+	// Only for "version", eg: ">="
+	Op      string
+	Version string
+}
+
+// Parse reads `//! key: value` lines at the top of `src`.
+// Unknown keys are ignored, so headers can carry other metadata.
+func Parse(src string) ([]Requirement, error) {
+	var reqs []Requirement
+	for i, line := range strings.Split(src, "\n") {
+		line = strings.TrimSpace(line)
+		if i == 0 && strings.HasPrefix(line, "#!") {
+			continue
+		}
+		if !strings.HasPrefix(line, Prefix) {
+			break
+		}
+		key, val, ok := strings.Cut(strings.TrimPrefix(line, Prefix), ":")
+		if !ok || strings.TrimSpace(key) != "requires" {
+			continue
+		}
+		for _, item := range strings.Split(val, ",") {
+			item = strings.TrimSpace(item)
+			if item == "" {
+				continue
+			}
+			req, err := parseRequirement(item)
+			if err != nil {
+				return nil, fmt.Errorf("line %d: %v", i+1, err)
+			}
+			reqs = append(reqs, req)
+		}
+	}
+	return reqs, nil
+}
+
+func parseRequirement(item string) (Requirement, error) {
+	fields := strings.Fields(item)
+	switch {
+	case len(fields) == 1:
+		return Requirement{Name: fields[0]}, nil
+	case len(fields) == 3 && fields[0] == "version":
+		switch fields[1] {
+		case ">=", ">", "<=", "<", "==", "!=":
+			return Requirement{Name: "version", Op: fields[1], Version: fields[2]}, nil
+		}
+		return Requirement{}, fmt.Errorf("unknown operator '%s'", fields[1])
+	}
+	return Requirement{}, fmt.Errorf("invalid requirement '%s'", item)
+}
+
+// Check validates the header of `src`, it returns nil if all requirements are met.
+func Check(src string) error {
+	reqs, err := Parse(src)
+	if err != nil {
+		return err
+	}
+	for _, req := range reqs {
+		if req.Name == "version" {
+			if !compareVersion(consts.VERSION, req.Op, req.Version) {
+				return fmt.Errorf("requires version %s %s, but current is %s",
+					req.Op, req.Version, consts.VERSION)
+			}
+		} else if !hasModule(req.Name) {
+			return fmt.Errorf("requires module '%s', but it's not found", req.Name)
+		}
+	}
+	return nil
+}
+
+func hasModule(name string) bool {
+	if state.HasLib(name) {
+		return true
+	}
+	for _, path := range []string{name + ".lk", name + "/init.lk"} {
+		if _, err := mods.Files.Open(path); err == nil {
+			return true
+		}
+		if _, err := os.Stat(path); err == nil {
+			return true
+		}
+	}
+	return false
+}
+
+// compareVersion compares dot separated numbers, missing parts are 0
+func compareVersion(a, op, b string) bool {
+	as, bs := strings.Split(a, "."), strings.Split(b, ".")
+	cmp := 0
+	for i := 0; cmp == 0 && (i < len(as) || i < len(bs)); i++ {
+		var x, y int
+		if i < len(as) {
+			x, _ = strconv.Atoi(as[i])
+		}
+		if i < len(bs) {
+			y, _ = strconv.Atoi(bs[i])
+		}
+		if x < y {
+			cmp = -1
+		} else if x > y {
+			cmp = 1
+		}
+	}
+	switch op {
+	case ">=":
+		return cmp >= 0
+	case ">":
+		return cmp > 0
+	case "<=":
+		return cmp <= 0
+	case "<":
+		return cmp < 0
+	case "==":
+		return cmp == 0
+	case "!=":
+		return cmp != 0
+	}
+	return false
+}
@@ -1,43 +1,202 @@
 package main
 
 import (
+	"errors"
 	"flag"
+	"fmt"
+	"io/fs"
 	"io/ioutil"
 	"os"
+	"path/filepath"
+	"sort"
 	"strings"
+	"time"
 
 	"github.com/lollipopkit/gommon/log"
+	"github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/bench"
+	"github.com/lollipopkit/lk/binchunk"
+	"github.com/lollipopkit/lk/compiler"
+	"github.com/lollipopkit/lk/compiler/format"
 	"github.com/lollipopkit/lk/compiler/parser"
+	"github.com/lollipopkit/lk/cover"
+	"github.com/lollipopkit/lk/debugger"
+	"github.com/lollipopkit/lk/exe"
+	"github.com/lollipopkit/lk/header"
 	. "github.com/lollipopkit/lk/json"
+	"github.com/lollipopkit/lk/profile"
+	"github.com/lollipopkit/lk/project"
 	"github.com/lollipopkit/lk/repl"
 	"github.com/lollipopkit/lk/state"
+	"github.com/lollipopkit/lk/stdlib"
+	"github.com/lollipopkit/lk/vm"
 )
 
 var (
 	args = []string{}
+	// folded stacks are written here if set
+	profileOut string
+	// print line coverage after running
+	coverReport bool
+	// lcov tracefile is written here if set
+	lcovOut string
 )
 
 func main() {
+	// Self-contained executable: all args belong to the script
+	if p, err := exe.Current(); err != nil {
+		log.Red("[exe] read payload failed: " + err.Error())
+		os.Exit(1)
+	} else if p != nil {
+		runChunk(p.Chunk, exe.ChunkName)
+		return
+	}
+
 	ast := flag.Bool("a", false, "Write AST Tree Json")
 	compile := flag.Bool("c", false, "Compile file")
+	exePath := flag.String("exe", "", "Build a self-contained executable to this path")
+	exeEmbed := flag.String("embed", "", "Files or dirs to embed into the executable or bundle, separated by ','")
+	bundlePath := flag.String("bundle", "", "Write the file and modules it imports to this .lkb, run it by `lk out.lkb`")
+	bundleList := flag.Bool("list", false, "List modules and files in a .lkb bundle")
+	disasm := flag.Bool("disasm", false, "Print bytecode of a .lk, .lkc or .lkb file")
+	verify := flag.Bool("verify", false, "Check bytecode of a .lkc or .lkb file without running it")
+	benchmark := flag.Bool("b", false, "Run `bench_*` functions of file")
+	benchIters := flag.Int("n", 100, "Iterations of each benchmark")
+	benchBaseline := flag.String("baseline", "", "Compare benchmarks with this json")
+	benchSave := flag.String("save", "", "Save benchmark results to this json")
+	benchThreshold := flag.Float64("threshold", 10, "Allowed p50 regression (%) against baseline")
+	benchNoise := flag.Duration("noise", time.Microsecond, "p50 differences below it are ignored")
+	benchOps := flag.Bool("ops", false, "Benchmark each opcode family, no file needed")
+	fmtSrc := flag.Bool("fmt", false, "Format .lk files or dirs in place")
+	fmtCheck := flag.Bool("check", false, "With -fmt, list unformatted files and exit 1 instead of writing")
+	debug := flag.Bool("debug", false, "Debug file interactively, `help` lists commands")
+	dap := flag.Bool("dap", false, "Serve the Debug Adapter Protocol on stdio for editors")
+	flag.StringVar(&profileOut, "profile", "", "Sample the call stack while running, write folded stacks for flamegraphs to this path")
+	flag.BoolVar(&coverReport, "cover", false, "Print hit counts of each line after running")
+	flag.StringVar(&lcovOut, "lcov", "", "Write line coverage in lcov format to this path")
+	flag.BoolVar(&stdlib.TrackHandles, "leaks", false, "List sockets, kv stores and processes never closed when the script ends")
+	deps := flag.Bool("deps", false, "Fetch git deps of lk.json in the current dir or its parents")
+	deny := flag.String("deny", "", "Turn warnings into errors, only `deprecated` is supported")
 
 	flag.Parse()
 	args = flag.Args()
+	switch *deny {
+	case "":
+	case "deprecated":
+		stdlib.DenyDeprecated = true
+	default:
+		log.Red("[deny] unknown warning: " + *deny)
+		os.Exit(1)
+	}
+	if *benchIters < 1 {
+		log.Red("[bench] iterations must be positive")
+		os.Exit(1)
+	}
+	if *exePath != "" && *bundlePath != "" {
+		log.Red("[exe] -exe and -bundle can't be used together")
+		os.Exit(1)
+	}
+	if *benchOps {
+		bench.RunOpcodes(bench.Config{
+			Warmup:     *benchIters / 10,
+			Iterations: *benchIters,
+		})
+		return
+	}
+	if *deps {
+		if !fetchDeps() {
+			os.Exit(1)
+		}
+		return
+	}
+	if *fmtSrc {
+		if !formatFiles(args, *fmtCheck) {
+			os.Exit(1)
+		}
+		return
+	}
+	if *dap {
+		if err := debugger.ServeDAP(os.Stdin, os.Stdout); err != nil {
+			log.Red("[dap] %v", err)
+			os.Exit(1)
+		}
+		return
+	}
 	if len(args) == 0 {
 		repl.Repl()
 		return
 	}
 
 	fPath := args[0]
+	scriptArgs := args[1:]
+	if len(scriptArgs) > 0 && scriptArgs[0] == "--" {
+		scriptArgs = scriptArgs[1:]
+	}
+	stdlib.Args = append([]string{os.Args[0], fPath}, scriptArgs...)
 	if *ast {
 		writeAst(fPath)
 	} else if *compile {
 		state.Compile(fPath)
+	} else if *exePath != "" || *bundlePath != "" {
+		var embeds []string
+		if *exeEmbed != "" {
+			embeds = strings.Split(*exeEmbed, ",")
+		}
+		m, err := project.Find(filepath.Dir(fPath))
+		if err == nil && *exePath != "" {
+			err = exe.Build(fPath, *exePath, embeds, m)
+		} else if err == nil {
+			var p *exe.Payload
+			if p, err = exe.BundleWith(fPath, embeds, m); err == nil {
+				err = exe.WriteBundle(p, *bundlePath)
+			}
+		}
+		if err != nil {
+			log.Red("[exe] build failed: " + err.Error())
+			os.Exit(1)
+		}
+	} else if *bundleList {
+		p, err := exe.ReadBundle(fPath)
+		if err != nil {
+			log.Red("[bundle] %v", err)
+			os.Exit(1)
+		}
+		for _, line := range p.List() {
+			fmt.Println(line)
+		}
+	} else if *disasm {
+		if err := disasmFile(fPath); err != nil {
+			log.Red("[disasm] %v", err)
+			os.Exit(1)
+		}
+	} else if *verify {
+		if !verifyFile(fPath) {
+			os.Exit(1)
+		}
+	} else if *debug {
+		if err := debugger.Run(fPath, os.Stdin, os.Stdout); err != nil {
+			log.Red("[debug] %v", err)
+			os.Exit(1)
+		}
+	} else if *benchmark {
+		ok := bench.Run(fPath, bench.Config{
+			Warmup:     *benchIters / 10,
+			Iterations: *benchIters,
+			Baseline:   *benchBaseline,
+			Save:       *benchSave,
+			Threshold:  *benchThreshold,
+			Noise:      benchNoise.Nanoseconds(),
+		})
+		if !ok {
+			os.Exit(1)
+		}
 	} else {
 		if strings.HasSuffix(fPath, ".lk") || strings.HasSuffix(fPath, ".lkc") {
 			runVM(fPath)
+		} else if strings.HasSuffix(fPath, exe.BundleSuffix) {
+			runBundle(fPath)
 		} else {
-			log.Yellow("Can't run file without suffix '.lk(c)':\n" + fPath)
+			log.Yellow("Can't run file without suffix '.lk(c)' or '.lkb':\n" + fPath)
 		}
 	}
 }
@@ -70,9 +229,259 @@ func runVM(path string) {
 		log.Red("[run] can't read file: " + err.Error())
 		os.Exit(1)
 	}
+	if strings.HasSuffix(path, ".lk") {
+		if err := header.Check(string(data)); err != nil {
+			log.Red("[run] %s: %v", path, err)
+			os.Exit(1)
+		}
+	}
+	m, err := project.Find(filepath.Dir(path))
+	if err != nil {
+		log.Red("[run] %v", err)
+		os.Exit(1)
+	}
+	stdlib.Project = m
+	runChunk(data, path)
+}
+
+func runBundle(path string) {
+	p, err := exe.ReadBundle(path)
+	if err != nil {
+		log.Red("[run] %v", err)
+		os.Exit(1)
+	}
+	exe.Use(p)
+	runChunk(p.Chunk, path)
+}
+
+// loadProtos loads prototypes of a script, a compiled chunk or a bundle,
+// modules of a bundle follow its main chunk.
+// Chunks are not verified, it's up to the caller.
+func loadProtos(path string) (names []string, protos []*binchunk.Prototype, err error) {
+	if strings.HasSuffix(path, exe.BundleSuffix) {
+		p, err := exe.ReadBundle(path)
+		if err != nil {
+			return nil, nil, err
+		}
+		proto, err := binchunk.Load(p.Chunk)
+		if err != nil {
+			return nil, nil, err
+		}
+		names, protos = []string{path}, []*binchunk.Prototype{proto}
+		modules := make([]string, 0, len(p.Modules))
+		for name := range p.Modules {
+			modules = append(modules, name)
+		}
+		sort.Strings(modules)
+		for _, name := range modules {
+			chunk, _ := p.Module(name)
+			proto, err := binchunk.Load(chunk)
+			if err != nil {
+				return nil, nil, fmt.Errorf("module %s: %v", name, err)
+			}
+			names = append(names, fmt.Sprintf("module %s (%s)", name, p.Modules[name][:16]))
+			protos = append(protos, proto)
+		}
+		return names, protos, nil
+	}
+
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, nil, err
+	}
+	var proto *binchunk.Prototype
+	if strings.HasSuffix(path, ".lk") {
+		/* the compiler panics on syntax errors */
+		defer func() {
+			if r := recover(); r != nil {
+				err = fmt.Errorf("%v", r)
+			}
+		}()
+		proto = compiler.Compile(string(data), path)
+	} else if proto, err = binchunk.Load(data); err != nil {
+		return nil, nil, err
+	}
+	return []string{path}, []*binchunk.Prototype{proto}, nil
+}
+
+// disasmFile prints bytecode of a script, a compiled chunk or
+// a bundle with its modules
+func disasmFile(path string) error {
+	names, protos, err := loadProtos(path)
+	if err != nil {
+		return err
+	}
+	for i, proto := range protos {
+		if i > 0 {
+			fmt.Printf("\n== %s\n", names[i])
+		}
+		vm.Disasm(os.Stdout, proto)
+	}
+	return nil
+}
+
+// verifyFile checks chunks in path like they are checked on load,
+// it prints every rejected chunk and returns false if there is any
+func verifyFile(path string) bool {
+	names, protos, err := loadProtos(path)
+	if err != nil {
+		log.Red("[verify] %v", err)
+		return false
+	}
+	ok := true
+	for i, proto := range protos {
+		err := vm.Verify(proto)
+		if err == nil {
+			log.Green("[verify] ok: " + names[i])
+			continue
+		}
+		ok = false
+		if e, is := err.(*vm.VerifyError); is {
+			log.Red("[verify] %s: %s error in %s at pc %d: %s", names[i], e.Kind, e.Proto, e.PC, e.Msg)
+		} else {
+			log.Red("[verify] %s: %v", names[i], err)
+		}
+	}
+	return ok
+}
+
+func fetchDeps() bool {
+	m, err := project.Find(".")
+	if err == nil && m == nil {
+		err = errors.New("no " + project.FileName + " found")
+	}
+	if err != nil {
+		log.Red("[deps] %v", err)
+		return false
+	}
+	fetched, err := m.Fetch()
+	for _, name := range fetched {
+		log.Green("[deps] fetched " + name)
+	}
+	if err != nil {
+		log.Red("[deps] %v", err)
+		return false
+	}
+	return true
+}
+
+// runChunk runs a script, an uncaught error exits with 1
+// or the code returned by the fn of os.on_error.
+func runChunk(data []byte, name string) {
 	ls := state.New()
-	defer ls.CatchAndPrint(false)
+	defer func() {
+		if err := recover(); err != nil {
+			if code, ok := stdlib.OnError(ls, err); ok {
+				os.Exit(code)
+			}
+			ls.PrintError(err, false)
+			os.Exit(1)
+		}
+	}()
+	/* runs before the handler above, which may exit */
+	defer ls.Close()
+	if profileOut != "" {
+		p := profile.Start(ls, profile.DefaultInterval)
+		defer writeProfile(p)
+	}
+	if stdlib.TrackHandles {
+		defer reportLeaks()
+	}
+	if coverReport || lcovOut != "" {
+		c := cover.Start(ls)
+		defer writeCoverage(c)
+	}
 	ls.OpenLibs()
-	ls.Load(data, path, "bt")
+	if ls.Load(data, name, "bt") != api.LK_OK {
+		panic(ls.ToString(-1))
+	}
 	ls.Call(0, -1)
 }
+
+func writeProfile(p *profile.Profile) {
+	p.Stop()
+	f, err := os.Create(profileOut)
+	if err != nil {
+		log.Red("[profile] %v", err)
+		return
+	}
+	defer f.Close()
+	if err := p.WriteFolded(f); err != nil {
+		log.Red("[profile] %v", err)
+	}
+}
+
+func reportLeaks() {
+	leaks := stdlib.Leaks()
+	if len(leaks) == 0 {
+		return
+	}
+	log.Yellow("[leaks] %d handle(s) not closed:", len(leaks))
+	for _, leak := range leaks {
+		log.Yellow(leak)
+	}
+}
+
+func writeCoverage(c *cover.Coverage) {
+	if coverReport {
+		stat, err := os.Stdout.Stat()
+		color := err == nil && stat.Mode()&os.ModeCharDevice != 0
+		c.WriteReport(os.Stdout, color)
+	}
+	if lcovOut == "" {
+		return
+	}
+	f, err := os.Create(lcovOut)
+	if err != nil {
+		log.Red("[cover] %v", err)
+		return
+	}
+	defer f.Close()
+	if err := c.WriteLcov(f); err != nil {
+		log.Red("[cover] %v", err)
+	}
+}
+
+// formatFiles formats .lk files in paths, dirs are walked.
+// In check mode, files are not written and unformatted ones are listed.
+// Returns false on errors, or on unformatted files when checking.
+func formatFiles(paths []string, check bool) bool {
+	if len(paths) == 0 {
+		paths = []string{"."}
+	}
+	ok := true
+	for _, root := range paths {
+		err := filepath.WalkDir(root, func(path string, d fs.DirEntry, err error) error {
+			if err != nil {
+				return err
+			}
+			if d.IsDir() || !strings.HasSuffix(path, ".lk") {
+				return nil
+			}
+			src, err := os.ReadFile(path)
+			if err != nil {
+				return err
+			}
+			out, err := format.Format(string(src), path)
+			if err != nil {
+				log.Red("[fmt] %v", err)
+				ok = false
+				return nil
+			}
+			if out == string(src) {
+				return nil
+			}
+			if check {
+				log.Yellow(path)
+				ok = false
+				return nil
+			}
+			return os.WriteFile(path, []byte(out), 0644)
+		})
+		if err != nil {
+			log.Red("[fmt] %v", err)
+			ok = false
+		}
+	}
+	return ok
+}
@@ -0,0 +1,78 @@
+package profile
+
+import (
+	"fmt"
+	"io"
+	"path/filepath"
+	"sort"
+	"strings"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// DefaultInterval between two samples
+const DefaultInterval = time.Millisecond
+
+// Profile counts samples of lk call stacks
+type Profile struct {
+	// folded stack -> samples
+	stacks map[string]int
+	stop   func()
+}
+
+// Start samples the stack of ls every interval until Stop
+func Start(ls LkState, interval time.Duration) *Profile {
+	p := &Profile{stacks: map[string]int{}}
+	p.stop = ls.SetProfiler(interval, p.sample)
+	return p
+}
+
+func (p *Profile) Stop() {
+	if p.stop != nil {
+		p.stop()
+		p.stop = nil
+	}
+}
+
+func (p *Profile) sample(ls LkState, weight int) {
+	frames := []string{}
+	for level := 0; ; level++ {
+		info, ok := ls.GetInfo(level)
+		if !ok {
+			break
+		}
+		frames = append(frames, FrameName(info))
+	}
+	/* root first */
+	for i, j := 0, len(frames)-1; i < j; i, j = i+1, j-1 {
+		frames[i], frames[j] = frames[j], frames[i]
+	}
+	p.stacks[strings.Join(frames, ";")] += weight
+}
+
+// FrameName is `file.lk:main` for a main chunk, `file.lk:fn@12:15`
+// for the function defined at line 12 running line 15.
+func FrameName(info DebugInfo) string {
+	name := filepath.Base(info.Source)
+	if info.LineDefined == 0 {
+		return fmt.Sprintf("%s:main:%d", name, info.Line)
+	}
+	return fmt.Sprintf("%s:fn@%d:%d", name, info.LineDefined, info.Line)
+}
+
+// WriteFolded writes `frame;frame count` lines, the format of
+// flamegraph.pl and most flamegraph tools.
+func (p *Profile) WriteFolded(w io.Writer) error {
+	stacks := make([]string, 0, len(p.stacks))
+	for s := range p.stacks {
+		stacks = append(stacks, s)
+	}
+	sort.Strings(stacks)
+	for _, s := range stacks {
+		if _, err := fmt.Fprintf(w, "%s %d\n", s, p.stacks[s]); err != nil {
+			return err
+		}
+	}
+	return nil
+}
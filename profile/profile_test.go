@@ -0,0 +1,44 @@
+package profile
+
+import (
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/lollipopkit/lk/state"
+)
+
+const busy = `shy spin = fn(n) {
+    s := 0
+    for i = 1, n {
+        s = s + i % 7
+    }
+    rt s
+}
+for i = 1, 20 {
+    spin(20000)
+}
+`
+
+func TestFolded(t *testing.T) {
+	ls := state.New()
+	ls.OpenLibs()
+	ls.LoadString(busy, "busy.lk")
+	p := Start(ls, 100*time.Microsecond)
+	ls.Call(0, 0)
+	p.Stop()
+
+	var sb strings.Builder
+	if err := p.WriteFolded(&sb); err != nil {
+		t.Fatal(err)
+	}
+	out := sb.String()
+	if !strings.Contains(out, "busy.lk:main:9;busy.lk:fn@1:") {
+		t.Errorf("no samples in spin:\n%s", out)
+	}
+	for _, line := range strings.Split(strings.TrimSpace(out), "\n") {
+		if i := strings.LastIndexByte(line, ' '); i < 0 || !strings.HasPrefix(line, "busy.lk:main:") {
+			t.Errorf("bad folded line %q", line)
+		}
+	}
+}
@@ -0,0 +1,171 @@
+package project
+
+import (
+	"crypto/sha256"
+	"errors"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/json"
+)
+
+// FileName of the manifest, looked up from the script dir to its parents
+const FileName = "lk.json"
+
+// Manifest declares where imports of a project are searched:
+//
+//	{
+//	  "name": "app",
+//	  "paths": ["src"],
+//	  "deps": {
+//	    "utils": {"path": "../utils"},
+//	    "semver": {"git": "https://github.com/a/semver", "ref": "v1.0.0"}
+//	  }
+//	}
+//
+// `import 'utils/str'` loads `../utils/str.lk`, `import 'utils'` loads
+// `../utils/init.lk`. Other imports are also searched under `paths`.
+type Manifest struct {
+	Name string `json:"name"`
+	// Dirs searched for imports, relative to the manifest
+	Paths []string       `json:"paths"`
+	Deps  map[string]Dep `json:"deps"`
+	// Dir of the manifest
+	Dir string `json:"-"`
+}
+
+// Dep is a local dir, or a git repo fetched by `lk -deps`
+type Dep struct {
+	Path string `json:"path"`
+	Git  string `json:"git"`
+	// Branch or tag, the default branch if empty
+	Ref string `json:"ref"`
+}
+
+var depNameRe = regexp.MustCompile(`^[a-zA-Z_][a-zA-Z0-9_-]*$`)
+
+// Find loads the manifest in dir or its nearest parent.
+// It returns nil without error if there is none.
+func Find(dir string) (*Manifest, error) {
+	dir, err := filepath.Abs(dir)
+	if err != nil {
+		return nil, err
+	}
+	for {
+		path := filepath.Join(dir, FileName)
+		if _, err := os.Stat(path); err == nil {
+			return Load(path)
+		}
+		parent := filepath.Dir(dir)
+		if parent == dir {
+			return nil, nil
+		}
+		dir = parent
+	}
+}
+
+func Load(path string) (*Manifest, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	m := &Manifest{}
+	if err := Json.Unmarshal(data, m); err != nil {
+		return nil, fmt.Errorf("%s: %v", path, err)
+	}
+	if m.Dir, err = filepath.Abs(filepath.Dir(path)); err != nil {
+		return nil, err
+	}
+	for name, dep := range m.Deps {
+		if !depNameRe.MatchString(name) {
+			return nil, fmt.Errorf("%s: invalid dep name '%s'", path, name)
+		}
+		if (dep.Path == "") == (dep.Git == "") {
+			return nil, fmt.Errorf("%s: dep '%s' needs either `path` or `git`", path, name)
+		}
+	}
+	return m, nil
+}
+
+// DepDir is where dep `name` is, git deps are in the user cache dir
+func (m *Manifest) DepDir(name string) (string, error) {
+	dep, ok := m.Deps[name]
+	if !ok {
+		return "", fmt.Errorf("unknown dep '%s'", name)
+	}
+	if dep.Path != "" {
+		if filepath.IsAbs(dep.Path) {
+			return dep.Path, nil
+		}
+		return filepath.Join(m.Dir, dep.Path), nil
+	}
+	cache, err := os.UserCacheDir()
+	if err != nil {
+		return "", err
+	}
+	hash := sha256.Sum256([]byte(dep.Git + "#" + dep.Ref))
+	return filepath.Join(cache, "lk", "deps", fmt.Sprintf("%s-%x", name, hash[:6])), nil
+}
+
+// Candidates are files tried in order for `import name`
+func (m *Manifest) Candidates(name string) []string {
+	files := []string{}
+	dep, rest, _ := strings.Cut(name, "/")
+	if _, ok := m.Deps[dep]; ok {
+		if dir, err := m.DepDir(dep); err == nil {
+			if rest == "" {
+				files = append(files, filepath.Join(dir, "init.lk"))
+			} else {
+				files = append(files, _moduleFiles(dir, rest)...)
+			}
+		}
+	}
+	for _, p := range m.Paths {
+		files = append(files, _moduleFiles(filepath.Join(m.Dir, p), name)...)
+	}
+	return files
+}
+
+func _moduleFiles(dir, name string) []string {
+	base := filepath.Join(dir, filepath.FromSlash(name))
+	return []string{base + ".lk", base + ".lkc", filepath.Join(base, "init.lk")}
+}
+
+// Fetch clones git deps not fetched yet, it returns names of cloned ones
+func (m *Manifest) Fetch() ([]string, error) {
+	names := make([]string, 0, len(m.Deps))
+	for name := range m.Deps {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	fetched := []string{}
+	for _, name := range names {
+		dep := m.Deps[name]
+		if dep.Git == "" {
+			continue
+		}
+		dir, err := m.DepDir(name)
+		if err != nil {
+			return fetched, err
+		}
+		if _, err := os.Stat(dir); err == nil {
+			continue
+		}
+		args := []string{"clone", "--depth", "1"}
+		if dep.Ref != "" {
+			args = append(args, "--branch", dep.Ref)
+		}
+		args = append(args, dep.Git, dir)
+		if out, err := exec.Command("git", args...).CombinedOutput(); err != nil {
+			os.RemoveAll(dir)
+			return fetched, errors.New(name + ": " + strings.TrimSpace(string(out)))
+		}
+		fetched = append(fetched, name)
+	}
+	return fetched, nil
+}
@@ -0,0 +1,60 @@
+package project
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+)
+
+const manifest = `{
+  "name": "app",
+  "paths": ["src"],
+  "deps": {
+    "utils": {"path": "../utils"}
+  }
+}`
+
+func TestFindAndCandidates(t *testing.T) {
+	root := t.TempDir()
+	dir := filepath.Join(root, "app")
+	sub := filepath.Join(dir, "src", "sub")
+	if err := os.MkdirAll(sub, 0755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, FileName), []byte(manifest), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	m, err := Find(sub)
+	if err != nil || m == nil {
+		t.Fatalf("find: %v, %v", m, err)
+	}
+	utils := filepath.Join(root, "utils")
+	want := []string{
+		filepath.Join(utils, "str.lk"),
+		filepath.Join(utils, "str.lkc"),
+		filepath.Join(utils, "str", "init.lk"),
+		filepath.Join(dir, "src", "utils", "str.lk"),
+		filepath.Join(dir, "src", "utils", "str.lkc"),
+		filepath.Join(dir, "src", "utils", "str", "init.lk"),
+	}
+	if got := m.Candidates("utils/str"); !reflect.DeepEqual(got, want) {
+		t.Errorf("got %v, want %v", got, want)
+	}
+	if got := m.Candidates("utils")[0]; got != filepath.Join(utils, "init.lk") {
+		t.Errorf("dep root: %s", got)
+	}
+
+	if m, err := Find(root); m != nil || err != nil {
+		t.Errorf("no manifest: %v, %v", m, err)
+	}
+}
+
+func TestLoadInvalidDep(t *testing.T) {
+	path := filepath.Join(t.TempDir(), FileName)
+	os.WriteFile(path, []byte(`{"deps": {"a": {"path": "x", "git": "y"}}}`), 0644)
+	if _, err := Load(path); err == nil {
+		t.Error("dep with both path and git should fail")
+	}
+}
@@ -28,6 +28,7 @@ var (
 		"`Ctrl + a`: Clear REPL history",
 		"",
 		"`reset()`: Reset REPL state",
+		"`help(name)`: Show the doc of a fn or module, eg: help('num')",
 	}
 	printRunesPre  = []rune("print(")
 	printfRunesPre = []rune("printf(")
@@ -38,9 +39,18 @@ var (
 )
 
 func newState() {
+	if ls != nil {
+		ls.Close()
+	}
 	ls = state.New()
 	ls.OpenLibs()
+	ls.GetGlobal("help")
+	fnHelp := ls.ToGoFunction(-1)
+	ls.Pop(1)
 	ls.Register("help", func(ls api.LkState) int {
+		if ls.GetTop() > 0 {
+			return fnHelp(ls)
+		}
 		print(strings.Join(helpMsgs, "\n") + "\n")
 		return 0
 	})
@@ -88,7 +98,9 @@ func protectedCall(ls api.LkState, cmd string) {
 	defer ls.CatchAndPrint(true)
 
 	//log.Green(">>> " + cmd)
-	ls.LoadString(cmd, "stdin")
+	if ls.LoadString(cmd, "stdin") != api.LK_OK {
+		panic(ls.ToString(-1))
+	}
 
 	ls.PCall(0, api.LK_MULTRET, 1)
 	updateHistory(cmd)
@@ -3,6 +3,7 @@ package state
 import (
 	"fmt"
 	"math"
+	"math/big"
 
 	. "github.com/lollipopkit/lk/api"
 	"github.com/lollipopkit/lk/utils"
@@ -13,6 +14,10 @@ type operator struct {
 	integerFunc func(int64, int64) int64
 	floatFunc   func(float64, float64) float64
 	symbol      string
+	// set if integerFunc may overflow, the result is a bigint then
+	overflows func(int64, int64) bool
+	bigFunc   func(*big.Int, *big.Int) *big.Int
+	decFunc   decFunc
 }
 
 var (
@@ -36,23 +41,25 @@ var (
 	iunm  = func(a, _ int64) int64 { return -a }
 	funm  = func(a, _ float64) float64 { return -a }
 	bnot  = func(a, _ int64) int64 { return ^a }
+
+	unmOverflows = func(a, _ int64) bool { return a == math.MinInt64 }
 )
 
 var operators = []operator{
-	{"__add", iadd, fadd, "+"},
-	{"__sub", isub, fsub, "-"},
-	{"__mul", imul, fmul, "*"},
-	{"__mod", imod, fmod, "%"},
-	{"__pow", nil, pow, "^"},
-	{"__div", nil, div, "/"},
-	{"__idiv", iidiv, fidiv, "~/"},
-	{"__band", band, nil, "&"},
-	{"__bor", bor, nil, "|"},
-	{"__bxor", bxor, nil, "^"},
-	{"__shl", shl, nil, "<<"},
-	{"__shr", shr, nil, ">>"},
-	{"__unm", iunm, funm, "-"},
-	{"__bnot", bnot, nil, "~"},
+	{"__add", iadd, fadd, "+", utils.AddOverflows, badd, dadd},
+	{"__sub", isub, fsub, "-", utils.SubOverflows, bsub, dsub},
+	{"__mul", imul, fmul, "*", utils.MulOverflows, bmul, dmul},
+	{"__mod", imod, fmod, "%", nil, bmod, dmod},
+	{"__pow", nil, pow, "^", nil, nil, nil},
+	{"__div", nil, div, "/", nil, nil, ddiv},
+	{"__idiv", iidiv, fidiv, "~/", utils.IFloorDivOverflows, bidiv, didiv},
+	{"__band", band, nil, "&", nil, nil, nil},
+	{"__bor", bor, nil, "|", nil, nil, nil},
+	{"__bxor", bxor, nil, "^", nil, nil, nil},
+	{"__shl", shl, nil, "<<", nil, nil, nil},
+	{"__shr", shr, nil, ">>", nil, nil, nil},
+	{"__unm", iunm, funm, "-", unmOverflows, bunm, dunm},
+	{"__bnot", bnot, nil, "~", nil, nil, nil},
 }
 
 // [-(2|1), +1, e]
@@ -99,10 +106,21 @@ func _arith(a, b any, op operator) any {
 		if op.integerFunc != nil { // add,sub,mul,mod,idiv,unm
 			if x, ok := a.(int64); ok {
 				if y, ok := b.(int64); ok {
-					return op.integerFunc(x, y)
+					if op.overflows == nil || !op.overflows(x, y) {
+						return op.integerFunc(x, y)
+					}
+					return _normBig(op.bigFunc(big.NewInt(x), big.NewInt(y)))
+				}
+			}
+			if x, ok := _toBig(a); ok {
+				if y, ok := _toBig(b); ok {
+					return _normBig(op.bigFunc(x, y))
 				}
 			}
 		}
+		if r, ok := _decArith(a, b, op); ok {
+			return r
+		}
 		if x, ok := convertToFloat(a); ok {
 			if y, ok := convertToFloat(b); ok {
 				return op.floatFunc(x, y)
@@ -4,6 +4,7 @@ import (
 	"fmt"
 	"os"
 	"strings"
+	"sync/atomic"
 
 	"github.com/lollipopkit/gommon/log"
 	"github.com/lollipopkit/gommon/sys"
@@ -43,7 +44,7 @@ func (self *lkState) Call(nArgs, nResults int) {
 
 func (self *lkState) callGoClosure(nArgs, nResults int, c *lkClosure) {
 	// create new lua stack
-	newStack := newLuaStack(nArgs+LK_MINSTACK, self)
+	newStack := self.acquireStack(nArgs + LK_MINSTACK)
 	newStack.closure = c
 
 	// pass args, pop func
@@ -64,6 +65,7 @@ func (self *lkState) callGoClosure(nArgs, nResults int, c *lkClosure) {
 		self.stack.check(len(results))
 		self.stack.pushN(results, nResults)
 	}
+	self.releaseStack(newStack)
 }
 
 func (self *lkState) callLuaClosure(nArgs, nResults int, c *lkClosure) {
@@ -72,7 +74,7 @@ func (self *lkState) callLuaClosure(nArgs, nResults int, c *lkClosure) {
 	isVararg := c.proto.IsVararg == 1
 
 	// create new lua stack
-	newStack := newLuaStack(nRegs+LK_MINSTACK, self)
+	newStack := self.acquireStack(nRegs + LK_MINSTACK)
 	newStack.closure = c
 
 	// pass args, pop func
@@ -94,10 +96,17 @@ func (self *lkState) callLuaClosure(nArgs, nResults int, c *lkClosure) {
 		self.stack.check(len(results))
 		self.stack.pushN(results, nResults)
 	}
+	self.releaseStack(newStack)
 }
 
 func (self *lkState) runLuaClosure() {
 	for {
+		if self.hook != nil {
+			self.traceLine()
+		}
+		if self.prof != nil && atomic.LoadInt32(&self.prof.ticks) > 0 {
+			self.sample()
+		}
 		inst := vm.Instruction(self.Fetch())
 		inst.Execute(self)
 		if inst.Opcode() == vm.OP_RETURN {
@@ -108,18 +117,23 @@ func (self *lkState) runLuaClosure() {
 
 func (self *lkState) CatchAndPrint(isRepl bool) {
 	if err := recover(); err != nil {
-		log.Red("%v\n", err)
-		stack := self.stack
-		if isRepl {
-			_catchEachStack(stack, -1)
-			return
-		}
-		stackIdx := 0
-		for stack.prev != nil {
-			_catchEachStack(stack, stackIdx)
-			stack = stack.prev
-			stackIdx++
-		}
+		self.PrintError(err, isRepl)
+	}
+}
+
+// PrintError prints err with the call stack where it was raised
+func (self *lkState) PrintError(err any, isRepl bool) {
+	log.Red("%v\n", err)
+	stack := self.stack
+	if isRepl {
+		_catchEachStack(stack, -1)
+		return
+	}
+	stackIdx := 0
+	for stack.prev != nil {
+		_catchEachStack(stack, stackIdx)
+		stack = stack.prev
+		stackIdx++
 	}
 }
 
@@ -28,6 +28,9 @@ func (self *lkState) Compare(idx1, idx2 int, op CompareOp) bool {
 }
 
 func _eq(a, b any, ls *lkState) bool {
+	if c, ok := _exactCmp(a, b); ok {
+		return c == 0
+	}
 	switch x := a.(type) {
 	case nil:
 		return b == nil
@@ -68,6 +71,9 @@ func _eq(a, b any, ls *lkState) bool {
 }
 
 func _lt(a, b any, ls *lkState) bool {
+	if c, ok := _exactCmp(a, b); ok {
+		return c < 0
+	}
 	switch x := a.(type) {
 	case string:
 		if y, ok := b.(string); ok {
@@ -97,6 +103,9 @@ func _lt(a, b any, ls *lkState) bool {
 }
 
 func _le(a, b any, ls *lkState) bool {
+	if c, ok := _exactCmp(a, b); ok {
+		return c <= 0
+	}
 	switch x := a.(type) {
 	case string:
 		if y, ok := b.(string); ok {
@@ -6,7 +6,10 @@ import . "github.com/lollipopkit/lk/api"
 // http://www.lua.org/manual/5.3/manual.html#lua_newthread
 // lua-5.3.4/src/lstate.c#lua_newthread()
 func (self *lkState) NewThread() LkState {
-	t := &lkState{registry: self.registry}
+	t := &lkState{registry: self.registry, hook: self.hook, prof: self.prof}
+	if self.locals != nil {
+		t.locals = self.locals.copy()
+	}
 	t.pushLuaStack(newLuaStack(LK_MINSTACK, t))
 	self.stack.push(t)
 	return t
@@ -74,3 +77,14 @@ func (self *lkState) Status() LkStatus {
 func (self *lkState) GetStack() bool {
 	return self.stack.prev != nil
 }
+
+// [-0, +1, –]
+// pushes the table of values local to this thread.
+// A new thread starts with a copy of its creator's table,
+// so changes are seen by threads created after them, but not siblings.
+func (self *lkState) PushLocals() {
+	if self.locals == nil {
+		self.locals = newLkTable(0, 4)
+	}
+	self.stack.push(self.locals)
+}
@@ -0,0 +1,149 @@
+package state
+
+import (
+	"sync/atomic"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// profiler is shared by a state and its coroutines
+type profiler struct {
+	// intervals passed since the last sample, added by the ticker
+	ticks int32
+	hook  ProfileHook
+}
+
+// SetDebugHook sets the line hook, nil removes it.
+// Coroutines created later share the hook.
+func (self *lkState) SetDebugHook(hook DebugHook) {
+	self.hook = hook
+}
+
+// GetInfo describes the lk function running at level,
+// 0 is the current one, 1 is its caller and so on.
+// Go functions are skipped.
+func (self *lkState) GetInfo(level int) (DebugInfo, bool) {
+	stack := self._lkFrame(level)
+	if stack == nil {
+		return DebugInfo{}, false
+	}
+	depth := 0
+	for s := stack; s != nil; s = s.prev {
+		if s.closure != nil && s.closure.proto != nil {
+			depth++
+		}
+	}
+	proto := stack.closure.proto
+	pc := stack.pc
+	if stack != self.stack {
+		/* a caller, pc is after its call instruction */
+		pc--
+	}
+	return DebugInfo{
+		Source:      proto.Source,
+		Line:        _lineAt(proto.LineInfo, pc),
+		LineDefined: int(proto.LineDefined),
+		Depth:       depth,
+	}, true
+}
+
+// GetLocal pushes the n-th (from 1) active local of the function at level,
+// returns its name. Returns "" and pushes nothing if there is no such local.
+func (self *lkState) GetLocal(level, n int) string {
+	stack := self._lkFrame(level)
+	if stack == nil || n < 1 {
+		return ""
+	}
+	/* the n-th active local is in register n-1 */
+	pc := uint32(stack.pc)
+	slot := 0
+	for _, v := range stack.closure.proto.LocVars {
+		if v.StartPC > pc || pc >= v.EndPC {
+			continue
+		}
+		slot++
+		if slot == n {
+			self.stack.push(stack.slots[slot-1])
+			return v.VarName
+		}
+	}
+	return ""
+}
+
+func (self *lkState) _lkFrame(level int) *lkStack {
+	for stack := self.stack; stack != nil; stack = stack.prev {
+		if stack.closure == nil || stack.closure.proto == nil {
+			continue
+		}
+		if level == 0 {
+			return stack
+		}
+		level--
+	}
+	return nil
+}
+
+func _lineAt(lines []uint32, pc int) int {
+	if pc >= len(lines) {
+		pc = len(lines) - 1
+	}
+	if pc < 0 {
+		return 0
+	}
+	return int(lines[pc])
+}
+
+// traceLine calls the hook if the next instruction starts a line
+func (self *lkState) traceLine() {
+	stack := self.stack
+	lines := stack.closure.proto.LineInfo
+	pc := stack.pc
+	if self.inHook || pc >= len(lines) {
+		return
+	}
+	line := int(lines[pc])
+	/* a new frame, a new line or a jump back */
+	if pc == 0 || line != stack.hookLine || pc <= stack.hookPC {
+		stack.hookLine = line
+		stack.hookPC = pc
+		info, _ := self.GetInfo(0)
+		self.inHook = true
+		defer func() { self.inHook = false }()
+		self.hook(self, info)
+		return
+	}
+	stack.hookPC = pc
+}
+
+// SetProfiler calls hook about every interval until stop is called.
+// A ticker only marks that a sample is due, the hook runs on the
+// goroutine of the script before its next instruction.
+// Time spent in Go functions is counted for the lk line calling them.
+func (self *lkState) SetProfiler(interval time.Duration, hook ProfileHook) (stop func()) {
+	p := &profiler{hook: hook}
+	self.prof = p
+	ticker := time.NewTicker(interval)
+	done := make(chan struct{})
+	go func() {
+		for {
+			select {
+			case <-ticker.C:
+				atomic.AddInt32(&p.ticks, 1)
+			case <-done:
+				ticker.Stop()
+				return
+			}
+		}
+	}()
+	return func() {
+		close(done)
+		self.prof = nil
+	}
+}
+
+func (self *lkState) sample() {
+	if weight := atomic.SwapInt32(&self.prof.ticks, 0); weight > 0 {
+		self.prof.hook(self, int(weight))
+	}
+}
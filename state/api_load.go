@@ -1,6 +1,7 @@
 package state
 
 import (
+	"fmt"
 	"io/ioutil"
 	"os"
 	"strings"
@@ -10,6 +11,7 @@ import (
 	"github.com/lollipopkit/lk/binchunk"
 	"github.com/lollipopkit/lk/compiler"
 	"github.com/lollipopkit/lk/utils"
+	"github.com/lollipopkit/lk/vm"
 )
 
 func Compile(source string) *binchunk.Prototype {
@@ -41,24 +43,40 @@ func Compile(source string) *binchunk.Prototype {
 
 // [-0, +1, –]
 // http://www.lua.org/manual/5.3/manual.html#lua_load
+// Syntax errors and broken binary chunks push the error message
+// and return LK_ERRSYNTAX, they never exit or panic.
 func (self *lkState) Load(chunk []byte, chunkName, mode string) LkStatus {
-	var proto *binchunk.Prototype
-	if chunkName == "stdin" || strings.HasSuffix(chunkName, ".lk") {
-		proto = compiler.Compile(string(chunk), chunkName)
-	} else {
-		var err error
-		proto, err = binchunk.Load(chunk)
-		if err != nil {
-			log.Red("[load] load chunk failed: " + err.Error())
-			os.Exit(2)
-		}
+	proto, err := _loadProto(chunk, chunkName, mode)
+	if err != nil {
+		self.stack.push(fmt.Sprintf("%v", err))
+		return LK_ERRSYNTAX
 	}
 
 	c := newLuaClosure(proto)
 	self.stack.push(c)
 	if len(proto.Upvalues) > 0 {
 		env := self.registry.get(LK_RIDX_GLOBALS)
-		c.upVals[0] = &env
+		c.upVals[0] = &upvalue{&env}
 	}
 	return LK_OK
 }
+
+func _loadProto(chunk []byte, chunkName, mode string) (proto *binchunk.Prototype, err any) {
+	if mode == "t" || chunkName == "stdin" || strings.HasSuffix(chunkName, ".lk") {
+		/* the compiler panics on syntax errors */
+		defer func() {
+			if r := recover(); r != nil {
+				proto, err = nil, r
+			}
+		}()
+		return compiler.Compile(string(chunk), chunkName), nil
+	}
+	proto, e := binchunk.Load(chunk)
+	if e == nil {
+		e = vm.Verify(proto)
+	}
+	if e != nil {
+		return nil, "load chunk failed: " + e.Error()
+	}
+	return proto, nil
+}
@@ -28,6 +28,9 @@ func (self *lkState) Next(idx int) bool {
 	val := self.stack.get(idx)
 	if t, ok := val.(*lkTable); ok {
 		key := self.stack.pop()
+		if key == nil {
+			self.openLazy(t)
+		}
 		if nextKey := t.nextKey(key); nextKey != nil {
 			self.stack.push(nextKey)
 			self.stack.push(t.get(nextKey))
@@ -50,13 +50,13 @@ func (self *lkState) LoadProto(idx int) {
 		uvIdx := int(subProto.Upvalues[i].Idx)
 		if subProto.Upvalues[i].Instack == 1 {
 			if stack.openuvs == nil {
-				stack.openuvs = map[int]*any{}
+				stack.openuvs = map[int]*upvalue{}
 			}
 
 			if openuv, found := stack.openuvs[uvIdx]; found {
 				closure.upVals[i] = openuv
 			} else {
-				closure.upVals[i] = &stack.slots[uvIdx]
+				closure.upVals[i] = &upvalue{&stack.slots[uvIdx]}
 				stack.openuvs[uvIdx] = closure.upVals[i]
 			}
 		} else {
@@ -66,10 +66,11 @@ func (self *lkState) LoadProto(idx int) {
 }
 
 func (self *lkState) CloseUpvalues(a int) {
-	for i := range self.stack.openuvs {
+	for i, uv := range self.stack.openuvs {
 		if i >= a-1 {
-			val := *self.stack.openuvs[i]
-			self.stack.openuvs[i] = &val
+			/* closures sharing uv see the copy from now on */
+			val := *uv.val
+			uv.val = &val
 			delete(self.stack.openuvs, i)
 		}
 	}
@@ -3,7 +3,9 @@ package state
 import (
 	"fmt"
 	"io/ioutil"
+	"sort"
 
+	"github.com/lollipopkit/gommon/log"
 	. "github.com/lollipopkit/lk/api"
 	"github.com/lollipopkit/lk/stdlib"
 )
@@ -154,10 +156,12 @@ func (self *lkState) LoadFile(filename string) LkStatus {
 // [-0, +1, m]
 // http://www.lua.org/manual/5.3/manual.html#luaL_loadfilex
 func (self *lkState) LoadFileX(filename, mode string) LkStatus {
-	if data, err := ioutil.ReadFile(filename); err == nil {
-		return self.Load(data, filename, mode)
+	data, err := ioutil.ReadFile(filename)
+	if err != nil {
+		self.stack.push("cannot open " + filename)
+		return LK_ERRFILE
 	}
-	return LK_ERRFILE
+	return self.Load(data, filename, mode)
 }
 
 // [-0, +1, –]
@@ -282,24 +286,51 @@ func (self *lkState) CallMeta(obj int, event string) bool {
 	return true
 }
 
+var libs = map[string]GoFunction{
+	"_G":      stdlib.OpenBaseLib,
+	"math":    stdlib.OpenMathLib,
+	"str":     stdlib.OpenStringLib,
+	"utf8":    stdlib.OpenUTF8Lib,
+	"os":      stdlib.OpenOSLib,
+	"pkg":     stdlib.OpenPackageLib,
+	"sync":    stdlib.OpenCoroutineLib,
+	"table":   stdlib.OpenTableLib,
+	"num":     stdlib.OpenNumLib,
+	"term":    stdlib.OpenTermLib,
+	"ast":     stdlib.OpenAstLib,
+	"embed":   stdlib.OpenEmbedLib,
+	"crypto":  stdlib.OpenCryptoLib,
+	"config":  stdlib.OpenConfigLib,
+	"i18n":    stdlib.OpenI18nLib,
+	"bytes":   stdlib.OpenBytesLib,
+	"regex":   stdlib.OpenRegexLib,
+	"semver":  stdlib.OpenSemverLib,
+	"csv":     stdlib.OpenCsvLib,
+	"rand":    stdlib.OpenRandLib,
+	"decimal": stdlib.OpenDecimalLib,
+}
+
+// HasLib reports whether `name` is opened by OpenLibs
+func HasLib(name string) bool {
+	_, ok := libs[name]
+	return ok
+}
+
 // [-0, +0, e]
 // http://www.lua.org/manual/5.3/manual.html#luaL_openlibs
+// Libs not in eagerLibs are opened on first use, see lazyLib.
 func (self *lkState) OpenLibs() {
-	libs := map[string]GoFunction{
-		"_G":    stdlib.OpenBaseLib,
-		"math":  stdlib.OpenMathLib,
-		"str":   stdlib.OpenStringLib,
-		"utf8":  stdlib.OpenUTF8Lib,
-		"os":    stdlib.OpenOSLib,
-		"pkg":   stdlib.OpenPackageLib,
-		"sync":  stdlib.OpenCoroutineLib,
-		"http":  stdlib.OpenHttpLib,
-		"table": stdlib.OpenTableLib,
-		"num":   stdlib.OpenNumLib,
-		"term":  stdlib.OpenTermLib,
-	}
-
+	/* sorted, so init hooks run in the same order every time */
+	names := make([]string, 0, len(libs))
 	for name := range libs {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		if !eagerLibs[name] {
+			self.lazyLib(name)
+			continue
+		}
 		// Only add "_G" as global
 		self.RequireF(name, libs[name], true)
 		self.Pop(1)
@@ -318,6 +349,7 @@ func (self *lkState) RequireF(modname string, openf GoFunction, glb bool) {
 		self.Call(1, 1)            /* call 'openf' to open module */
 		self.PushValue(-1)         /* make copy of module (call result) */
 		self.SetField(-3, modname) /* _LOADED[modname] = module */
+		self.initLib(modname)
 	}
 	self.Remove(-2) /* remove _LOADED table */
 	if glb {
@@ -326,6 +358,44 @@ func (self *lkState) RequireF(modname string, openf GoFunction, glb bool) {
 	}
 }
 
+// initLib runs the init hook of lib modname, and remembers it
+// if it has to be shut down by Close
+func (self *lkState) initLib(modname string) {
+	hooks, ok := stdlib.HooksOf(modname)
+	if !ok {
+		return
+	}
+	if hooks.Init != nil {
+		hooks.Init(self)
+	}
+	if hooks.Shutdown != nil {
+		main := self.mainThread()
+		main.opened = append(main.opened, modname)
+	}
+}
+
+// [-0, +0, -]
+func (self *lkState) Close() {
+	main := self.mainThread()
+	opened := main.opened
+	main.opened = nil
+	for i := len(opened) - 1; i >= 0; i-- {
+		self.shutdownLib(opened[i])
+	}
+}
+
+// shutdownLib runs the shutdown hook of lib modname,
+// a panic is logged so the other libs are still shut down
+func (self *lkState) shutdownLib(modname string) {
+	defer func() {
+		if err := recover(); err != nil {
+			log.Red("[%s] shutdown failed: %v", modname, err)
+		}
+	}()
+	hooks, _ := stdlib.HooksOf(modname)
+	hooks.Shutdown(self)
+}
+
 // [-0, +1, m]
 // http://www.lua.org/manual/5.3/manual.html#luaL_newlib
 func (self *lkState) NewLib(l FuncReg) {
@@ -0,0 +1,104 @@
+package state
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/stdlib"
+)
+
+func TestLibHooks(t *testing.T) {
+	calls := []string{}
+	open := func(ls LkState) int {
+		ls.NewTable()
+		return 1
+	}
+	for _, name := range []string{"hooks_a", "hooks_b"} {
+		name := name
+		stdlib.SetLibHooks(name, stdlib.LibHooks{
+			Init: func(ls LkState) {
+				calls = append(calls, "init "+name)
+			},
+			Shutdown: func(ls LkState) {
+				calls = append(calls, "shutdown "+name)
+				if name == "hooks_b" {
+					panic("shutdown fails")
+				}
+			},
+		})
+	}
+
+	ls := New()
+	ls.RequireF("hooks_a", open, false)
+	ls.RequireF("hooks_b", open, false)
+	/* opened already, no init again */
+	ls.RequireF("hooks_a", open, false)
+	ls.Close()
+	ls.Close()
+
+	want := []string{"init hooks_a", "init hooks_b", "shutdown hooks_b", "shutdown hooks_a"}
+	if !reflect.DeepEqual(calls, want) {
+		t.Fatalf("got %v, want %v", calls, want)
+	}
+}
+
+func TestHelpText(t *testing.T) {
+	ls := New()
+	ls.OpenLibs()
+	if got := stdlib.HelpText(ls, "num.currency"); !strings.HasPrefix(got, "num.currency(amount: any") {
+		t.Errorf("fn: %s", got)
+	}
+	got := stdlib.HelpText(ls, "num")
+	if !strings.HasPrefix(got, "num\n") || !strings.Contains(got, "\n  num.currency(amount: any") {
+		t.Errorf("module: %s", got)
+	}
+	if got := stdlib.HelpText(ls, "nope"); got != "no help for 'nope'" {
+		t.Errorf("unknown: %s", got)
+	}
+}
+
+func TestDenyDeprecated(t *testing.T) {
+	stdlib.DenyDeprecated = true
+	defer func() { stdlib.DenyDeprecated = false }()
+	ls := New()
+	ls.OpenLibs()
+	if !ls.DoString("os.rand()", "deny") {
+		t.Fatal("os.rand should fail")
+	}
+	if msg := ls.ToString(-1); !strings.Contains(msg, "os.rand is deprecated, use rand.int") {
+		t.Errorf("error: %s", msg)
+	}
+}
+
+func TestLazyLibs(t *testing.T) {
+	ls := New().(*lkState)
+	ls.OpenLibs()
+	if len(ls.lazy) == 0 {
+		t.Fatal("all libs are opened eagerly")
+	}
+	src := "v := import('semver')\n" +
+		"assert(v == semver and semver.parse != nil)\n" +
+		"assert(csv.parse != nil)"
+	if ls.DoString(src, "lazy") {
+		t.Fatal(ls.ToString(-1))
+	}
+	for _, name := range ls.lazy {
+		if name == "semver" || name == "csv" {
+			t.Errorf("%s is not opened", name)
+		}
+	}
+}
+
+func TestSchemeCachePerState(t *testing.T) {
+	for _, v := range []string{"1", "2"} {
+		ls := New()
+		ls.OpenLibs()
+		src := "pkg.register_scheme('mem', fn(path) => 'rt " + v + "')\n" +
+			"assert(import('mem:v') == " + v + ")"
+		if ls.DoString(src, "scheme") {
+			t.Fatalf("state %s: %s", v, ls.ToString(-1))
+		}
+	}
+}
@@ -0,0 +1,108 @@
+package state
+
+import (
+	"fmt"
+	"reflect"
+	"testing"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/compiler"
+	"github.com/lollipopkit/lk/compiler/ast"
+	"github.com/lollipopkit/lk/compiler/parser"
+)
+
+// FuzzDifferential runs a program from source and from its dumped chunk,
+// both must give the same results or the same error.
+func FuzzDifferential(f *testing.F) {
+	for _, src := range []string{
+		"rt 1 + 2 * 3, 7 ~/ 2, 7 % -3, 2 ^ 10",
+		"a := {1, 2, 'x': 3}\na[5] = a.x\nrt #a, a[5]",
+		"a := nil\nrt a ?? 'd', a == nil ? 1 : 2",
+		"s := 'ab'\nrt s + 'c', #s, s == 'ab'",
+		"rt 1 / 0, -(0 / 0) != -(0 / 0), 3 & 5 | 8 ~ 1, 1 << 62",
+		"a, b := 1\nb ??= 2\na += b\nrt a, b",
+	} {
+		f.Add(src)
+	}
+	f.Fuzz(func(t *testing.T, src string) {
+		if !terminates(src) {
+			return
+		}
+		fromSrc := runChunk([]byte(src), "t")
+
+		var chunk []byte
+		func() {
+			defer func() { recover() }()
+			data, err := compiler.Compile(src, "fuzz").Dump("")
+			if err != nil {
+				t.Fatalf("dump failed: %v", err)
+			}
+			chunk = data
+		}()
+		if chunk == nil {
+			return
+		}
+		if fromChunk := runChunk(chunk, "b"); fromSrc != fromChunk {
+			t.Fatalf("results differ on %q:\nsource: %s\nchunk:  %s", src, fromSrc, fromChunk)
+		}
+	})
+}
+
+// runChunk returns the results, or the error, as a string
+func runChunk(chunk []byte, mode string) (out string) {
+	ls := New()
+	ls.OpenLibs()
+	defer func() {
+		if r := recover(); r != nil {
+			out = fmt.Sprintf("compile error: %v", r)
+		}
+	}()
+	ls.Load(chunk, "fuzz", mode)
+	if ls.PCall(0, LK_MULTRET, 0) != LK_OK {
+		return fmt.Sprintf("error: %v", ls.ToPointer(-1))
+	}
+	n := ls.GetTop()
+	for i := 1; i <= n; i++ {
+		out += ls.TypeName2(i) + ":" + ls.ToString2(i) + "\t"
+	}
+	return out
+}
+
+// terminates reports whether src parses and has no loop, call or fn,
+// so it can't run forever or touch the outside world.
+func terminates(src string) (ok bool) {
+	defer func() {
+		if recover() != nil {
+			ok = false
+		}
+	}()
+	return !hasNode(reflect.ValueOf(parser.Parse(src, "fuzz")))
+}
+
+func hasNode(v reflect.Value) bool {
+	switch v.Kind() {
+	case reflect.Pointer, reflect.Interface:
+		if v.IsNil() {
+			return false
+		}
+		switch v.Interface().(type) {
+		case *ast.WhileStat, *ast.ForNumStat, *ast.ForInStat,
+			*ast.FuncCallExp, *ast.FuncDefExp, *ast.LocalFuncDefStat:
+			return true
+		}
+		return hasNode(v.Elem())
+	case reflect.Struct:
+		for i := 0; i < v.NumField(); i++ {
+			if hasNode(v.Field(i)) {
+				return true
+			}
+		}
+	case reflect.Slice:
+		for i := 0; i < v.Len(); i++ {
+			if hasNode(v.Index(i)) {
+				return true
+			}
+		}
+	}
+	return false
+}
@@ -0,0 +1,67 @@
+package state
+
+import (
+	. "github.com/lollipopkit/lk/api"
+)
+
+// eagerLibs are opened by OpenLibs right away, since they set
+// globals or metatables, or are used by almost every script.
+// Others are opened on first use of their fields.
+var eagerLibs = map[string]bool{
+	"_G":    true,
+	"pkg":   true,
+	"str":   true,
+	"table": true,
+	"num":   true,
+	"math":  true,
+	"os":    true,
+}
+
+// lazyLib puts a placeholder of lib modname in globals and _LOADED.
+// Its `__index` opens the lib into the placeholder, so the lib is
+// the same table whether it's reached before or after opening.
+func (self *lkState) lazyLib(modname string) {
+	main := self.mainThread()
+	if main.lazy == nil {
+		main.lazy = map[*lkTable]string{}
+	}
+	t := newLkTable(0, 1)
+	t.put("__index", newGoClosure(lazyIndex, 0))
+	main.lazy[t] = modname
+
+	self.GetSubTable(LK_REGISTRYINDEX, "_LOADED")
+	self.stack.push(t)
+	self.SetField(-2, modname)
+	self.Pop(1)
+	self.stack.push(t)
+	self.SetGlobal(modname)
+}
+
+// lazyIndex is `__index` of placeholders: (t, k) => t[k]
+func lazyIndex(ls LkState) int {
+	self := ls.(*lkState)
+	if t, ok := self.stack.get(1).(*lkTable); ok {
+		self.openLazy(t)
+	}
+	ls.RawGet(1)
+	return 1
+}
+
+// openLazy opens the lib of placeholder t, if it's not opened yet
+func (self *lkState) openLazy(t *lkTable) {
+	main := self.mainThread()
+	modname, ok := main.lazy[t]
+	if !ok {
+		return
+	}
+	delete(main.lazy, t)
+	t.put("__index", nil)
+
+	self.PushGoFunction(libs[modname])
+	self.PushString(modname)
+	self.Call(1, 1)
+	if lib, ok := self.stack.pop().(*lkTable); ok {
+		t.combine(lib)
+	}
+	self.initLib(modname)
+}
@@ -0,0 +1,16 @@
+//go:build !lk_minimal
+
+package state
+
+import "github.com/lollipopkit/lk/stdlib"
+
+// libs using sockets, files or processes, left out by `-tags lk_minimal`
+// for embedders which don't need them
+func init() {
+	libs["http"] = stdlib.OpenHttpLib
+	libs["udp"] = stdlib.OpenUdpLib
+	libs["blob"] = stdlib.OpenBlobLib
+	libs["kv"] = stdlib.OpenKvLib
+	libs["shell"] = stdlib.OpenShellLib
+	libs["app"] = stdlib.OpenAppLib
+}
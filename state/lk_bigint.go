@@ -0,0 +1,121 @@
+package state
+
+import (
+	"math"
+	"math/big"
+
+	"github.com/lollipopkit/lk/decimal"
+)
+
+// Ints overflowing int64 in +, -, *, ~/ and unary - are promoted to
+// *big.Int, and go back to int64 once a result fits again.
+// Bigints are nums: they mix with ints exactly, and with floats as floats.
+
+var big1 = big.NewInt(1)
+
+var (
+	badd = func(a, b *big.Int) *big.Int { return new(big.Int).Add(a, b) }
+	bsub = func(a, b *big.Int) *big.Int { return new(big.Int).Sub(a, b) }
+	bmul = func(a, b *big.Int) *big.Int { return new(big.Int).Mul(a, b) }
+	bunm = func(a, _ *big.Int) *big.Int { return new(big.Int).Neg(a) }
+)
+
+// floor division, like utils.IFloorDiv
+func bidiv(a, b *big.Int) *big.Int {
+	q, m := new(big.Int).QuoRem(a, b, new(big.Int))
+	if m.Sign() != 0 && m.Sign() != b.Sign() {
+		q.Sub(q, big1)
+	}
+	return q
+}
+
+// the sign of result follows b, like utils.IMod
+func bmod(a, b *big.Int) *big.Int {
+	m := new(big.Int).Rem(a, b)
+	if m.Sign() != 0 && m.Sign() != b.Sign() {
+		m.Add(m, b)
+	}
+	return m
+}
+
+func _toBig(val any) (*big.Int, bool) {
+	switch x := val.(type) {
+	case int64:
+		return big.NewInt(x), true
+	case *big.Int:
+		return x, true
+	}
+	return nil, false
+}
+
+// _normBig returns n as int64 if it fits
+func _normBig(n *big.Int) any {
+	if n.IsInt64() {
+		return n.Int64()
+	}
+	return n
+}
+
+func _bigToFloat(n *big.Int) float64 {
+	f, _ := new(big.Float).SetInt(n).Float64()
+	return f
+}
+
+// _exactCmp compares a and b exactly if one of them is a bigint or decimal.
+// c is 2 if the other one is NaN, so none of <, == and <= holds.
+func _exactCmp(a, b any) (c int, ok bool) {
+	switch a.(type) {
+	case *big.Int, *decimal.Decimal:
+	default:
+		switch b.(type) {
+		case *big.Int, *decimal.Decimal:
+		default:
+			return 0, false
+		}
+	}
+	x, okx := _toRat(a)
+	y, oky := _toRat(b)
+	if okx && oky {
+		return x.Cmp(y), true
+	}
+	/* inf or nan */
+	if f, ok := a.(float64); ok && oky {
+		return _nonFiniteCmp(f), true
+	}
+	if g, ok := b.(float64); ok && okx {
+		if c := _nonFiniteCmp(g); c != 2 {
+			return -c, true
+		}
+		return 2, true
+	}
+	return 0, false
+}
+
+// _toRat converts exact nums and finite floats
+func _toRat(val any) (*big.Rat, bool) {
+	switch x := val.(type) {
+	case int64:
+		return new(big.Rat).SetInt64(x), true
+	case float64:
+		if math.IsNaN(x) || math.IsInf(x, 0) {
+			return nil, false
+		}
+		return new(big.Rat).SetFloat64(x), true
+	case *big.Int:
+		return new(big.Rat).SetInt(x), true
+	case *decimal.Decimal:
+		return x.Rat(), true
+	}
+	return nil, false
+}
+
+// _nonFiniteCmp compares inf or nan f with any finite num
+func _nonFiniteCmp(f float64) int {
+	switch {
+	case f > 0:
+		return 1
+	case f < 0:
+		return -1
+	}
+	return 2
+}
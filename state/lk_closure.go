@@ -10,13 +10,19 @@ import (
 type lkClosure struct {
 	proto  *binchunk.Prototype // lua closure
 	goFunc GoFunction          // go closure
-	upVals []*any
+	upVals []*upvalue
+}
+
+// upvalue is shared by closures capturing the same variable,
+// it points into the stack while open, and to its own copy once closed.
+type upvalue struct {
+	val *any
 }
 
 func newLuaClosure(proto *binchunk.Prototype) *lkClosure {
 	c := &lkClosure{proto: proto}
 	if nUpvals := len(proto.Upvalues); nUpvals > 0 {
-		c.upVals = make([]*any, nUpvals)
+		c.upVals = make([]*upvalue, nUpvals)
 	}
 	return c
 }
@@ -24,7 +30,7 @@ func newLuaClosure(proto *binchunk.Prototype) *lkClosure {
 func newGoClosure(f GoFunction, nUpvals int) *lkClosure {
 	c := &lkClosure{goFunc: f}
 	if nUpvals > 0 {
-		c.upVals = make([]*any, nUpvals)
+		c.upVals = make([]*upvalue, nUpvals)
 	}
 	return c
 }
@@ -0,0 +1,54 @@
+package state
+
+import (
+	"math/big"
+
+	"github.com/lollipopkit/lk/decimal"
+)
+
+// Decimals come from `12.34d` literals or the decimal lib.
+// They mix with ints and bigints exactly, mixing with floats is an error:
+// money shouldn't lose cents silently.
+
+type decFunc func(*decimal.Decimal, *decimal.Decimal) *decimal.Decimal
+
+var (
+	dadd  decFunc = (*decimal.Decimal).Add
+	dsub  decFunc = (*decimal.Decimal).Sub
+	dmul  decFunc = (*decimal.Decimal).Mul
+	ddiv  decFunc = (*decimal.Decimal).Div
+	didiv decFunc = (*decimal.Decimal).FloorDiv
+	dmod  decFunc = (*decimal.Decimal).Mod
+	dunm  decFunc = func(a, _ *decimal.Decimal) *decimal.Decimal { return a.Neg() }
+)
+
+// _decArith is ok if a or b is a decimal, r is nil if the other one
+// can't be a decimal or op doesn't support decimals
+func _decArith(a, b any, op operator) (r any, ok bool) {
+	_, decA := a.(*decimal.Decimal)
+	_, decB := b.(*decimal.Decimal)
+	if !decA && !decB {
+		return nil, false
+	}
+	if op.decFunc == nil {
+		return nil, true
+	}
+	if x, ok := _toDec(a); ok {
+		if y, ok := _toDec(b); ok {
+			return op.decFunc(x, y), true
+		}
+	}
+	return nil, true
+}
+
+func _toDec(val any) (*decimal.Decimal, bool) {
+	switch x := val.(type) {
+	case int64:
+		return decimal.FromInt(x), true
+	case *big.Int:
+		return decimal.FromBig(x), true
+	case *decimal.Decimal:
+		return x, true
+	}
+	return nil, false
+}
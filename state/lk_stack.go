@@ -10,8 +10,11 @@ type lkStack struct {
 	state   *lkState
 	closure *lkClosure
 	varargs []any
-	openuvs map[int]*any
+	openuvs map[int]*upvalue
 	pc      int
+	/* line hook: last traced line and pc */
+	hookLine int
+	hookPC   int
 	/* linked list */
 	prev *lkStack
 }
@@ -26,9 +29,18 @@ func newLuaStack(size int, state *lkState) *lkStack {
 
 func (self *lkStack) check(n int) {
 	free := len(self.slots) - self.top
+	if free >= n {
+		return
+	}
+	old := cap(self.slots)
 	for i := free; i < n; i++ {
 		self.slots = append(self.slots, nil)
 	}
+	if cap(self.slots) != old { /* moved, open upvalues follow */
+		for i, uv := range self.openuvs {
+			uv.val = &self.slots[i]
+		}
+	}
 }
 
 func (self *lkStack) push(val any) {
@@ -99,7 +111,7 @@ func (self *lkStack) get(idx int) any {
 		if c == nil || uvIdx >= len(c.upVals) {
 			return nil
 		}
-		return *(c.upVals[uvIdx])
+		return *(c.upVals[uvIdx].val)
 	}
 
 	if idx == LK_REGISTRYINDEX {
@@ -118,7 +130,7 @@ func (self *lkStack) set(idx int, val any) {
 		uvIdx := LK_REGISTRYINDEX - idx - 1
 		c := self.closure
 		if c != nil && uvIdx < len(c.upVals) {
-			c.upVals[uvIdx] = &val
+			*(c.upVals[uvIdx].val) = val
 		}
 		return
 	}
@@ -0,0 +1,48 @@
+package state
+
+// max number of released call frames kept per state
+const maxPooledStacks = 64
+
+// acquireStack returns a call frame with `size` slots,
+// reusing a released one when possible.
+func (self *lkState) acquireStack(size int) *lkStack {
+	n := len(self.stackPool)
+	if n == 0 {
+		return newLuaStack(size, self)
+	}
+
+	stack := self.stackPool[n-1]
+	self.stackPool[n-1] = nil
+	self.stackPool = self.stackPool[:n-1]
+	if cap(stack.slots) >= size {
+		stack.slots = stack.slots[:size]
+	} else {
+		stack.slots = make([]any, size)
+	}
+	return stack
+}
+
+// releaseStack clears a finished call frame and keeps it for later calls.
+// Upvalues still open on it are closed first, so closures don't see the reuse.
+func (self *lkState) releaseStack(stack *lkStack) {
+	if len(self.stackPool) >= maxPooledStacks {
+		return
+	}
+
+	for _, uv := range stack.openuvs {
+		val := *uv.val
+		uv.val = &val
+	}
+	for i := range stack.slots {
+		stack.slots[i] = nil
+	}
+	stack.top = 0
+	stack.closure = nil
+	stack.varargs = nil
+	stack.openuvs = nil
+	stack.pc = 0
+	stack.hookLine = 0
+	stack.hookPC = 0
+	stack.prev = nil
+	self.stackPool = append(self.stackPool, stack)
+}
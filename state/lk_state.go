@@ -9,6 +9,20 @@ type lkState struct {
 	coStatus LkStatus
 	coCaller *lkState
 	coChan   chan int
+	// values local to this thread, copied from the creator
+	locals *lkTable
+	// libs with shutdown hooks, in the order they were opened.
+	// Only set on the main thread.
+	opened []string
+	// placeholders of libs not opened yet -> lib name.
+	// Only set on the main thread.
+	lazy map[*lkTable]string
+	/* released call frames */
+	stackPool []*lkStack
+	/* debug */
+	hook   DebugHook
+	inHook bool
+	prof   *profiler
 }
 
 func New() LkState {
@@ -27,6 +41,10 @@ func (self *lkState) isMainThread() bool {
 	return self.registry.get(LK_RIDX_MAINTHREAD) == self
 }
 
+func (self *lkState) mainThread() *lkState {
+	return self.registry.get(LK_RIDX_MAINTHREAD).(*lkState)
+}
+
 func (self *lkState) pushLuaStack(stack *lkStack) {
 	stack.prev = self.stack
 	self.stack = stack
@@ -2,8 +2,12 @@ package state
 
 import (
 	"math"
+	"math/big"
 	"strconv"
+	"strings"
 
+	"github.com/lollipopkit/lk/consts"
+	"github.com/lollipopkit/lk/decimal"
 	. "github.com/lollipopkit/lk/json"
 	"github.com/lollipopkit/lk/utils"
 )
@@ -37,6 +41,9 @@ func (self *lkTable) String() (string, error) {
 }
 
 func (t *lkTable) Json() any {
+	if t.isSecret() {
+		return consts.Redacted
+	}
 	tb := t.copy()
 	if len(tb._map) == 0 {
 		for i := range tb.arr {
@@ -60,6 +67,11 @@ func (t *lkTable) Json() any {
 	return tb._map
 }
 
+func (self *lkTable) isSecret() bool {
+	_, ok := self._map[consts.SecretField].(*lkClosure)
+	return ok
+}
+
 func (self *lkTable) combine(t *lkTable) {
 	if t == nil {
 		return
@@ -101,11 +113,55 @@ func (self *lkTable) get(key any) any {
 	return self._map[key]
 }
 
+// bigKey is the key of ints out of int64 range, bigints or integral floats:
+// their decimal digits, so different bigints never share a slot
+type bigKey string
+
+// decKey is the key of decimals equal to no int or float:
+// their digits without trailing zeros, so 0.10d and 0.1d share a slot
+type decKey string
+
+// _floatToInteger returns the key of a num in tables: int64 if it fits,
+// so equal nums share a slot, and an exact bigKey beyond int64.
+// Decimals equal to an int or float share its slot, others get a decKey.
 func _floatToInteger(key any) any {
+	switch x := key.(type) {
+	case *big.Int:
+		if x.IsInt64() {
+			return x.Int64()
+		}
+		return bigKey(x.String())
+	case *decimal.Decimal:
+		r := x.Rat()
+		if r.IsInt() {
+			return _floatToInteger(new(big.Int).Set(r.Num()))
+		}
+		if f, exact := r.Float64(); exact {
+			return f
+		}
+		return decKey(strings.TrimRight(x.String(), "0"))
+	}
 	if f, ok := key.(float64); ok {
 		if i, ok := utils.FloatToInteger(f); ok {
 			return i
 		}
+		if !math.IsInf(f, 0) && f == math.Trunc(f) {
+			n, _ := new(big.Float).SetFloat64(f).Int(nil)
+			return bigKey(n.String())
+		}
+	}
+	return key
+}
+
+// _keyValue turns a key back into the value seen by scripts
+func _keyValue(key any) any {
+	switch k := key.(type) {
+	case bigKey:
+		n, _ := new(big.Int).SetString(string(k), 10)
+		return n
+	case decKey:
+		d, _ := decimal.Parse(string(k))
+		return d
 	}
 	return key
 }
@@ -170,6 +226,9 @@ func (self *lkTable) _expandArray() {
 }
 
 func (self *lkTable) nextKey(key any) any {
+	if key != nil {
+		key = _floatToInteger(key)
+	}
 	if self.keys == nil || (key == nil && self.changed) {
 		self.initKeys()
 		self.changed = false
@@ -188,7 +247,7 @@ func (self *lkTable) nextKey(key any) any {
 		nextKey = self.keys[intKey]
 	}
 
-	return nextKey
+	return _keyValue(nextKey)
 }
 
 func (self *lkTable) initKeys() {
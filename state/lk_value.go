@@ -2,8 +2,10 @@ package state
 
 import (
 	"fmt"
+	"math/big"
 
 	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/decimal"
 	"github.com/lollipopkit/lk/utils"
 )
 
@@ -13,7 +15,7 @@ func typeOf(val any) LkType {
 		return LK_TNIL
 	case bool:
 		return LK_TBOOLEAN
-	case int64, float64, int, float32:
+	case int64, float64, int, float32, *big.Int, *decimal.Decimal:
 		return LK_TNUMBER
 	case string:
 		return LK_TSTRING
@@ -50,6 +52,10 @@ func convertToFloat(val any) (float64, bool) {
 		return x, true
 	case float32:
 		return float64(x), true
+	case *big.Int:
+		return _bigToFloat(x), true
+	case *decimal.Decimal:
+		return x.Float64(), true
 	case string:
 		return utils.ParseFloat(x)
 	default:
@@ -0,0 +1,168 @@
+package state
+
+import (
+	"math"
+	"math/big"
+	"testing"
+	"testing/quick"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// Algebraic laws of the operators, checked on random operands.
+// Keep them passing when touching api_arith.go or api_compare.go.
+
+func arith(op ArithOp, a, b any) any {
+	ls := New().(*lkState)
+	ls.stack.push(a)
+	ls.stack.push(b)
+	ls.Arith(op)
+	return ls.stack.pop()
+}
+
+// same is ==, but NaN is the same as NaN, and bigints are compared by value
+func same(a, b any) bool {
+	if x, ok := a.(*big.Int); ok {
+		y, ok := b.(*big.Int)
+		return ok && x.Cmp(y) == 0
+	}
+	x, okx := a.(float64)
+	y, oky := b.(float64)
+	if okx && oky && math.IsNaN(x) && math.IsNaN(y) {
+		return true
+	}
+	return a == b
+}
+
+// exact is 2^53, beyond it int64 -> float64 loses precision
+const exact = 1 << 53
+
+func check(t *testing.T, f any) {
+	t.Helper()
+	if err := quick.Check(f, nil); err != nil {
+		t.Error(err)
+	}
+}
+
+func TestAddMulCommutative(t *testing.T) {
+	for _, op := range []ArithOp{LK_OPADD, LK_OPMUL} {
+		check(t, func(a, b int64) bool {
+			return same(arith(op, a, b), arith(op, b, a))
+		})
+		check(t, func(a, b float64) bool {
+			return same(arith(op, a, b), arith(op, b, a))
+		})
+		check(t, func(a int64, b float64) bool {
+			return same(arith(op, a, b), arith(op, b, a))
+		})
+	}
+}
+
+// int results are exact: int64 if they fit, bigints if not
+func TestIntArithExact(t *testing.T) {
+	bigs := map[ArithOp]func(a, b *big.Int) *big.Int{
+		LK_OPADD: badd, LK_OPSUB: bsub, LK_OPMUL: bmul,
+	}
+	for op, f := range bigs {
+		check(t, func(a, b int64) bool {
+			want := f(big.NewInt(a), big.NewInt(b))
+			switch r := arith(op, a, b).(type) {
+			case int64:
+				return want.IsInt64() && want.Int64() == r
+			case *big.Int:
+				return !want.IsInt64() && want.Cmp(r) == 0
+			}
+			return false
+		})
+	}
+}
+
+// bigint results go back to int64 once they fit
+func TestBigIntDemote(t *testing.T) {
+	check(t, func(a int64) bool {
+		a = a&math.MaxInt64 | 1
+		n := arith(LK_OPADD, int64(math.MaxInt64), a)
+		if _, ok := n.(*big.Int); !ok {
+			return false
+		}
+		return arith(LK_OPSUB, n, a) == int64(math.MaxInt64)
+	})
+	ls := New().(*lkState)
+	ls.stack.push(int64(math.MinInt64))
+	ls.Arith(LK_OPUNM)
+	n, ok := ls.stack.pop().(*big.Int)
+	if !ok || n.Cmp(new(big.Int).Lsh(big1, 63)) != 0 {
+		t.Errorf("-minint = %v", n)
+	}
+}
+
+// a == (a ~/ b) * b + a % b
+func TestFloorDivMod(t *testing.T) {
+	check(t, func(a, b int64) bool {
+		if b == 0 {
+			return true
+		}
+		q := arith(LK_OPIDIV, a, b)
+		r := arith(LK_OPMOD, a, b)
+		return arith(LK_OPADD, arith(LK_OPMUL, q, b), r) == a
+	})
+	// the sign of remainder follows divisor
+	check(t, func(a, b int64) bool {
+		if b == 0 {
+			return true
+		}
+		r := arith(LK_OPMOD, a, b).(int64)
+		return r == 0 || (r < 0) == (b < 0)
+	})
+}
+
+// s + '' == s, and + on strs is associative
+func TestConcat(t *testing.T) {
+	check(t, func(s string) bool {
+		if _, ok := convertToFloat(s); ok {
+			return true /* numeric strs are added as numbers */
+		}
+		return arith(LK_OPADD, s, "") == s && arith(LK_OPADD, "", s) == s
+	})
+	check(t, func(a, b, c string) bool {
+		a, b, c = "a"+a, "b"+b, "c"+c /* keep them non numeric */
+		return arith(LK_OPADD, arith(LK_OPADD, a, b), c) ==
+			arith(LK_OPADD, a, arith(LK_OPADD, b, c))
+	})
+}
+
+// exactly one of a < b, a == b, b < a
+func TestCompareTotal(t *testing.T) {
+	total := func(a, b any) bool {
+		n := 0
+		for _, ok := range []bool{_lt(a, b, nil), _eq(a, b, nil), _lt(b, a, nil)} {
+			if ok {
+				n++
+			}
+		}
+		return n == 1 && _le(a, b, nil) == (_lt(a, b, nil) || _eq(a, b, nil))
+	}
+	check(t, func(a, b int64) bool { return total(a, b) })
+	check(t, func(a, b string) bool { return total(a, b) })
+	check(t, func(a, b float64) bool {
+		if math.IsNaN(a) || math.IsNaN(b) {
+			return true
+		}
+		return total(a, b)
+	})
+	check(t, func(a int64, b float64) bool {
+		n := arith(LK_OPMUL, a&math.MaxInt64|1<<62, int64(4))
+		return total(n, a) && total(n, b) && total(n, arith(LK_OPADD, n, int64(1)))
+	})
+	check(t, func(a int64, b float64) bool {
+		a %= exact
+		return total(a, b) && total(a, float64(a))
+	})
+}
+
+func TestIntFloatEq(t *testing.T) {
+	check(t, func(a int64) bool {
+		a %= exact
+		return _eq(a, float64(a), nil) && _eq(float64(a), a, nil)
+	})
+}
@@ -0,0 +1,67 @@
+package stdlib
+
+import (
+	"fmt"
+	"sync"
+
+	"github.com/lollipopkit/gommon/log"
+	. "github.com/lollipopkit/lk/api"
+)
+
+// DenyDeprecated makes calls of deprecated fns raise errors
+// instead of warnings. The cli sets it by `-deny deprecated`.
+var DenyDeprecated bool
+
+var (
+	// `name source:line` already warned
+	deprecatedWarned   = map[string]bool{}
+	deprecatedWarnedMu sync.Mutex
+)
+
+// Deprecated wraps f, its calls print a warning with hint
+// once per call site, eg: `use rand.float instead`
+func Deprecated(name, hint string, f GoFunction) GoFunction {
+	return func(ls LkState) int {
+		_warnDeprecated(ls, name, hint)
+		return f(ls)
+	}
+}
+
+func _warnDeprecated(ls LkState, name, hint string) {
+	msg := name + " is deprecated"
+	if hint != "" {
+		msg += ", " + hint
+	}
+	if DenyDeprecated {
+		ls.Error2("%s", msg)
+	}
+	site := "?"
+	if info, ok := ls.GetInfo(0); ok {
+		site = fmt.Sprintf("%s:%d", info.Source, info.Line)
+	}
+	key := name + " " + site
+	deprecatedWarnedMu.Lock()
+	warned := deprecatedWarned[key]
+	deprecatedWarned[key] = true
+	deprecatedWarnedMu.Unlock()
+	if !warned {
+		log.Yellow("[deprecated] %s: %s", site, msg)
+	}
+}
+
+// deprecate (name, fn [, hint])
+// return fn which warns like deprecated builtin fns when called
+func baseDeprecate(ls LkState) int {
+	name := ls.CheckString(1)
+	ls.CheckType(2, LK_TFUNCTION)
+	hint := ls.OptString(3, "")
+	ls.SetTop(2)
+	ls.PushGoClosure(func(ls LkState) int {
+		_warnDeprecated(ls, name, hint)
+		ls.PushValue(LkUpvalueIndex(1))
+		ls.Insert(1)
+		ls.Call(ls.GetTop()-1, LK_MULTRET)
+		return ls.GetTop()
+	}, 1)
+	return 1
+}
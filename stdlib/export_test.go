@@ -0,0 +1,6 @@
+//go:build !lk_minimal
+
+package stdlib
+
+/* for tests in stdlib_test, which can import state */
+var ServeLk = _serveLk
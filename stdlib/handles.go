@@ -0,0 +1,75 @@
+package stdlib
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// TrackHandles records where udp sockets, kv stores and processes
+// are opened, so ones never closed can be listed by Leaks.
+// The cli sets it by `-leaks`.
+var TrackHandles bool
+
+type openHandle struct {
+	seq  int64
+	name string
+	// `source:line` of lk functions, innermost first
+	trace []string
+}
+
+var (
+	openHandles   = map[string]openHandle{}
+	openHandlesMu sync.Mutex
+	openHandleSeq int64
+)
+
+// _trackOpen records a handle opened by the calling lk code
+func _trackOpen(ls LkState, kind string, id any) {
+	if !TrackHandles {
+		return
+	}
+	trace := []string{}
+	for level := 0; ; level++ {
+		info, ok := ls.GetInfo(level)
+		if !ok {
+			break
+		}
+		trace = append(trace, fmt.Sprintf("%s:%d", info.Source, info.Line))
+	}
+	key := fmt.Sprintf("%s %v", kind, id)
+	openHandlesMu.Lock()
+	openHandleSeq++
+	openHandles[key] = openHandle{openHandleSeq, key, trace}
+	openHandlesMu.Unlock()
+}
+
+func _trackClose(kind string, id any) {
+	if !TrackHandles {
+		return
+	}
+	openHandlesMu.Lock()
+	delete(openHandles, fmt.Sprintf("%s %v", kind, id))
+	openHandlesMu.Unlock()
+}
+
+// Leaks describes handles not closed yet, in the order they were opened
+func Leaks() []string {
+	openHandlesMu.Lock()
+	handles := make([]openHandle, 0, len(openHandles))
+	for _, h := range openHandles {
+		handles = append(handles, h)
+	}
+	openHandlesMu.Unlock()
+	sort.Slice(handles, func(i, j int) bool {
+		return handles[i].seq < handles[j].seq
+	})
+	leaks := make([]string, len(handles))
+	for i, h := range handles {
+		leaks[i] = h.name + " opened at\n\t" + strings.Join(h.trace, "\n\t")
+	}
+	return leaks
+}
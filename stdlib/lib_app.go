@@ -0,0 +1,239 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"context"
+	"fmt"
+	"net/http"
+	"os"
+	"os/signal"
+	"syscall"
+	"time"
+
+	"github.com/lollipopkit/gommon/log"
+	. "github.com/lollipopkit/lk/api"
+)
+
+var appLib = map[string]GoFunction{
+	"run":   appRun,
+	"defer": appDefer,
+	"stop":  appStop,
+}
+
+const (
+	// registry key of fns added by app.defer
+	LK_APP_DEFER_TABLE = "_APP_DEFER"
+	appHealthPath      = "/healthz"
+)
+
+// app.stop() wakes app.run from any script code
+var appStopCh = make(chan string, 1)
+
+// a liveness request, answered on the main goroutine
+type appHealthReq struct {
+	reply chan appHealth
+}
+
+type appHealth struct {
+	ok  bool
+	msg string
+}
+
+func OpenAppLib(ls LkState) int {
+	ls.NewLib(appLib)
+	return 1
+}
+
+// app.defer(fn)
+// fn runs when app.run shuts down, the last added runs first
+func appDefer(ls LkState) int {
+	ls.CheckType(1, LK_TFUNCTION)
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_APP_DEFER_TABLE)
+	n := ls.Len2(-1)
+	ls.PushValue(1)
+	ls.SetI(-2, n)
+	ls.Pop(1)
+	return 0
+}
+
+// app.stop([reason])
+// asks app.run to shut down after the current callback returns
+func appStop(ls LkState) int {
+	select {
+	case appStopCh <- ls.OptString(1, "stop"):
+	default:
+	}
+	return 0
+}
+
+// app.run(opts)
+// opts:
+//   - on_start: fn(), an error aborts the app
+//   - tick: fn(), called every `interval` ms (default 1000)
+//   - max_failures: consecutive tick errors before giving up (default 3)
+//   - health: fn() => ok, msg. Without it, the app is healthy while ticks pass
+//   - health_addr: serves `GET /healthz` on it, eg: `:8081`
+//   - on_shutdown: fn(reason), reason is the signal name, `stop` or the error
+//   - drain_timeout: ms to wait for the health server and processes
+//     started by os.spawn to finish on shutdown, then they are killed (default 3000)
+//
+// blocks until SIGINT / SIGTERM, app.stop() or too many tick failures.
+// Every callback runs on the calling goroutine.
+// return err
+func appRun(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	opts := ls.AbsIndex(1)
+	interval := _optIntField(ls, opts, "interval", 1000)
+	maxFailures := _optIntField(ls, opts, "max_failures", 3)
+	drain := time.Duration(_optIntField(ls, opts, "drain_timeout", 3000)) * time.Millisecond
+
+	sigs := make(chan os.Signal, 1)
+	signal.Notify(sigs, syscall.SIGINT, syscall.SIGTERM)
+	defer signal.Stop(sigs)
+
+	healthReqs := make(chan appHealthReq)
+	var srv *http.Server
+	if ls.GetField(opts, "health_addr") == LK_TSTRING {
+		srv = _appHealthServer(ls.ToString(-1), healthReqs)
+	}
+	ls.Pop(1)
+
+	var runErr error
+	reason := ""
+	if err := _appCall(ls, opts, "on_start", 0); err != nil {
+		runErr = fmt.Errorf("on_start: %v", err)
+		reason = runErr.Error()
+	}
+
+	var ticks <-chan time.Time
+	hasTick := ls.GetField(opts, "tick") == LK_TFUNCTION
+	ls.Pop(1)
+	if hasTick && interval > 0 {
+		ticker := time.NewTicker(time.Duration(interval) * time.Millisecond)
+		defer ticker.Stop()
+		ticks = ticker.C
+	}
+
+	failures := 0
+	var lastErr error
+	for reason == "" {
+		select {
+		case sig := <-sigs:
+			reason = sig.String()
+		case reason = <-appStopCh:
+		case <-ticks:
+			if lastErr = _appCall(ls, opts, "tick", 0); lastErr != nil {
+				failures++
+				log.Red("[app] tick failed (%d/%d): %v", failures, maxFailures, lastErr)
+				if failures >= maxFailures {
+					runErr = fmt.Errorf("tick: %v", lastErr)
+					reason = runErr.Error()
+				}
+			} else {
+				failures = 0
+			}
+		case req := <-healthReqs:
+			req.reply <- _appHealth(ls, opts, lastErr)
+		}
+	}
+
+	if srv != nil {
+		ctx, cancel := context.WithTimeout(context.Background(), drain)
+		srv.Shutdown(ctx)
+		cancel()
+	}
+	ls.PushString(reason)
+	if err := _appCall(ls, opts, "on_shutdown", 1); err != nil {
+		log.Red("[app] on_shutdown failed: %v", err)
+	}
+	_appRunDeferred(ls)
+	if n := _drainProcs(drain); n > 0 {
+		log.Yellow("[app] killed %d process(es) still running", n)
+	}
+
+	if runErr != nil {
+		ls.PushString(runErr.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// _appCall calls opts[name] if it's a fn, with n args on the stack top.
+// The stack is restored afterwards.
+func _appCall(ls LkState, opts int, name string, n int) error {
+	top := ls.GetTop() - n
+	defer ls.SetTop(top)
+	if ls.GetField(opts, name) != LK_TFUNCTION {
+		return nil
+	}
+	ls.Insert(top + 1)
+	if ls.PCall(n, 0, 0) != LK_OK {
+		return fmt.Errorf("%s", ls.ToString(-1))
+	}
+	return nil
+}
+
+func _appHealth(ls LkState, opts int, lastErr error) appHealth {
+	top := ls.GetTop()
+	defer ls.SetTop(top)
+	if ls.GetField(opts, "health") != LK_TFUNCTION {
+		if lastErr != nil {
+			return appHealth{false, lastErr.Error()}
+		}
+		return appHealth{true, "ok"}
+	}
+	if ls.PCall(0, 2, 0) != LK_OK {
+		return appHealth{false, ls.ToString(-1)}
+	}
+	h := appHealth{ls.ToBoolean(-2), "ok"}
+	if ls.IsString(-1) {
+		h.msg = ls.ToString(-1)
+	} else if !h.ok {
+		h.msg = "unhealthy"
+	}
+	return h
+}
+
+func _appHealthServer(addr string, reqs chan appHealthReq) *http.Server {
+	mux := http.NewServeMux()
+	mux.HandleFunc(appHealthPath, func(w http.ResponseWriter, r *http.Request) {
+		req := appHealthReq{reply: make(chan appHealth, 1)}
+		var h appHealth
+		select {
+		case reqs <- req:
+			h = <-req.reply
+		case <-time.After(5 * time.Second):
+			/* main goroutine is stuck in a callback */
+			h = appHealth{false, "busy"}
+		}
+		if !h.ok {
+			w.WriteHeader(http.StatusServiceUnavailable)
+		}
+		w.Write([]byte(h.msg))
+	})
+	srv := &http.Server{Addr: addr, Handler: mux}
+	go func() {
+		if err := srv.ListenAndServe(); err != nil && err != http.ErrServerClosed {
+			log.Red("[app] health server: %v", err)
+		}
+	}()
+	return srv
+}
+
+// _appRunDeferred runs fns of app.defer in reverse order, then clears them
+func _appRunDeferred(ls LkState) {
+	top := ls.GetTop()
+	defer ls.SetTop(top)
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_APP_DEFER_TABLE)
+	for i := ls.Len2(-1) - 1; i >= 0; i-- {
+		ls.GetI(-1, i)
+		if ls.PCall(0, 0, 0) != LK_OK {
+			log.Red("[app] deferred fn failed: %s", ls.ToString(-1))
+			ls.Pop(1)
+		}
+	}
+	ls.PushNil()
+	ls.SetField(LK_REGISTRYINDEX, LK_APP_DEFER_TABLE)
+}
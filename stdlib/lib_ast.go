@@ -0,0 +1,385 @@
+package stdlib
+
+import (
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/compiler/ast"
+	"github.com/lollipopkit/lk/compiler/lexer"
+	"github.com/lollipopkit/lk/compiler/parser"
+	"github.com/lollipopkit/lk/compiler/printer"
+)
+
+var astLib = map[string]GoFunction{
+	"parse":     astParse,
+	"to_source": astToSource,
+}
+
+func OpenAstLib(ls LkState) int {
+	ls.NewLib(astLib)
+	return 1
+}
+
+// ast.parse (source [, chunkname])
+// returns the program as nested tables, each node has a `type` field
+func astParse(ls LkState) int {
+	src := ls.CheckString(1)
+	name := ls.OptString(2, "ast")
+	block, err := _parseSource(src, name)
+	if err != nil {
+		ls.PushNil()
+		ls.PushFString("%v", err)
+		return 2
+	}
+	pushValue(ls, _astToMap(block))
+	ls.PushNil()
+	return 2
+}
+
+// ast.to_source (node)
+// node can be a Block, a statement or an expression
+func astToSource(ls LkState) int {
+	node := _astNode(ls, 1)
+	src, err := _printNode(node)
+	if err != nil {
+		return ls.Error2("ast: %v", err)
+	}
+	ls.PushString(src)
+	return 1
+}
+
+func _parseSource(src, name string) (block *ast.Block, err any) {
+	defer func() {
+		err = recover()
+	}()
+	block = parser.Parse(src, name)
+	return
+}
+
+func _printNode(node any) (src string, err any) {
+	defer func() {
+		err = recover()
+	}()
+	src = printer.PrintNode(node)
+	return
+}
+
+/* ast -> table */
+
+func _astToMap(node any) any {
+	switch n := node.(type) {
+	case nil:
+		return nil
+	case *ast.Block:
+		return lkMap{"type": "Block", "line": n.LastLine,
+			"stats": _astStatsToList(n.Stats), "rets": _astExpsToList(n.RetExps)}
+	case *ast.EmptyStat:
+		return lkMap{"type": "Empty"}
+	case *ast.BreakStat:
+		return lkMap{"type": "Break", "line": n.Line}
+	case *ast.IfStat:
+		blocks := make([]any, len(n.Blocks))
+		for i := range n.Blocks {
+			blocks[i] = _astToMap(n.Blocks[i])
+		}
+		return lkMap{"type": "If", "conds": _astExpsToList(n.Exps), "blocks": blocks}
+	case *ast.WhileStat:
+		return lkMap{"type": "While", "cond": _astToMap(n.Exp), "block": _astToMap(n.Block)}
+	case *ast.ForNumStat:
+		return lkMap{"type": "ForNum", "line": n.LineOfFor, "var": n.VarName,
+			"init": _astToMap(n.InitExp), "limit": _astToMap(n.LimitExp),
+			"step": _astToMap(n.StepExp), "block": _astToMap(n.Block)}
+	case *ast.ForInStat:
+		return lkMap{"type": "ForIn", "line": n.LineOfDo, "names": n.NameList,
+			"exps": _astExpsToList(n.ExpList), "block": _astToMap(n.Block)}
+	case *ast.AssignStat:
+		return lkMap{"type": "Assign", "line": n.LastLine,
+			"vars": _astExpsToList(n.VarList), "exps": _astExpsToList(n.ExpList)}
+	case *ast.LocalVarDeclStat:
+		return lkMap{"type": "Local", "line": n.LastLine,
+			"names": n.NameList, "exps": _astExpsToList(n.ExpList)}
+	case *ast.LocalFuncDefStat:
+		return lkMap{"type": "LocalFunc", "name": n.Name, "func": _astToMap(n.Exp)}
+	case *ast.NilExp:
+		return lkMap{"type": "Nil", "line": n.Line}
+	case *ast.TrueExp:
+		return lkMap{"type": "True", "line": n.Line}
+	case *ast.FalseExp:
+		return lkMap{"type": "False", "line": n.Line}
+	case *ast.VarargExp:
+		return lkMap{"type": "Vararg", "line": n.Line}
+	case *ast.IntegerExp:
+		return lkMap{"type": "Integer", "line": n.Line, "value": n.Int}
+	case *ast.FloatExp:
+		return lkMap{"type": "Float", "line": n.Line, "value": n.Float}
+	case *ast.DecimalExp:
+		return lkMap{"type": "Decimal", "line": n.Line, "value": n.Dec}
+	case *ast.StringExp:
+		return lkMap{"type": "String", "line": n.Line, "value": n.Str}
+	case *ast.InterpExp:
+		return lkMap{"type": "Interp", "line": n.Line, "lits": n.Lits, "exps": _astExpsToList(n.Exps)}
+	case *ast.UnopExp:
+		return lkMap{"type": "Unop", "line": n.Line,
+			"op": lexer.TokenName(n.Op), "exp": _astToMap(n.Unop)}
+	case *ast.BinopExp:
+		return lkMap{"type": "Binop", "line": n.Line, "op": lexer.TokenName(n.Op),
+			"left": _astToMap(n.Left), "right": _astToMap(n.Right)}
+	case *ast.TernaryExp:
+		return lkMap{"type": "Ternary", "line": n.Line, "cond": _astToMap(n.Cond),
+			"yes": _astToMap(n.True), "no": _astToMap(n.False)}
+	case *ast.TableConstructorExp:
+		entries := make([]any, len(n.ValExps))
+		for i := range n.ValExps {
+			entries[i] = lkMap{"key": _astToMap(n.KeyExps[i]), "val": _astToMap(n.ValExps[i])}
+		}
+		return lkMap{"type": "Table", "line": n.Line, "entries": entries}
+	case *ast.FuncDefExp:
+		return lkMap{"type": "Func", "line": n.Line, "params": n.ParList,
+			"vararg": n.IsVararg, "block": _astToMap(n.Block)}
+	case *ast.NameExp:
+		return lkMap{"type": "Name", "line": n.Line, "name": n.Name}
+	case *ast.ParensExp:
+		return lkMap{"type": "Parens", "exp": _astToMap(n.Exp)}
+	case *ast.TableAccessExp:
+		return lkMap{"type": "Index", "line": n.LastLine,
+			"obj": _astToMap(n.PrefixExp), "key": _astToMap(n.KeyExp)}
+	case *ast.FuncCallExp:
+		var method any
+		if n.NameExp != nil {
+			method = n.NameExp.Str
+		}
+		return lkMap{"type": "Call", "line": n.Line, "callee": _astToMap(n.PrefixExp),
+			"method": method, "args": _astExpsToList(n.Args)}
+	}
+	panic("ast: unknown node")
+}
+
+// nil list stays nil, eg: `rets` of a block without `rt`
+func _astExpsToList(exps []ast.Exp) any {
+	if exps == nil {
+		return nil
+	}
+	list := make([]any, len(exps))
+	for i := range exps {
+		list[i] = _astToMap(exps[i])
+	}
+	return list
+}
+
+func _astStatsToList(stats []ast.Stat) any {
+	list := make([]any, len(stats))
+	for i := range stats {
+		list[i] = _astToMap(stats[i])
+	}
+	return list
+}
+
+/* table -> ast */
+
+func _astNode(ls LkState, idx int) any {
+	idx = ls.AbsIndex(idx)
+	if !ls.IsTable(idx) {
+		ls.Error2("ast: node must be a table, got %s", ls.TypeName2(idx))
+	}
+	line := int(_astInt(ls, idx, "line"))
+	switch t := _astStr(ls, idx, "type"); t {
+	case "Block":
+		return &ast.Block{LastLine: line,
+			Stats: _astStats(ls, idx, "stats"), RetExps: _astExps(ls, idx, "rets")}
+	case "Empty":
+		return &ast.EmptyStat{}
+	case "Break":
+		return &ast.BreakStat{Line: line}
+	case "If":
+		blocks := []*ast.Block{}
+		_astEach(ls, idx, "blocks", func() {
+			blocks = append(blocks, _astBlockAt(ls, -1))
+		})
+		exps := _astExps(ls, idx, "conds")
+		if len(exps) != len(blocks) {
+			ls.Error2("ast: If has %d conds but %d blocks", len(exps), len(blocks))
+		}
+		return &ast.IfStat{Exps: exps, Blocks: blocks}
+	case "While":
+		return &ast.WhileStat{Exp: _astChild(ls, idx, "cond"), Block: _astBlock(ls, idx, "block")}
+	case "ForNum":
+		step := _astChild(ls, idx, "step")
+		if step == nil {
+			step = &ast.IntegerExp{Line: line, Int: 1}
+		}
+		return &ast.ForNumStat{LineOfFor: line, LineOfDo: line,
+			VarName: _astStr(ls, idx, "var"), InitExp: _astChild(ls, idx, "init"),
+			LimitExp: _astChild(ls, idx, "limit"), StepExp: step,
+			Block: _astBlock(ls, idx, "block")}
+	case "ForIn":
+		return &ast.ForInStat{LineOfDo: line, NameList: _astStrs(ls, idx, "names"),
+			ExpList: _astExps(ls, idx, "exps"), Block: _astBlock(ls, idx, "block")}
+	case "Assign":
+		return &ast.AssignStat{LastLine: line,
+			VarList: _astExps(ls, idx, "vars"), ExpList: _astExps(ls, idx, "exps")}
+	case "Local":
+		return &ast.LocalVarDeclStat{LastLine: line,
+			NameList: _astStrs(ls, idx, "names"), ExpList: _astExps(ls, idx, "exps")}
+	case "LocalFunc":
+		fd, ok := _astChild(ls, idx, "func").(*ast.FuncDefExp)
+		if !ok {
+			ls.Error2("ast: LocalFunc.func must be a Func")
+		}
+		return &ast.LocalFuncDefStat{Name: _astStr(ls, idx, "name"), Exp: fd}
+	case "Nil":
+		return &ast.NilExp{Line: line}
+	case "True":
+		return &ast.TrueExp{Line: line}
+	case "False":
+		return &ast.FalseExp{Line: line}
+	case "Vararg":
+		return &ast.VarargExp{Line: line}
+	case "Integer":
+		return &ast.IntegerExp{Line: line, Int: _astInt(ls, idx, "value")}
+	case "Float":
+		ls.GetField(idx, "value")
+		f := ls.ToNumber(-1)
+		ls.Pop(1)
+		return &ast.FloatExp{Line: line, Float: f}
+	case "Interp":
+		return &ast.InterpExp{Line: line, Lits: _astStrs(ls, idx, "lits"), Exps: _astExps(ls, idx, "exps")}
+	case "Decimal":
+		return &ast.DecimalExp{Line: line, Dec: _astStr(ls, idx, "value")}
+	case "String":
+		return &ast.StringExp{Line: line, Str: _astStr(ls, idx, "value")}
+	case "Unop":
+		return &ast.UnopExp{Line: line, Op: _astOp(ls, idx), Unop: _astChild(ls, idx, "exp")}
+	case "Binop":
+		return &ast.BinopExp{Line: line, Op: _astOp(ls, idx),
+			Left: _astChild(ls, idx, "left"), Right: _astChild(ls, idx, "right")}
+	case "Ternary":
+		return &ast.TernaryExp{Line: line, Cond: _astChild(ls, idx, "cond"),
+			True: _astChild(ls, idx, "yes"), False: _astChild(ls, idx, "no")}
+	case "Table":
+		tb := &ast.TableConstructorExp{Line: line, LastLine: line}
+		_astEach(ls, idx, "entries", func() {
+			tb.KeyExps = append(tb.KeyExps, _astChild(ls, -1, "key"))
+			tb.ValExps = append(tb.ValExps, _astChild(ls, -1, "val"))
+		})
+		return tb
+	case "Func":
+		return &ast.FuncDefExp{Line: line, LastLine: line,
+			ParList: _astStrs(ls, idx, "params"), IsVararg: _astBool(ls, idx, "vararg"),
+			Block: _astBlock(ls, idx, "block")}
+	case "Name":
+		return &ast.NameExp{Line: line, Name: _astStr(ls, idx, "name")}
+	case "Parens":
+		return &ast.ParensExp{Exp: _astChild(ls, idx, "exp")}
+	case "Index":
+		return &ast.TableAccessExp{LastLine: line,
+			PrefixExp: _astChild(ls, idx, "obj"), KeyExp: _astChild(ls, idx, "key")}
+	case "Call":
+		var method *ast.StringExp
+		if ls.GetField(idx, "method") != LK_TNIL {
+			method = &ast.StringExp{Line: line, Str: ls.CheckString(-1)}
+		}
+		ls.Pop(1)
+		return &ast.FuncCallExp{Line: line, LastLine: line,
+			PrefixExp: _astChild(ls, idx, "callee"), NameExp: method,
+			Args: _astExps(ls, idx, "args")}
+	default:
+		ls.Error2("ast: unknown node type '%s'", t)
+	}
+	return nil
+}
+
+// _astEach calls f with each item of list `t[key]` on the top of stack.
+// It returns false if `t[key]` is nil.
+func _astEach(ls LkState, idx int, key string, f func()) bool {
+	if ls.GetField(idx, key) == LK_TNIL {
+		ls.Pop(1)
+		return false
+	}
+	if !ls.IsTable(-1) {
+		ls.Error2("ast: field '%s' must be a list", key)
+	}
+	for i := int64(0); ls.RawGetI(-1, i) != LK_TNIL; i++ {
+		f()
+		ls.Pop(1)
+	}
+	ls.Pop(2) /* nil and list */
+	return true
+}
+
+func _astChild(ls LkState, idx int, key string) any {
+	ls.GetField(idx, key)
+	defer ls.Pop(1)
+	if ls.IsNil(-1) {
+		return nil
+	}
+	return _astNode(ls, -1)
+}
+
+func _astBlockAt(ls LkState, idx int) *ast.Block {
+	block, ok := _astNode(ls, idx).(*ast.Block)
+	if !ok {
+		ls.Error2("ast: Block expected")
+	}
+	return block
+}
+
+func _astBlock(ls LkState, idx int, key string) *ast.Block {
+	ls.GetField(idx, key)
+	defer ls.Pop(1)
+	return _astBlockAt(ls, -1)
+}
+
+func _astExps(ls LkState, idx int, key string) []ast.Exp {
+	exps := []ast.Exp{}
+	if !_astEach(ls, idx, key, func() {
+		exps = append(exps, _astNode(ls, -1))
+	}) {
+		return nil
+	}
+	return exps
+}
+
+func _astStats(ls LkState, idx int, key string) []ast.Stat {
+	stats := []ast.Stat{}
+	_astEach(ls, idx, key, func() {
+		stats = append(stats, _astNode(ls, -1))
+	})
+	return stats
+}
+
+func _astStrs(ls LkState, idx int, key string) []string {
+	strs := []string{}
+	_astEach(ls, idx, key, func() {
+		strs = append(strs, ls.CheckString(-1))
+	})
+	return strs
+}
+
+func _astStr(ls LkState, idx int, key string) string {
+	ls.GetField(idx, key)
+	defer ls.Pop(1)
+	s, ok := ls.ToStringX(-1)
+	if !ok {
+		ls.Error2("ast: field '%s' must be a str", key)
+	}
+	return s
+}
+
+func _astInt(ls LkState, idx int, key string) int64 {
+	ls.GetField(idx, key)
+	defer ls.Pop(1)
+	return ls.ToInteger(-1)
+}
+
+func _astBool(ls LkState, idx int, key string) bool {
+	ls.GetField(idx, key)
+	defer ls.Pop(1)
+	return ls.ToBoolean(-1)
+}
+
+func _astOp(ls LkState, idx int) int {
+	name := _astStr(ls, idx, "op")
+	op, ok := lexer.TokenKind(name)
+	if !ok {
+		ls.Error2("ast: unknown operator '%s'", name)
+	}
+	return op
+}
@@ -1,6 +1,10 @@
 package stdlib
 
 import (
+	"errors"
+	"fmt"
+	"math"
+	"sort"
 	"strconv"
 	"strings"
 
@@ -27,9 +31,22 @@ var baseFuncs = map[string]GoFunction{
 	"str":       baseToString,
 	"num":       baseToNumber,
 	"int":       mathToInt,
-	"json":     baseToJson,
+	"json":      baseToJson,
+	"eval":      baseEval,
+	"compile":   baseCompile,
+	"help":      baseHelp,
+	"deprecate": baseDeprecate,
 }
 
+// secret(value) wraps value, secret.expose(s) unwraps it
+var secretFuncs = map[string]GoFunction{
+	"__call": secretNew,
+	"expose": secretExpose,
+}
+
+// Embedders can set it to false to disable `eval` and `compile`
+var EvalEnabled = true
+
 // lua-5.3.4/src/lbaselib.c#luaopen_base()
 func OpenBaseLib(ls LkState) int {
 	/* open lib into global table */
@@ -41,14 +58,24 @@ func OpenBaseLib(ls LkState) int {
 	/* set global _VERSION */
 	ls.PushString(consts.VERSION)
 	ls.SetField(-2, "_VERSION")
+	/* set global secret */
+	ls.NewLib(secretFuncs)
+	ls.SetField(-2, "secret")
 	return 1
 }
 
 func baseNew(ls LkState) int {
 	ls.CheckType(1, LK_TTABLE)
+	pushNew(ls, 1)
+	return 1
+}
+
+// pushNew pushes an instance of class at idx
+func pushNew(ls LkState, idx int) {
+	idx = ls.AbsIndex(idx)
 	ls.CreateTable(0, 0)
 	ls.PushNil()
-	for ls.Next(1) {
+	for ls.Next(idx) {
 		ls.PushValue(-2)
 		if ls.IsTable(-2) {
 			ls.PushCopyTable(-2)
@@ -58,7 +85,6 @@ func baseNew(ls LkState) int {
 		ls.SetTable(-5)
 		ls.Pop(1)
 	}
-	return 1
 }
 
 // int (x)
@@ -189,7 +215,7 @@ func baseLoad(ls LkState) int {
 		chunkname := ls.OptString(2, chunk)
 		status = ls.Load([]byte(chunk), chunkname, mode)
 	} else { /* loading from a reader function */
-		panic("loading from a reader function") // todo
+		return ls.Error2("load: chunk must be a str")
 	}
 	return loadAux(ls, status, env)
 }
@@ -198,7 +224,7 @@ func baseLoad(ls LkState) int {
 func loadAux(ls LkState, status LkStatus, envIdx int) int {
 	if status == LK_OK {
 		if envIdx != 0 { /* 'env' parameter? */
-			panic("todo!")
+			return ls.Error2("load: env is not supported")
 		}
 		return 1
 	} else { /* error (message is on top of the stack) */
@@ -295,7 +321,11 @@ func baseToNumber(ls LkState) int {
 	return 1
 }
 
-// convert (json)str to table
+// json (str [, class])
+// convert (json)str to table.
+// If class is given, the result is an instance of it: fields are checked
+// against the type of class defaults, missing ones take the defaults.
+// On mismatch, it returns nil and a list of errors like `$.a.b: ...`
 func baseToJson(ls LkState) int {
 	str := ls.CheckString(1)
 	var item any
@@ -304,7 +334,204 @@ func baseToJson(ls LkState) int {
 		ls.PushString(err.Error())
 		return 2
 	}
-	pushValue(ls, item)
+	if ls.IsNoneOrNil(2) {
+		pushValue(ls, item)
+		ls.PushNil()
+		return 2
+	}
+
+	ls.CheckType(2, LK_TTABLE)
+	errs := []string{}
+	_decodeInto(ls, 2, item, "$", &errs)
+	if len(errs) > 0 {
+		ls.PushNil()
+		pushList(ls, errs)
+		return 2
+	}
 	ls.PushNil()
 	return 2
 }
+
+// _decodeInto pushes a copy of class at `idx` filled with obj
+func _decodeInto(ls LkState, idx int, item any, path string, errs *[]string) {
+	obj, ok := item.(map[string]any)
+	if !ok {
+		*errs = append(*errs, fmt.Sprintf("%s: expect object, got %s", path, _jsonType(item)))
+		ls.PushNil()
+		return
+	}
+	pushNew(ls, idx)
+	inst := ls.GetTop()
+	keys := make([]string, 0, len(obj))
+	for key := range obj {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys) /* stable error order */
+	for _, key := range keys {
+		val := obj[key]
+		fieldPath := path + "." + key
+		switch ls.GetField(idx, key) {
+		case LK_TNIL:
+			*errs = append(*errs, fieldPath+": unknown field")
+		case LK_TFUNCTION:
+			*errs = append(*errs, fieldPath+": can't override method")
+		case LK_TTABLE:
+			/* a class with fields is a nested record, others accept any table */
+			if _isRecord(ls, -1) {
+				_decodeInto(ls, ls.GetTop(), val, fieldPath, errs)
+			} else if _, ok := val.(map[string]any); ok || _isJsonList(val) {
+				pushValue(ls, val)
+			} else {
+				*errs = append(*errs, fmt.Sprintf("%s: expect table, got %s", fieldPath, _jsonType(val)))
+				ls.PushNil()
+			}
+			ls.SetField(inst, key)
+		default:
+			if v, err := _coerceJson(ls, -1, val); err != "" {
+				*errs = append(*errs, fieldPath+": "+err)
+			} else {
+				pushValue(ls, v)
+				ls.SetField(inst, key)
+			}
+		}
+		ls.Pop(1) /* class field */
+	}
+}
+
+// _isRecord reports whether table at idx has non-function fields
+func _isRecord(ls LkState, idx int) bool {
+	idx = ls.AbsIndex(idx)
+	ls.PushNil()
+	for ls.Next(idx) {
+		if ls.Type(-2) == LK_TSTRING && !ls.IsFunction(-1) && !strings.HasPrefix(ls.ToString(-2), "__") {
+			ls.Pop(2)
+			return true
+		}
+		ls.Pop(1)
+	}
+	return false
+}
+
+// _coerceJson checks val against the type of default at idx
+func _coerceJson(ls LkState, idx int, val any) (any, string) {
+	switch ls.Type(idx) {
+	case LK_TNUMBER:
+		f, ok := val.(float64)
+		if !ok {
+			break
+		}
+		if ls.IsInteger(idx) {
+			if f != math.Trunc(f) {
+				return nil, fmt.Sprintf("expect int, got %v", f)
+			}
+			return int64(f), ""
+		}
+		return f, ""
+	case LK_TSTRING:
+		if s, ok := val.(string); ok {
+			return s, ""
+		}
+	case LK_TBOOLEAN:
+		if b, ok := val.(bool); ok {
+			return b, ""
+		}
+	default:
+		return val, ""
+	}
+	return nil, fmt.Sprintf("expect %s, got %s", ls.TypeName2(idx), _jsonType(val))
+}
+
+func _isJsonList(val any) bool {
+	_, ok := val.([]any)
+	return ok
+}
+
+func _jsonType(val any) string {
+	switch val.(type) {
+	case nil:
+		return "null"
+	case bool:
+		return "bool"
+	case float64:
+		return "num"
+	case string:
+		return "str"
+	case []any:
+		return "list"
+	}
+	return "object"
+}
+
+// eval (source, ···)
+// compiles source and calls it with the rest args, returns its results
+func baseEval(ls LkState) int {
+	src := ls.CheckString(1)
+	if !EvalEnabled {
+		return ls.Error2("eval is disabled")
+	}
+	if err := _compileChunk(ls, src, "eval"); err != nil {
+		return ls.Error2("eval: %v", err)
+	}
+	ls.Replace(1) /* function takes the place of source */
+	ls.Call(ls.GetTop()-1, LK_MULTRET)
+	return ls.GetTop()
+}
+
+// compile (source [, chunkname])
+// returns a function of compiled source, or nil plus error message
+func baseCompile(ls LkState) int {
+	src := ls.CheckString(1)
+	name := ls.OptString(2, "compile")
+	if !EvalEnabled {
+		return ls.Error2("compile is disabled")
+	}
+	if err := _compileChunk(ls, src, name); err != nil {
+		ls.PushNil()
+		ls.PushFString("%v", err)
+		return 2
+	}
+	return 1
+}
+
+// The compiled chunk shares globals with the caller,
+// its locals are isolated as in any other function.
+func _compileChunk(ls LkState, src, name string) error {
+	if ls.Load([]byte(src), name, "t") != LK_OK {
+		err := errors.New(ls.ToString(-1))
+		ls.Pop(1)
+		return err
+	}
+	return nil
+}
+
+// secret (value)
+// The value is kept in a closure, so it never shows up
+// in print, str, json or error messages.
+func secretNew(ls LkState) int {
+	ls.CheckAny(2) /* 1 is `secret` itself */
+	ls.CreateTable(0, 3)
+	ls.PushValue(2)
+	ls.PushGoClosure(func(ls LkState) int {
+		ls.PushValue(LkUpvalueIndex(1))
+		return 1
+	}, 1)
+	ls.SetField(-2, consts.SecretField)
+	ls.PushGoFunction(func(ls LkState) int {
+		ls.PushString(consts.Redacted)
+		return 1
+	})
+	ls.SetField(-2, "__str")
+	ls.PushString("secret")
+	ls.SetField(-2, "__name")
+	return 1
+}
+
+// secret.expose (s)
+func secretExpose(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	if ls.GetField(1, consts.SecretField) != LK_TFUNCTION {
+		return ls.ArgError(1, "secret expected")
+	}
+	ls.Call(0, 1)
+	return 1
+}
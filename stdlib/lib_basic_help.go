@@ -0,0 +1,56 @@
+package stdlib
+
+import (
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// help (name | module)
+// prints the signature and doc of fn `name`, eg: help('num.currency'),
+// or signatures of all fns of a module, eg: help(num) or help('num').
+// `::` works as `.` in names.
+func baseHelp(ls LkState) int {
+	var name string
+	switch ls.Type(1) {
+	case LK_TSTRING:
+		name = strings.ReplaceAll(ls.ToString(1), "::", ".")
+	case LK_TTABLE:
+		name = _loadedName(ls, 1)
+	default:
+		return ls.ArgError(1, "fn name or module expected")
+	}
+	println(HelpText(ls, name))
+	return 0
+}
+
+// HelpText is the help of fn or module `name`
+func HelpText(ls LkState, name string) string {
+	if f, ok := LookupFn(name); ok {
+		return f.Help()
+	}
+	ls.GetField(LK_REGISTRYINDEX, LUA_LOADED_TABLE)
+	defer ls.Pop(2)
+	if ls.GetField(-1, name) != LK_TTABLE {
+		return "no help for '" + name + "'"
+	}
+	fns := []string{}
+	ls.PushNil()
+	for ls.Next(-2) {
+		if ls.Type(-2) == LK_TSTRING && ls.IsFunction(-1) {
+			fns = append(fns, ls.ToString(-2))
+		}
+		ls.Pop(1)
+	}
+	sort.Strings(fns)
+	lines := []string{name}
+	for _, fn := range fns {
+		if f, ok := LookupFn(name + "." + fn); ok {
+			lines = append(lines, "  "+f.String())
+		} else {
+			lines = append(lines, "  "+name+"."+fn+"(...)")
+		}
+	}
+	return strings.Join(lines, "\n")
+}
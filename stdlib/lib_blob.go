@@ -0,0 +1,401 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/xml"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var blobLib = map[string]GoFunction{
+	"client": blobNewClient,
+}
+
+var blobMethods = map[string]GoFunction{
+	"get":      blobGet,
+	"put":      blobPut,
+	"delete":   blobDelete,
+	"list":     blobList,
+	"download": blobDownload,
+	"upload":   blobUpload,
+}
+
+const (
+	s3EmptyHash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+	s3Unsigned  = "UNSIGNED-PAYLOAD"
+)
+
+var (
+	// credentials stay in go, the client table only has an id
+	blobClients   = map[int64]*blobClient{}
+	blobClientsMu sync.Mutex
+)
+
+type blobClient struct {
+	endpoint *url.URL
+	region   string
+	access   string
+	secret   string
+	token    string
+}
+
+func OpenBlobLib(ls LkState) int {
+	ls.NewLib(blobLib)
+	return 1
+}
+
+// blob.client([opts])
+// opts: {endpoint, region, access_key, secret_key, session_token}
+// missing ones are read from `S3_ENDPOINT` and `AWS_*` env
+// objects are addressed path style: `endpoint/bucket/key`
+// return client, err
+func blobNewClient(ls LkState) int {
+	opts := OptTable(ls, 1, lkMap{})
+	opt := func(key, env, dft string) string {
+		if v, ok := opts[key].(string); ok && v != "" {
+			return v
+		}
+		if v := os.Getenv(env); v != "" {
+			return v
+		}
+		return dft
+	}
+	region := opt("region", "AWS_REGION", "us-east-1")
+	c := &blobClient{
+		region: region,
+		access: opt("access_key", "AWS_ACCESS_KEY_ID", ""),
+		secret: opt("secret_key", "AWS_SECRET_ACCESS_KEY", ""),
+		token:  opt("session_token", "AWS_SESSION_TOKEN", ""),
+	}
+	endpoint, err := url.Parse(opt("endpoint", "S3_ENDPOINT", "https://s3."+region+".amazonaws.com"))
+	if err == nil && endpoint.Host == "" {
+		err = errors.New("endpoint needs a scheme and host")
+	}
+	if err == nil && (c.access == "" || c.secret == "") {
+		err = errors.New("missing access_key or secret_key")
+	}
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	c.endpoint = endpoint
+
+	blobClientsMu.Lock()
+	id := int64(len(blobClients) + 1)
+	blobClients[id] = c
+	blobClientsMu.Unlock()
+
+	ls.NewLib(blobMethods)
+	ls.PushInteger(id)
+	ls.SetField(-2, "id")
+	ls.PushNil()
+	return 2
+}
+
+func _blobSelf(ls LkState) *blobClient {
+	ls.CheckType(1, LK_TTABLE)
+	ls.GetField(1, "id")
+	id := ls.ToInteger(-1)
+	ls.Pop(1)
+	blobClientsMu.Lock()
+	defer blobClientsMu.Unlock()
+	c, ok := blobClients[id]
+	if !ok {
+		ls.Error2("not a blob client")
+	}
+	return c
+}
+
+// c:get(bucket, key)
+// return data, err
+func blobGet(ls LkState) int {
+	c := _blobSelf(ls)
+	resp, err := c.do("GET", ls.CheckString(2), ls.CheckString(3), nil, nil, s3EmptyHash, nil)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	defer resp.Body.Close()
+	data, err := io.ReadAll(resp.Body)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(string(data))
+	ls.PushNil()
+	return 2
+}
+
+// c:put(bucket, key, data [, content_type])
+// return err
+func blobPut(ls LkState) int {
+	c := _blobSelf(ls)
+	data := ls.CheckString(4)
+	headers := http.Header{}
+	headers.Set("Content-Type", ls.OptString(5, "application/octet-stream"))
+	sum := sha256.Sum256([]byte(data))
+	resp, err := c.do("PUT", ls.CheckString(2), ls.CheckString(3), nil, headers, hex.EncodeToString(sum[:]), strings.NewReader(data))
+	return _blobPushErr(ls, resp, err)
+}
+
+// c:delete(bucket, key)
+// return err
+func blobDelete(ls LkState) int {
+	c := _blobSelf(ls)
+	resp, err := c.do("DELETE", ls.CheckString(2), ls.CheckString(3), nil, nil, s3EmptyHash, nil)
+	return _blobPushErr(ls, resp, err)
+}
+
+type s3ListResult struct {
+	Contents []struct {
+		Key          string `xml:"Key"`
+		Size         int64  `xml:"Size"`
+		LastModified string `xml:"LastModified"`
+	} `xml:"Contents"`
+	IsTruncated bool   `xml:"IsTruncated"`
+	NextToken   string `xml:"NextContinuationToken"`
+}
+
+// c:list(bucket [, prefix])
+// return [{key, size, mtime}], err
+func blobList(ls LkState) int {
+	c := _blobSelf(ls)
+	bucket := ls.CheckString(2)
+	query := url.Values{"list-type": {"2"}}
+	if prefix := ls.OptString(3, ""); prefix != "" {
+		query.Set("prefix", prefix)
+	}
+
+	items := []any{}
+	for {
+		resp, err := c.do("GET", bucket, "", query, nil, s3EmptyHash, nil)
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		var result s3ListResult
+		err = xml.NewDecoder(resp.Body).Decode(&result)
+		resp.Body.Close()
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		for _, obj := range result.Contents {
+			item := lkMap{"key": obj.Key, "size": obj.Size}
+			if t, err := time.Parse(time.RFC3339, obj.LastModified); err == nil {
+				item["mtime"] = t.Unix()
+			}
+			items = append(items, item)
+		}
+		if !result.IsTruncated || result.NextToken == "" {
+			break
+		}
+		query.Set("continuation-token", result.NextToken)
+	}
+	pushList(ls, items)
+	ls.PushNil()
+	return 2
+}
+
+// c:download(bucket, key, path)
+// streams the object into a file
+// return err
+func blobDownload(ls LkState) int {
+	c := _blobSelf(ls)
+	path := ls.CheckString(4)
+	resp, err := c.do("GET", ls.CheckString(2), ls.CheckString(3), nil, nil, s3EmptyHash, nil)
+	if err == nil {
+		defer resp.Body.Close()
+		var f *os.File
+		if f, err = os.Create(path); err == nil {
+			_, err = io.Copy(f, resp.Body)
+			if cerr := f.Close(); err == nil {
+				err = cerr
+			}
+		}
+	}
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// c:upload(bucket, key, path [, content_type])
+// streams a file as the object, the payload is not signed
+// return err
+func blobUpload(ls LkState) int {
+	c := _blobSelf(ls)
+	f, err := os.Open(ls.CheckString(4))
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	defer f.Close()
+	headers := http.Header{}
+	headers.Set("Content-Type", ls.OptString(5, "application/octet-stream"))
+	resp, err := c.do("PUT", ls.CheckString(2), ls.CheckString(3), nil, headers, s3Unsigned, f)
+	return _blobPushErr(ls, resp, err)
+}
+
+func _blobPushErr(ls LkState, resp *http.Response, err error) int {
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	resp.Body.Close()
+	ls.PushNil()
+	return 1
+}
+
+// do sends a signed request, non 2xx responses are returned as errors
+func (c *blobClient) do(method, bucket, key string, query url.Values, headers http.Header, payloadHash string, body io.Reader) (*http.Response, error) {
+	u := *c.endpoint
+	u.Path = strings.TrimSuffix(u.Path, "/") + "/" + bucket
+	if key != "" {
+		u.Path += "/" + key
+	}
+	u.RawPath = _s3Escape(u.Path, false)
+	u.RawQuery = _s3Query(query)
+
+	req, err := http.NewRequest(method, u.String(), body)
+	if err != nil {
+		return nil, err
+	}
+	for k := range headers {
+		req.Header.Set(k, headers.Get(k))
+	}
+	if f, ok := body.(*os.File); ok {
+		/* S3 rejects chunked uploads without a length */
+		if info, err := f.Stat(); err == nil {
+			req.ContentLength = info.Size()
+		}
+	}
+	if c.token != "" {
+		req.Header.Set("X-Amz-Security-Token", c.token)
+	}
+	sigV4Sign(req, payloadHash, c.access, c.secret, c.region, "s3", time.Now())
+
+	resp, err := client.Do(req)
+	if err != nil {
+		return nil, err
+	}
+	if resp.StatusCode/100 != 2 {
+		defer resp.Body.Close()
+		var s3err struct {
+			Code    string `xml:"Code"`
+			Message string `xml:"Message"`
+		}
+		data, _ := io.ReadAll(resp.Body)
+		if xml.Unmarshal(data, &s3err) == nil && s3err.Code != "" {
+			return nil, fmt.Errorf("%s: %s", s3err.Code, s3err.Message)
+		}
+		return nil, errors.New(resp.Status)
+	}
+	return resp, nil
+}
+
+// sigV4Sign adds AWS Signature Version 4 headers to req.
+// Host, Range, Content-Type and every X-Amz-* header are signed.
+func sigV4Sign(req *http.Request, payloadHash, access, secret, region, service string, now time.Time) {
+	now = now.UTC()
+	amzDate := now.Format("20060102T150405Z")
+	date := amzDate[:8]
+	req.Header.Set("X-Amz-Date", amzDate)
+	req.Header.Set("X-Amz-Content-Sha256", payloadHash)
+
+	host := req.Host
+	if host == "" {
+		host = req.URL.Host
+	}
+	signed := map[string]string{"host": host}
+	for k := range req.Header {
+		lk := strings.ToLower(k)
+		if lk == "range" || lk == "content-type" || strings.HasPrefix(lk, "x-amz-") {
+			signed[lk] = strings.TrimSpace(req.Header.Get(k))
+		}
+	}
+	names := make([]string, 0, len(signed))
+	for k := range signed {
+		names = append(names, k)
+	}
+	sort.Strings(names)
+	var canonHeaders strings.Builder
+	for _, k := range names {
+		canonHeaders.WriteString(k + ":" + signed[k] + "\n")
+	}
+	signedHeaders := strings.Join(names, ";")
+
+	canonReq := strings.Join([]string{
+		req.Method,
+		req.URL.EscapedPath(),
+		req.URL.RawQuery,
+		canonHeaders.String(),
+		signedHeaders,
+		payloadHash,
+	}, "\n")
+	scope := date + "/" + region + "/" + service + "/aws4_request"
+	reqHash := sha256.Sum256([]byte(canonReq))
+	toSign := "AWS4-HMAC-SHA256\n" + amzDate + "\n" + scope + "\n" + hex.EncodeToString(reqHash[:])
+
+	key := []byte("AWS4" + secret)
+	for _, part := range []string{date, region, service, "aws4_request", toSign} {
+		mac := hmac.New(sha256.New, key)
+		mac.Write([]byte(part))
+		key = mac.Sum(nil)
+	}
+	req.Header.Set("Authorization", fmt.Sprintf(
+		"AWS4-HMAC-SHA256 Credential=%s/%s, SignedHeaders=%s, Signature=%s",
+		access, scope, signedHeaders, hex.EncodeToString(key),
+	))
+}
+
+// _s3Query encodes query sorted by key, as the canonical request needs
+func _s3Query(query url.Values) string {
+	keys := make([]string, 0, len(query))
+	for k := range query {
+		keys = append(keys, k)
+	}
+	sort.Strings(keys)
+	parts := make([]string, 0, len(keys))
+	for _, k := range keys {
+		parts = append(parts, _s3Escape(k, true)+"="+_s3Escape(query.Get(k), true))
+	}
+	return strings.Join(parts, "&")
+}
+
+// _s3Escape is RFC 3986 escaping, `/` is kept unless escapeSlash
+func _s3Escape(s string, escapeSlash bool) string {
+	var b strings.Builder
+	for i := 0; i < len(s); i++ {
+		ch := s[i]
+		switch {
+		case 'a' <= ch && ch <= 'z', 'A' <= ch && ch <= 'Z', '0' <= ch && ch <= '9',
+			ch == '-', ch == '_', ch == '.', ch == '~', ch == '/' && !escapeSlash:
+			b.WriteByte(ch)
+		default:
+			fmt.Fprintf(&b, "%%%02X", ch)
+		}
+	}
+	return b.String()
+}
@@ -0,0 +1,225 @@
+package stdlib
+
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var bytesLib = map[string]GoFunction{
+	"pack":        bytesPack,
+	"unpack":      bytesUnpack,
+	"size":        bytesSize,
+	"to_hex":      bytesToHex,
+	"from_hex":    bytesFromHex,
+	"base64":      bytesBase64,
+	"from_base64": bytesFromBase64,
+	"slice":       bytesSlice,
+	"concat":      bytesConcat,
+}
+
+func OpenBytesLib(ls LkState) int {
+	ls.NewLib(bytesLib)
+	return 1
+}
+
+// Format, like python's struct:
+//
+//	<  little endian (default)    >  big endian    !  network (big)
+//	b B  int8 / uint8     h H  int16 / uint16    i I  int32 / uint32
+//	q Q  int64 / uint64   f d  float32 / float64  ?  bool
+//	s    str, `4s` is 4 bytes (zero padded)       x  pad byte
+//
+// A count before a code repeats it, eg: `<2H4s` is 2 uint16 and a 4-bytes str.
+type packItem struct {
+	code  byte
+	count int
+}
+
+func parsePackFmt(f string) (binary.ByteOrder, []packItem, error) {
+	var order binary.ByteOrder = binary.LittleEndian
+	items := []packItem{}
+	for i := 0; i < len(f); i++ {
+		c := f[i]
+		switch c {
+		case '<', '=':
+			order = binary.LittleEndian
+			continue
+		case '>', '!':
+			order = binary.BigEndian
+			continue
+		case ' ':
+			continue
+		}
+		count, hasCount := 0, false
+		for i < len(f) && f[i] >= '0' && f[i] <= '9' {
+			count = count*10 + int(f[i]-'0')
+			hasCount = true
+			i++
+		}
+		if i >= len(f) {
+			return nil, nil, fmt.Errorf("missing code after count in '%s'", f)
+		}
+		if !hasCount {
+			count = 1
+		}
+		c = f[i]
+		if packSize(c) == 0 && c != 's' && c != 'x' {
+			return nil, nil, fmt.Errorf("invalid format code '%c'", c)
+		}
+		items = append(items, packItem{c, count})
+	}
+	return order, items, nil
+}
+
+// size of one value, 0 for `s` and `x` (they are sized by count)
+func packSize(c byte) int {
+	switch c {
+	case 'b', 'B', '?':
+		return 1
+	case 'h', 'H':
+		return 2
+	case 'i', 'I', 'f':
+		return 4
+	case 'q', 'Q', 'd':
+		return 8
+	}
+	return 0
+}
+
+func itemSize(it packItem) int {
+	if it.code == 's' || it.code == 'x' {
+		return it.count
+	}
+	return packSize(it.code) * it.count
+}
+
+// bytes.size(fmt)
+func bytesSize(ls LkState) int {
+	_, items, err := parsePackFmt(ls.CheckString(1))
+	if err != nil {
+		return ls.Error2("bytes.size: %v", err)
+	}
+	size := 0
+	for _, it := range items {
+		size += itemSize(it)
+	}
+	ls.PushInteger(int64(size))
+	return 1
+}
+
+// bytes.pack(fmt, ...)
+// returns the packed str
+func bytesPack(ls LkState) int {
+	order, items, err := parsePackFmt(ls.CheckString(1))
+	if err != nil {
+		return ls.Error2("bytes.pack: %v", err)
+	}
+	buf := []byte{}
+	arg := 2
+	for _, it := range items {
+		switch it.code {
+		case 'x':
+			buf = append(buf, make([]byte, it.count)...)
+			continue
+		case 's':
+			s := ls.CheckString(arg)
+			arg++
+			b := make([]byte, it.count)
+			copy(b, s)
+			buf = append(buf, b...)
+			continue
+		}
+		for n := 0; n < it.count; n++ {
+			b := make([]byte, packSize(it.code))
+			switch it.code {
+			case '?':
+				if ls.ToBoolean(arg) {
+					b[0] = 1
+				}
+			case 'f':
+				order.PutUint32(b, math.Float32bits(float32(ls.CheckNumber(arg))))
+			case 'd':
+				order.PutUint64(b, math.Float64bits(ls.CheckNumber(arg)))
+			default:
+				v := uint64(ls.CheckInteger(arg))
+				switch len(b) {
+				case 1:
+					b[0] = byte(v)
+				case 2:
+					order.PutUint16(b, uint16(v))
+				case 4:
+					order.PutUint32(b, uint32(v))
+				case 8:
+					order.PutUint64(b, v)
+				}
+			}
+			arg++
+			buf = append(buf, b...)
+		}
+	}
+	ls.PushString(string(buf))
+	return 1
+}
+
+// bytes.unpack(fmt, data [, offset])
+// returns the values, followed by the offset after them
+func bytesUnpack(ls LkState) int {
+	order, items, err := parsePackFmt(ls.CheckString(1))
+	if err != nil {
+		return ls.Error2("bytes.unpack: %v", err)
+	}
+	data := ls.CheckString(2)
+	pos := int(ls.OptInteger(3, 0))
+	ls.ArgCheck(pos >= 0 && pos <= len(data), 3, "offset out of range")
+
+	n := 0
+	for _, it := range items {
+		size := itemSize(it)
+		if pos+size > len(data) {
+			return ls.Error2("bytes.unpack: data too short, need %d bytes at %d", size, pos)
+		}
+		switch it.code {
+		case 'x':
+			pos += size
+			continue
+		case 's':
+			ls.PushString(data[pos : pos+size])
+			pos += size
+			n++
+			continue
+		}
+		ls.CheckStack2(it.count, "too many results")
+		for k := 0; k < it.count; k++ {
+			b := []byte(data[pos : pos+packSize(it.code)])
+			pos += len(b)
+			n++
+			switch it.code {
+			case '?':
+				ls.PushBoolean(b[0] != 0)
+			case 'b':
+				ls.PushInteger(int64(int8(b[0])))
+			case 'B':
+				ls.PushInteger(int64(b[0]))
+			case 'h':
+				ls.PushInteger(int64(int16(order.Uint16(b))))
+			case 'H':
+				ls.PushInteger(int64(order.Uint16(b)))
+			case 'i':
+				ls.PushInteger(int64(int32(order.Uint32(b))))
+			case 'I':
+				ls.PushInteger(int64(order.Uint32(b)))
+			case 'q', 'Q':
+				ls.PushInteger(int64(order.Uint64(b)))
+			case 'f':
+				ls.PushNumber(float64(math.Float32frombits(order.Uint32(b))))
+			case 'd':
+				ls.PushNumber(math.Float64frombits(order.Uint64(b)))
+			}
+		}
+	}
+	ls.PushInteger(int64(pos))
+	return n + 1
+}
@@ -0,0 +1,111 @@
+package stdlib
+
+import (
+	"encoding/base64"
+	"encoding/hex"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// bytes.to_hex(data)
+// return lowercase hex of data
+func bytesToHex(ls LkState) int {
+	ls.PushString(hex.EncodeToString([]byte(ls.CheckString(1))))
+	return 1
+}
+
+// bytes.from_hex(text)
+// return data, err
+func bytesFromHex(ls LkState) int {
+	data, err := hex.DecodeString(ls.CheckString(1))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(string(data))
+	ls.PushNil()
+	return 2
+}
+
+func _base64Enc(url bool) *base64.Encoding {
+	if url {
+		return base64.URLEncoding
+	}
+	return base64.StdEncoding
+}
+
+// bytes.base64(data [, url])
+// url uses the `-_` alphabet of urls and file names
+// return padded base64 of data
+func bytesBase64(ls LkState) int {
+	data := ls.CheckString(1)
+	ls.PushString(_base64Enc(ls.ToBoolean(2)).EncodeToString([]byte(data)))
+	return 1
+}
+
+// bytes.from_base64(text [, url])
+// padding `=` is optional
+// return data, err
+func bytesFromBase64(ls LkState) int {
+	text := strings.TrimRight(ls.CheckString(1), "=")
+	enc := _base64Enc(ls.ToBoolean(2)).WithPadding(base64.NoPadding)
+	data, err := enc.DecodeString(text)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(string(data))
+	ls.PushNil()
+	return 2
+}
+
+// bytes.slice(data, from [, to])
+// bytes in [from, to), from 0. Negative idx counts from the end,
+// to defaults to the end. Out of range idxs are clamped.
+// return the slice
+func bytesSlice(ls LkState) int {
+	data := ls.CheckString(1)
+	n := int64(len(data))
+	clamp := func(i int64) int64 {
+		if i < 0 {
+			i += n
+		}
+		if i < 0 {
+			return 0
+		}
+		if i > n {
+			return n
+		}
+		return i
+	}
+	from := clamp(ls.CheckInteger(2))
+	to := clamp(ls.OptInteger(3, n))
+	if from >= to {
+		ls.PushString("")
+		return 1
+	}
+	ls.PushString(data[from:to])
+	return 1
+}
+
+// bytes.concat(list) or bytes.concat(a, b, ...)
+// return all data joined
+func bytesConcat(ls LkState) int {
+	var sb strings.Builder
+	if ls.IsTable(1) {
+		for i := int64(0); i < ls.Len2(1); i++ {
+			ls.GetI(1, i)
+			sb.WriteString(ls.CheckString(-1))
+			ls.Pop(1)
+		}
+	} else {
+		for i := 1; i <= ls.GetTop(); i++ {
+			sb.WriteString(ls.CheckString(i))
+		}
+	}
+	ls.PushString(sb.String())
+	return 1
+}
@@ -0,0 +1,262 @@
+package stdlib
+
+import (
+	"bufio"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+)
+
+var configLib = map[string]GoFunction{
+	"load":     configLoad,
+	"load_env": configLoadEnv,
+}
+
+func OpenConfigLib(ls LkState) int {
+	ls.NewLib(configLib)
+	return 1
+}
+
+// config.load({files = ['.env', 'config.json'], env_prefix = 'APP_', schema = {...}})
+// Later sources override earlier ones: files in order, then env vars.
+// Missing files are skipped. Keys of every source are lowercased,
+// so `APP_PORT` overrides `PORT` of .env and `Port` of json as key `port`.
+// schema: {'port': 'int', 'debug': {'type': 'bool', 'default': false, 'required': true}}
+// types: str, int, num, bool
+// return cfg, err
+func configLoad(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	cfg := map[string]any{}
+
+	ls.GetField(1, "files")
+	for _, f := range OptList(ls, ls.GetTop(), nil) {
+		name, ok := f.(string)
+		if !ok {
+			return ls.ArgError(1, "files must be a list of str")
+		}
+		kv, err := _loadConfigFile(name)
+		if err != nil {
+			return _pushConfigErr(ls, err)
+		}
+		for k, v := range kv {
+			cfg[strings.ToLower(k)] = v
+		}
+	}
+	ls.Pop(1)
+
+	ls.GetField(1, "env_prefix")
+	if prefix, ok := ls.ToStringX(-1); ok && prefix != "" {
+		for _, env := range os.Environ() {
+			k, v, _ := strings.Cut(env, "=")
+			if strings.HasPrefix(k, prefix) {
+				cfg[strings.ToLower(strings.TrimPrefix(k, prefix))] = v
+			}
+		}
+	}
+	ls.Pop(1)
+
+	if ls.GetField(1, "schema") == LK_TTABLE {
+		if err := _applySchema(ls, ls.AbsIndex(-1), cfg); err != nil {
+			return _pushConfigErr(ls, err)
+		}
+	}
+	ls.Pop(1)
+
+	pushTable(ls, cfg)
+	ls.PushNil()
+	return 2
+}
+
+// config.load_env([path])
+// reads a dotenv file (default `.env`) into os env, existing vars are kept
+// return err
+func configLoadEnv(ls LkState) int {
+	kv, err := _parseDotenv(ls.OptString(1, ".env"))
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	for k, v := range kv {
+		if _, ok := os.LookupEnv(k); !ok {
+			os.Setenv(k, v.(string))
+		}
+	}
+	ls.PushNil()
+	return 1
+}
+
+func _pushConfigErr(ls LkState, err error) int {
+	ls.PushNil()
+	ls.PushString("config: " + err.Error())
+	return 2
+}
+
+func _loadConfigFile(name string) (map[string]any, error) {
+	if _, err := os.Stat(name); os.IsNotExist(err) {
+		return nil, nil
+	}
+	switch ext := filepath.Ext(name); {
+	case ext == ".json":
+		data, err := os.ReadFile(name)
+		if err != nil {
+			return nil, err
+		}
+		kv := map[string]any{}
+		if err := Json.Unmarshal(data, &kv); err != nil {
+			return nil, fmt.Errorf("%s: %v", name, err)
+		}
+		return kv, nil
+	case ext == ".env" || filepath.Base(name) == ".env" || strings.HasPrefix(filepath.Base(name), ".env."):
+		return _parseDotenv(name)
+	default:
+		return nil, fmt.Errorf("%s: unsupported format, use .env or .json", name)
+	}
+}
+
+// _parseDotenv supports `KEY=VAL`, `export KEY=VAL`, `# comments`,
+// 'single quoted' (raw) and "double quoted" (with \n \t \" escapes) values.
+func _parseDotenv(name string) (map[string]any, error) {
+	f, err := os.Open(name)
+	if err != nil {
+		return nil, err
+	}
+	defer f.Close()
+
+	kv := map[string]any{}
+	sc := bufio.NewScanner(f)
+	for lineNo := 1; sc.Scan(); lineNo++ {
+		line := strings.TrimSpace(sc.Text())
+		if line == "" || line[0] == '#' {
+			continue
+		}
+		line = strings.TrimPrefix(line, "export ")
+		k, v, ok := strings.Cut(line, "=")
+		k = strings.TrimSpace(k)
+		if !ok || k == "" {
+			return nil, fmt.Errorf("%s:%d: expect KEY=VALUE", name, lineNo)
+		}
+		v = strings.TrimSpace(v)
+		switch {
+		case len(v) >= 2 && v[0] == '\'' && v[len(v)-1] == '\'':
+			v = v[1 : len(v)-1]
+		case len(v) >= 2 && v[0] == '"' && v[len(v)-1] == '"':
+			uq, err := strconv.Unquote(v)
+			if err != nil {
+				return nil, fmt.Errorf("%s:%d: %v", name, lineNo, err)
+			}
+			v = uq
+		default:
+			/* strip inline comment */
+			if i := strings.Index(v, " #"); i >= 0 {
+				v = strings.TrimSpace(v[:i])
+			}
+		}
+		kv[k] = v
+	}
+	return kv, sc.Err()
+}
+
+// _applySchema coerces values in cfg with schema table at `idx`
+func _applySchema(ls LkState, idx int, cfg map[string]any) error {
+	ls.PushNil()
+	for ls.Next(idx) {
+		key := strings.ToLower(ls.ToString(-2))
+		typ, required := "", false
+		var def any
+		if ls.IsTable(-1) {
+			ls.GetField(-1, "type")
+			typ = ls.ToString(-1)
+			ls.GetField(-2, "required")
+			required = ls.ToBoolean(-1)
+			ls.GetField(-3, "default")
+			switch ls.Type(-1) {
+			case LK_TNIL, LK_TBOOLEAN, LK_TNUMBER, LK_TSTRING:
+				def = ls.ToPointer(-1)
+			default:
+				ls.Pop(5) /* default, required, type, field, key */
+				return fmt.Errorf("key '%s': default must be a str, num or bool", key)
+			}
+			ls.Pop(3)
+		} else {
+			typ = ls.ToString(-1)
+		}
+		ls.Pop(1)
+
+		val, ok := cfg[key]
+		if !ok || val == nil {
+			if def == nil {
+				if required {
+					ls.Pop(1) /* key */
+					return fmt.Errorf("missing required key '%s'", key)
+				}
+				continue
+			}
+			/* defaults are coerced like loaded values */
+			val = def
+		}
+		v, err := _coerceConfig(val, typ)
+		if err != nil {
+			ls.Pop(1) /* key */
+			return fmt.Errorf("key '%s': %v", key, err)
+		}
+		cfg[key] = v
+	}
+	return nil
+}
+
+func _coerceConfig(val any, typ string) (any, error) {
+	s, isStr := val.(string)
+	switch typ {
+	case "", "any":
+		return val, nil
+	case "str":
+		if isStr {
+			return s, nil
+		}
+		return fmt.Sprint(val), nil
+	case "int":
+		switch v := val.(type) {
+		case int64:
+			return v, nil
+		case float64:
+			if v == float64(int64(v)) {
+				return int64(v), nil
+			}
+		case string:
+			if i, err := strconv.ParseInt(strings.TrimSpace(v), 10, 64); err == nil {
+				return i, nil
+			}
+		}
+	case "num":
+		switch v := val.(type) {
+		case int64:
+			return float64(v), nil
+		case float64:
+			return v, nil
+		case string:
+			if f, err := strconv.ParseFloat(strings.TrimSpace(v), 64); err == nil {
+				return f, nil
+			}
+		}
+	case "bool":
+		switch v := val.(type) {
+		case bool:
+			return v, nil
+		case string:
+			switch strings.ToLower(strings.TrimSpace(v)) {
+			case "1", "true", "yes", "on":
+				return true, nil
+			case "0", "false", "no", "off", "":
+				return false, nil
+			}
+		}
+	default:
+		return nil, fmt.Errorf("unknown type '%s'", typ)
+	}
+	return nil, fmt.Errorf("can't convert %v to %s", val, typ)
+}
@@ -0,0 +1,297 @@
+package stdlib
+
+import (
+	"crypto"
+	"crypto/aes"
+	"crypto/cipher"
+	"crypto/hmac"
+	"crypto/rand"
+	"crypto/rsa"
+	"crypto/sha256"
+	"crypto/subtle"
+	"crypto/x509"
+	"encoding/base64"
+	"encoding/pem"
+	"errors"
+	"fmt"
+	"strings"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+)
+
+var cryptoLib = map[string]GoFunction{
+	"jwt_sign":   cryptoJwtSign,
+	"jwt_verify": cryptoJwtVerify,
+	"encrypt":    cryptoEncrypt,
+	"decrypt":    cryptoDecrypt,
+	"random_key": cryptoRandomKey,
+	"ct_eq":      cryptoCtEq,
+}
+
+func OpenCryptoLib(ls LkState) int {
+	ls.NewLib(cryptoLib)
+	return 1
+}
+
+var b64url = base64.RawURLEncoding
+
+// crypto.jwt_sign(claims, key [, alg])
+// alg: `HS256` (default) with a secret, or `RS256` with a PEM private key
+// return token, err
+func cryptoJwtSign(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	key := ls.CheckString(2)
+	alg := strings.ToUpper(ls.OptString(3, "HS256"))
+	claims := ls.ToString2(1) /* json */
+	ls.Pop(1)
+
+	header, _ := Json.MarshalToString(map[string]string{"alg": alg, "typ": "JWT"})
+	signing := b64url.EncodeToString([]byte(header)) + "." +
+		b64url.EncodeToString([]byte(claims))
+	sig, err := _jwtSign(alg, key, signing)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(signing + "." + b64url.EncodeToString(sig))
+	ls.PushNil()
+	return 2
+}
+
+// crypto.jwt_verify(token, key [, alg])
+// alg: `HS256` (default) with a secret, or `RS256` with a PEM public key.
+// Tokens whose header has another alg are rejected, the token can't choose it.
+// `exp` and `nbf` claims are checked if present.
+// return claims, err
+func cryptoJwtVerify(ls LkState) int {
+	alg := strings.ToUpper(ls.OptString(3, "HS256"))
+	claims, err := _jwtVerify(ls.CheckString(1), ls.CheckString(2), alg)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	pushValue(ls, claims)
+	ls.PushNil()
+	return 2
+}
+
+func _jwtSign(alg, key, signing string) ([]byte, error) {
+	switch alg {
+	case "HS256":
+		mac := hmac.New(sha256.New, []byte(key))
+		mac.Write([]byte(signing))
+		return mac.Sum(nil), nil
+	case "RS256":
+		priv, err := _parseRSAPrivateKey(key)
+		if err != nil {
+			return nil, err
+		}
+		sum := sha256.Sum256([]byte(signing))
+		return rsa.SignPKCS1v15(nil, priv, crypto.SHA256, sum[:])
+	}
+	return nil, fmt.Errorf("unsupported alg: %s", alg)
+}
+
+func _jwtVerify(token, key, alg string) (map[string]any, error) {
+	parts := strings.Split(token, ".")
+	if len(parts) != 3 {
+		return nil, errors.New("malformed token")
+	}
+	var header struct {
+		Alg string `json:"alg"`
+	}
+	if err := _b64Json(parts[0], &header); err != nil {
+		return nil, fmt.Errorf("invalid header: %v", err)
+	}
+	if header.Alg != alg {
+		return nil, fmt.Errorf("alg mismatch: token has %s, want %s", header.Alg, alg)
+	}
+	sig, err := b64url.DecodeString(parts[2])
+	if err != nil {
+		return nil, fmt.Errorf("invalid signature: %v", err)
+	}
+
+	signing := parts[0] + "." + parts[1]
+	switch alg {
+	case "HS256":
+		/* a public key is no secret, it signs forged tokens */
+		if block, _ := pem.Decode([]byte(key)); block != nil {
+			return nil, errors.New("HS256 needs a secret, not a PEM key")
+		}
+		want, _ := _jwtSign("HS256", key, signing)
+		if !hmac.Equal(sig, want) {
+			return nil, errors.New("signature mismatch")
+		}
+	case "RS256":
+		pub, err := _parseRSAPublicKey(key)
+		if err != nil {
+			return nil, err
+		}
+		sum := sha256.Sum256([]byte(signing))
+		if err := rsa.VerifyPKCS1v15(pub, crypto.SHA256, sum[:], sig); err != nil {
+			return nil, errors.New("signature mismatch")
+		}
+	default:
+		/* never accept `none` */
+		return nil, fmt.Errorf("unsupported alg: %s", alg)
+	}
+
+	var claims map[string]any
+	if err := _b64Json(parts[1], &claims); err != nil {
+		return nil, fmt.Errorf("invalid claims: %v", err)
+	}
+	now := float64(time.Now().Unix())
+	if exp, ok := claims["exp"].(float64); ok && now >= exp {
+		return nil, errors.New("token expired")
+	}
+	if nbf, ok := claims["nbf"].(float64); ok && now < nbf {
+		return nil, errors.New("token not valid yet")
+	}
+	return claims, nil
+}
+
+func _b64Json(s string, v any) error {
+	data, err := b64url.DecodeString(s)
+	if err != nil {
+		return err
+	}
+	return Json.Unmarshal(data, v)
+}
+
+func _parseRSAPrivateKey(key string) (*rsa.PrivateKey, error) {
+	block, _ := pem.Decode([]byte(key))
+	if block == nil {
+		return nil, errors.New("invalid PEM private key")
+	}
+	if k, err := x509.ParsePKCS1PrivateKey(block.Bytes); err == nil {
+		return k, nil
+	}
+	k, err := x509.ParsePKCS8PrivateKey(block.Bytes)
+	if err != nil {
+		return nil, err
+	}
+	priv, ok := k.(*rsa.PrivateKey)
+	if !ok {
+		return nil, errors.New("not a RSA private key")
+	}
+	return priv, nil
+}
+
+// _parseRSAPublicKey accepts PKIX / PKCS1 public key, or a certificate
+func _parseRSAPublicKey(key string) (*rsa.PublicKey, error) {
+	block, _ := pem.Decode([]byte(key))
+	if block == nil {
+		return nil, errors.New("invalid PEM public key")
+	}
+	if k, err := x509.ParsePKCS1PublicKey(block.Bytes); err == nil {
+		return k, nil
+	}
+	var k any
+	if cert, err := x509.ParseCertificate(block.Bytes); err == nil {
+		k = cert.PublicKey
+	} else if k, err = x509.ParsePKIXPublicKey(block.Bytes); err != nil {
+		return nil, err
+	}
+	pub, ok := k.(*rsa.PublicKey)
+	if !ok {
+		return nil, errors.New("not a RSA public key")
+	}
+	return pub, nil
+}
+
+// crypto.ct_eq(a, b)
+// compares in constant time, use it for tokens and signatures
+func cryptoCtEq(ls LkState) int {
+	a := ls.CheckString(1)
+	b := ls.CheckString(2)
+	ls.PushBoolean(subtle.ConstantTimeCompare([]byte(a), []byte(b)) == 1)
+	return 1
+}
+
+// crypto.random_key([size])
+// returns `size` (default 32) random bytes, suitable as AES-256 key
+func cryptoRandomKey(ls LkState) int {
+	size := ls.OptInteger(1, 32)
+	ls.ArgCheck(size > 0, 1, "size must be positive")
+	key := make([]byte, size)
+	if _, err := rand.Read(key); err != nil {
+		return ls.Error2("random_key: %v", err)
+	}
+	ls.PushString(string(key))
+	return 1
+}
+
+// crypto.encrypt(key, plaintext [, {aad}])
+// AES-GCM with a random nonce, key must be 16, 24 or 32 bytes.
+// return nonce + ciphertext, err
+func cryptoEncrypt(ls LkState) int {
+	aead, err := _newAEAD(ls.CheckString(1))
+	if err != nil {
+		return _pushCryptoErr(ls, err)
+	}
+	plain := ls.CheckString(2)
+	aad := _cryptoAAD(ls, 3)
+
+	nonce := make([]byte, aead.NonceSize(), aead.NonceSize()+len(plain)+aead.Overhead())
+	if _, err := rand.Read(nonce); err != nil {
+		return _pushCryptoErr(ls, err)
+	}
+	out := aead.Seal(nonce, nonce, []byte(plain), aad)
+	ls.PushString(string(out))
+	ls.PushNil()
+	return 2
+}
+
+// crypto.decrypt(key, ciphertext [, {aad}])
+// return plaintext, err
+func cryptoDecrypt(ls LkState) int {
+	aead, err := _newAEAD(ls.CheckString(1))
+	if err != nil {
+		return _pushCryptoErr(ls, err)
+	}
+	data := ls.CheckString(2)
+	aad := _cryptoAAD(ls, 3)
+	if len(data) < aead.NonceSize() {
+		return _pushCryptoErr(ls, errors.New("ciphertext too short"))
+	}
+	nonce, sealed := data[:aead.NonceSize()], data[aead.NonceSize():]
+	plain, err := aead.Open(nil, []byte(nonce), []byte(sealed), aad)
+	if err != nil {
+		return _pushCryptoErr(ls, errors.New("decrypt failed: message authentication failed"))
+	}
+	ls.PushString(string(plain))
+	ls.PushNil()
+	return 2
+}
+
+func _newAEAD(key string) (cipher.AEAD, error) {
+	block, err := aes.NewCipher([]byte(key))
+	if err != nil {
+		return nil, err
+	}
+	return cipher.NewGCM(block)
+}
+
+// _cryptoAAD reads `opts.aad` at `idx`
+func _cryptoAAD(ls LkState, idx int) []byte {
+	if ls.IsNoneOrNil(idx) {
+		return nil
+	}
+	ls.CheckType(idx, LK_TTABLE)
+	ls.GetField(idx, "aad")
+	defer ls.Pop(1)
+	if ls.IsNil(-1) {
+		return nil
+	}
+	return []byte(ls.CheckString(-1))
+}
+
+func _pushCryptoErr(ls LkState, err error) int {
+	ls.PushNil()
+	ls.PushString(err.Error())
+	return 2
+}
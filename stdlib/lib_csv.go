@@ -0,0 +1,291 @@
+package stdlib
+
+import (
+	"encoding/csv"
+	"errors"
+	"io"
+	"os"
+	"sort"
+	"strings"
+	"unicode/utf8"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var csvLib = map[string]GoFunction{
+	"parse":  csvParse,
+	"read":   csvRead,
+	"encode": csvEncode,
+	"write":  csvWrite,
+}
+
+func OpenCsvLib(ls LkState) int {
+	ls.NewLib(csvLib)
+	return 1
+}
+
+type csvOpts struct {
+	comma rune
+	// first row names the fields, rows are tables
+	headers bool
+	// names given by the `headers` list
+	names []string
+}
+
+// _csvOpts reads {headers, delimiter} at idx.
+// headers is true to read names from the first row, or a list of names.
+func _csvOpts(ls LkState, idx int) (csvOpts, error) {
+	opts := csvOpts{comma: ','}
+	if ls.IsNoneOrNil(idx) {
+		return opts, nil
+	}
+	ls.CheckType(idx, LK_TTABLE)
+	if ls.GetField(idx, "delimiter") == LK_TSTRING {
+		d := ls.ToString(-1)
+		if utf8.RuneCountInString(d) != 1 {
+			ls.Pop(1)
+			return opts, errors.New("delimiter must be 1 char")
+		}
+		opts.comma, _ = utf8.DecodeRuneInString(d)
+	}
+	ls.Pop(1)
+	switch ls.GetField(idx, "headers") {
+	case LK_TBOOLEAN:
+		opts.headers = ls.ToBoolean(-1)
+		ls.Pop(1)
+	case LK_TTABLE:
+		ls.Pop(1)
+		opts.names = _strListField(ls, idx, "headers")
+	default:
+		ls.Pop(1)
+	}
+	return opts, nil
+}
+
+func (o csvOpts) reader(r io.Reader) *csv.Reader {
+	cr := csv.NewReader(r)
+	cr.Comma = o.comma
+	cr.FieldsPerRecord = -1
+	return cr
+}
+
+// _pushCsvRow pushes record as a list, or a table if names are known.
+// Missing fields are nil, extra ones are dropped.
+func _pushCsvRow(ls LkState, record, names []string) {
+	if names == nil {
+		pushList(ls, record)
+		return
+	}
+	ls.CreateTable(0, len(names))
+	for i, name := range names {
+		if i < len(record) {
+			ls.PushString(record[i])
+			ls.SetField(-2, name)
+		}
+	}
+}
+
+// csv.parse(text [, opts])
+// opts: {headers: true or a list of names, delimiter: ','}
+// Rows are lists of str, or tables keyed by headers.
+// return rows, err
+func csvParse(ls LkState) int {
+	text := ls.CheckString(1)
+	opts, err := _csvOpts(ls, 2)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	records, err := opts.reader(strings.NewReader(text)).ReadAll()
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	names := opts.names
+	if opts.headers && len(records) > 0 {
+		names, records = records[0], records[1:]
+	}
+	ls.CreateTable(len(records), 0)
+	for i, record := range records {
+		_pushCsvRow(ls, record, names)
+		ls.SetI(-2, int64(i))
+	}
+	ls.PushNil()
+	return 2
+}
+
+// csv.read(path [, opts])
+// opts are like csv.parse. Rows are read lazily:
+// for i, row in csv.read('a.csv') {}
+// A broken row raises an error.
+// return iterable, err
+func csvRead(ls LkState) int {
+	path := ls.CheckString(1)
+	opts, err := _csvOpts(ls, 2)
+	if err == nil {
+		_, err = os.Stat(path)
+	}
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.CreateTable(0, 3)
+	ls.PushString(path)
+	ls.SetField(-2, "path")
+	ls.PushValue(2)
+	ls.SetField(-2, "opts")
+	ls.PushGoFunction(csvReadIter)
+	ls.SetField(-2, "__iter")
+	ls.PushNil()
+	return 2
+}
+
+func csvReadIter(ls LkState) int {
+	ls.GetField(1, "path")
+	path := ls.ToString(-1)
+	ls.GetField(1, "opts")
+	opts, _ := _csvOpts(ls, ls.GetTop())
+	ls.Pop(2)
+
+	f, err := os.Open(path)
+	if err != nil {
+		return ls.Error2("csv: %v", err)
+	}
+	r := opts.reader(f)
+	names := opts.names
+	idx := int64(0)
+	next := func(ls LkState) int {
+		record, err := r.Read()
+		if err == nil && opts.headers && names == nil {
+			names = record
+			record, err = r.Read()
+		}
+		if err == io.EOF {
+			f.Close()
+			ls.PushNil()
+			return 1
+		}
+		if err != nil {
+			f.Close()
+			return ls.Error2("csv: %v", err)
+		}
+		ls.PushInteger(idx)
+		idx++
+		_pushCsvRow(ls, record, names)
+		return 2
+	}
+	ls.PushGoFunction(next)
+	ls.PushNil()
+	ls.PushNil()
+	return 3
+}
+
+// csv.encode(rows [, opts])
+// rows are lists, or tables written in order of `headers`
+// (default: all keys, sorted). Headers are written as the first row.
+// opts: {headers: list of names, delimiter: ','}
+// return text, err
+func csvEncode(ls LkState) int {
+	var sb strings.Builder
+	if err := _csvWriteRows(ls, &sb, 1, 2); err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(sb.String())
+	ls.PushNil()
+	return 2
+}
+
+// csv.write(path, rows [, opts])
+// writes rows like csv.encode
+// return err
+func csvWrite(ls LkState) int {
+	path := ls.CheckString(1)
+	f, err := os.Create(path)
+	if err == nil {
+		err = _csvWriteRows(ls, f, 2, 3)
+		if cerr := f.Close(); err == nil {
+			err = cerr
+		}
+	}
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+func _csvWriteRows(ls LkState, out io.Writer, rowsIdx, optsIdx int) error {
+	ls.CheckType(rowsIdx, LK_TTABLE)
+	opts, err := _csvOpts(ls, optsIdx)
+	if err != nil {
+		return err
+	}
+	w := csv.NewWriter(out)
+	w.Comma = opts.comma
+	n := ls.Len2(rowsIdx)
+	names := opts.names
+	if len(names) == 0 && n > 0 {
+		/* rows are tables, not lists */
+		isTable := ls.GetI(rowsIdx, 0) == LK_TTABLE && ls.Len2(-1) == 0
+		ls.Pop(1)
+		if isTable {
+			names = _csvKeys(ls, rowsIdx, n)
+		}
+	}
+	if len(names) > 0 {
+		w.Write(names)
+	}
+	for i := int64(0); i < n; i++ {
+		if ls.GetI(rowsIdx, i) != LK_TTABLE {
+			ls.Pop(1)
+			return errors.New("rows must be lists or tables")
+		}
+		record := []string{}
+		if len(names) > 0 && ls.Len2(-1) == 0 {
+			for _, name := range names {
+				ls.GetField(-1, name)
+				record = append(record, _cellStr(ls))
+			}
+		} else {
+			for j := int64(0); j < ls.Len2(-1); j++ {
+				ls.GetI(-1, j)
+				record = append(record, _cellStr(ls))
+			}
+		}
+		ls.Pop(1)
+		if err := w.Write(record); err != nil {
+			return err
+		}
+	}
+	w.Flush()
+	return w.Error()
+}
+
+// _csvKeys are keys of all n rows, sorted
+func _csvKeys(ls LkState, rowsIdx int, n int64) []string {
+	seen := map[string]bool{}
+	keys := []string{}
+	for i := int64(0); i < n; i++ {
+		if ls.GetI(rowsIdx, i) == LK_TTABLE {
+			ls.PushNil()
+			for ls.Next(-2) {
+				if ls.Type(-2) == LK_TSTRING {
+					if k := ls.ToString(-2); !seen[k] {
+						seen[k] = true
+						keys = append(keys, k)
+					}
+				}
+				ls.Pop(1)
+			}
+		}
+		ls.Pop(1)
+	}
+	sort.Strings(keys)
+	return keys
+}
@@ -0,0 +1,82 @@
+package stdlib
+
+import (
+	"math/big"
+	"strconv"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/decimal"
+)
+
+var decimalLib = map[string]GoFunction{
+	"new":   decimalNew,
+	"round": decimalRound,
+	"num":   decimalNum,
+}
+
+func OpenDecimalLib(ls LkState) int {
+	ls.NewLib(decimalLib)
+	return 1
+}
+
+func _checkDecimal(ls LkState, idx int) *decimal.Decimal {
+	d, ok := ls.ToPointer(idx).(*decimal.Decimal)
+	if !ok {
+		ls.ArgError(idx, "decimal expected")
+	}
+	return d
+}
+
+// decimal.new(x)
+// x is a str like '12.34', an int or a num.
+// `12.34d` is the same as decimal.new('12.34').
+// return decimal, err
+func decimalNew(ls LkState) int {
+	var d *decimal.Decimal
+	var err error
+	switch x := ls.ToPointer(1).(type) {
+	case *decimal.Decimal:
+		d = x
+	case int64:
+		d = decimal.FromInt(x)
+	case *big.Int:
+		d = decimal.FromBig(x)
+	case float64:
+		d, err = decimal.Parse(strconv.FormatFloat(x, 'f', -1, 64))
+	default:
+		d, err = decimal.Parse(ls.CheckString(1))
+	}
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.Push(d)
+	ls.PushNil()
+	return 2
+}
+
+// decimal.round(d, places [, mode])
+// mode: 'half_even' (default), 'half_up', 'half_down',
+// 'up' (away from 0), 'down' (toward 0), 'ceil' or 'floor'
+// return decimal with at most `places` digits after the point
+func decimalRound(ls LkState) int {
+	d := _checkDecimal(ls, 1)
+	places := ls.CheckInteger(2)
+	mode := ls.OptString(3, decimal.HalfEven)
+	switch mode {
+	case decimal.HalfEven, decimal.HalfUp, decimal.HalfDown,
+		decimal.Up, decimal.Down, decimal.Ceil, decimal.Floor:
+	default:
+		ls.ArgError(3, "invalid rounding mode '"+mode+"'")
+	}
+	ls.Push(d.Round(int32(places), mode))
+	return 1
+}
+
+// decimal.num(d)
+// return d as a float, it may lose precision
+func decimalNum(ls LkState) int {
+	ls.PushNumber(_checkDecimal(ls, 1).Float64())
+	return 1
+}
@@ -0,0 +1,70 @@
+package stdlib
+
+import (
+	"os"
+	"path"
+	"sort"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/exe"
+)
+
+var embedLib = map[string]GoFunction{
+	"read": embedRead,
+	"ls":   embedLs,
+}
+
+func OpenEmbedLib(ls LkState) int {
+	ls.NewLib(embedLib)
+	return 1
+}
+
+// embed.read (path)
+// Outside of a self-contained executable, it reads from disk,
+// so scripts work the same before and after `lk -exe`.
+func embedRead(ls LkState) int {
+	name := path.Clean(ls.CheckString(1))
+	files, ok := _embedFiles(ls)
+	if !ok {
+		data, err := os.ReadFile(name)
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		ls.PushString(string(data))
+		return 1
+	}
+	data, ok := files[name]
+	if !ok {
+		ls.PushNil()
+		ls.PushString("no embedded file: " + name)
+		return 2
+	}
+	ls.PushString(string(data))
+	return 1
+}
+
+// embed.ls ()
+// returns sorted paths of all embedded files
+func embedLs(ls LkState) int {
+	files, _ := _embedFiles(ls)
+	names := make([]string, 0, len(files))
+	for name := range files {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	pushList(ls, names)
+	return 1
+}
+
+func _embedFiles(ls LkState) (map[string][]byte, bool) {
+	p, err := exe.Current()
+	if err != nil {
+		ls.Error2("embed: %v", err)
+	}
+	if p == nil {
+		return nil, false
+	}
+	return p.Files, true
+}
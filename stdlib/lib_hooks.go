@@ -0,0 +1,28 @@
+package stdlib
+
+import . "github.com/lollipopkit/lk/api"
+
+// LibHooks let a lib set up and clean up things owned by a state,
+// eg: connection pools or caches.
+type LibHooks struct {
+	// Init runs once the lib is opened by a state
+	Init func(ls LkState)
+	// Shutdown runs when the state is closed by ls.Close().
+	// Libs are shut down in reverse order of their Init.
+	Shutdown func(ls LkState)
+}
+
+// lib name -> hooks
+var libHooks = map[string]LibHooks{}
+
+// SetLibHooks registers hooks of lib `name`, it should be called
+// before states are created, eg: in init() of the lib's file.
+func SetLibHooks(name string, hooks LibHooks) {
+	libHooks[name] = hooks
+}
+
+// HooksOf returns hooks registered by SetLibHooks
+func HooksOf(name string) (LibHooks, bool) {
+	hooks, ok := libHooks[name]
+	return hooks, ok
+}
@@ -1,3 +1,5 @@
+//go:build !lk_minimal
+
 package stdlib
 
 import (
@@ -12,8 +14,15 @@ import (
 var (
 	client  = http.Client{}
 	httpLib = map[string]GoFunction{
-		"req":    httpReq,
-		"listen": httpListen,
+		"req":             httpReq,
+		"listen":          httpListen,
+		"serve":           httpServe,
+		"router":          httpRouter,
+		"static_dir":      httpStaticDir,
+		"proxy":           httpProxy,
+		"parse_cookies":   httpParseCookies,
+		"parse_form":      httpParseForm,
+		"parse_multipart": httpParseMultipart,
 	}
 )
 
@@ -0,0 +1,97 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"io"
+	"mime"
+	"mime/multipart"
+	"net/http"
+	"net/url"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// http.parse_cookies(header)
+// header is the value of `Cookie`, returns {name: value}
+func httpParseCookies(ls LkState) int {
+	r := http.Request{Header: http.Header{"Cookie": {ls.CheckString(1)}}}
+	cookies := lkMap{}
+	for _, c := range r.Cookies() {
+		cookies[c.Name] = c.Value
+	}
+	pushTable(ls, cookies)
+	return 1
+}
+
+// http.parse_form(body)
+// body is `application/x-www-form-urlencoded`, multi values are joined by `;`
+// return form, err
+func httpParseForm(ls LkState) int {
+	values, err := url.ParseQuery(ls.CheckString(1))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	form := lkMap{}
+	for k, v := range values {
+		form[k] = strings.Join(v, ";")
+	}
+	pushTable(ls, form)
+	ls.PushNil()
+	return 2
+}
+
+// http.parse_multipart(body, content_type)
+// return {'fields': {name: value}, 'files': [{name, filename, content_type, data}]}, err
+func httpParseMultipart(ls LkState) int {
+	body := ls.CheckString(1)
+	_, params, err := mime.ParseMediaType(ls.CheckString(2))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	boundary, ok := params["boundary"]
+	if !ok {
+		ls.PushNil()
+		ls.PushString("no boundary in content type")
+		return 2
+	}
+
+	fields := lkMap{}
+	files := []any{}
+	mr := multipart.NewReader(strings.NewReader(body), boundary)
+	for {
+		part, err := mr.NextPart()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		data, err := io.ReadAll(part)
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		if part.FileName() == "" {
+			fields[part.FormName()] = string(data)
+			continue
+		}
+		files = append(files, lkMap{
+			"name":         part.FormName(),
+			"filename":     part.FileName(),
+			"content_type": part.Header.Get("Content-Type"),
+			"data":         string(data),
+		})
+	}
+	pushTable(ls, lkMap{"fields": fields, "files": files})
+	ls.PushNil()
+	return 2
+}
@@ -0,0 +1,107 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"net/http/httputil"
+	"net/url"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// http.static_dir(path)
+// returns a handler serving files under `path`, supports range requests.
+// Used with router `/static/*`, the captured rest path is served.
+func httpStaticDir(ls LkState) int {
+	root := ls.CheckString(1)
+	fs := http.FileServer(http.Dir(root))
+	ls.PushGoFunction(_goHandler(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("X-Content-Type-Options", "nosniff")
+		fs.ServeHTTP(w, r)
+	}, true))
+	return 1
+}
+
+// http.proxy(upstream)
+// returns a handler forwarding requests to `upstream`, eg: `http://127.0.0.1:3000`
+func httpProxy(ls LkState) int {
+	upstream, err := url.Parse(ls.CheckString(1))
+	if err != nil || upstream.Host == "" {
+		return ls.ArgError(1, "invalid upstream url")
+	}
+	proxy := httputil.NewSingleHostReverseProxy(upstream)
+	director := proxy.Director
+	proxy.Director = func(r *http.Request) {
+		director(r)
+		r.Host = upstream.Host
+	}
+	ls.PushGoFunction(_goHandler(proxy.ServeHTTP, false))
+	return 1
+}
+
+// _goHandler adapts a Go http handler to a lk handler:
+// fn(req) => {'status': 200, 'headers': {...}, 'body': '...'}
+func _goHandler(h http.HandlerFunc, useRest bool) GoFunction {
+	return func(ls LkState) int {
+		ls.CheckType(1, LK_TTABLE)
+		r, err := _reqFromTable(ls, 1, useRest)
+		if err != nil {
+			return ls.Error2("http: %v", err)
+		}
+		rec := httptest.NewRecorder()
+		h(rec, r)
+
+		headers := lkMap{}
+		for k, v := range rec.Header() {
+			headers[k] = strings.Join(v, ", ")
+		}
+		pushTable(ls, lkMap{
+			"status":  rec.Code,
+			"headers": headers,
+			"body":    rec.Body.String(),
+		})
+		return 1
+	}
+}
+
+// _reqFromTable is the reverse of genServeReqTable.
+// If `useRest` and router captured `*`, path is replaced by it.
+func _reqFromTable(ls LkState, idx int, useRest bool) (*http.Request, error) {
+	ls.GetField(idx, "method")
+	method := ls.OptString(-1, "GET")
+	ls.GetField(idx, "url")
+	rawURL := ls.OptString(-1, "/")
+	ls.GetField(idx, "body")
+	body := ls.OptString(-1, "")
+	ls.GetField(idx, "remote")
+	remote := ls.OptString(-1, "")
+	ls.Pop(4)
+
+	r, err := http.NewRequest(method, rawURL, strings.NewReader(body))
+	if err != nil {
+		return nil, err
+	}
+	r.RemoteAddr = remote
+	if ls.GetField(idx, "headers") == LK_TTABLE {
+		ls.PushNil()
+		for ls.Next(-2) {
+			r.Header.Set(_respStr(ls, -2), _respStr(ls, -1))
+			ls.Pop(1)
+		}
+	}
+	ls.Pop(1)
+
+	if useRest {
+		if ls.GetField(idx, "params") == LK_TTABLE {
+			if ls.GetField(-1, "*") == LK_TSTRING {
+				r.URL.Path = "/" + ls.ToString(-1)
+			}
+			ls.Pop(1)
+		}
+		ls.Pop(1)
+	}
+	return r, nil
+}
@@ -0,0 +1,103 @@
+//go:build !lk_minimal
+
+package stdlib_test
+
+import (
+	"fmt"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestStaticDir(t *testing.T) {
+	dir := t.TempDir()
+	public := filepath.Join(dir, "public")
+	if err := os.MkdirAll(filepath.Join(public, "css"), 0o755); err != nil {
+		t.Fatal(err)
+	}
+	files := map[string]string{
+		filepath.Join(public, "hello.txt"): "hello world",
+		filepath.Join(public, "css/a.css"): "body{}",
+		filepath.Join(dir, "secret.txt"):   "secret",
+	}
+	for path, content := range files {
+		if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	h := lkHandler(t, fmt.Sprintf(`
+r := http.router()
+r:get('/static/*', http.static_dir('%s'))
+rt r
+`, filepath.ToSlash(public)))
+
+	cases := []struct {
+		path, rng string
+		code      int
+		want      string
+	}{
+		{"/static/hello.txt", "", 200, "hello world"},
+		{"/static/css/a.css", "", 200, "body{}"},
+		{"/static/hello.txt", "bytes=0-4", 206, "hello"},
+		{"/static/hello.txt", "bytes=6-", 206, "world"},
+		{"/static/missing.txt", "", 404, ""},
+		{"/static/../secret.txt", "", 404, ""},
+		{"/static/css/../../secret.txt", "", 404, ""},
+		{"/static/..%2fsecret.txt", "", 404, ""},
+	}
+	for _, c := range cases {
+		r := httptest.NewRequest("GET", c.path, nil)
+		if c.rng != "" {
+			r.Header.Set("Range", c.rng)
+		}
+		w := httptest.NewRecorder()
+		h.ServeHTTP(w, r)
+		body := w.Body.String()
+		if w.Code != c.code || c.want != "" && body != c.want {
+			t.Errorf("%s %s: got %d %q, want %d %q", c.path, c.rng, w.Code, body, c.code, c.want)
+		}
+		if strings.Contains(body, "secret") {
+			t.Errorf("%s: leaked a file outside the root", c.path)
+		}
+		if got := w.Header().Get("X-Content-Type-Options"); got != "nosniff" {
+			t.Errorf("%s: X-Content-Type-Options is %q", c.path, got)
+		}
+	}
+}
+
+func TestProxy(t *testing.T) {
+	upstream := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		body, _ := io.ReadAll(r.Body)
+		w.Header().Set("X-Upstream", "yes")
+		w.WriteHeader(http.StatusAccepted)
+		fmt.Fprintf(w, "%s %s?%s host=%s test=%s fwd=%s body=%s",
+			r.Method, r.URL.Path, r.URL.RawQuery, r.Host,
+			r.Header.Get("X-Test"), r.Header.Get("X-Forwarded-For"), body)
+	}))
+	defer upstream.Close()
+	u, _ := url.Parse(upstream.URL)
+
+	h := lkHandler(t, fmt.Sprintf(`
+r := http.router()
+r:handle('*', '/api/*', http.proxy('%s'))
+rt r
+`, upstream.URL))
+
+	r := httptest.NewRequest("POST", "/api/items?x=1", strings.NewReader("payload"))
+	r.Header.Set("X-Test", "42")
+	w := httptest.NewRecorder()
+	h.ServeHTTP(w, r)
+
+	want := fmt.Sprintf("POST /api/items?x=1 host=%s test=42 fwd=192.0.2.1 body=payload", u.Host)
+	if w.Code != http.StatusAccepted || w.Body.String() != want {
+		t.Errorf("got %d %q, want %d %q", w.Code, w.Body.String(), http.StatusAccepted, want)
+	}
+	if got := w.Header().Get("X-Upstream"); got != "yes" {
+		t.Errorf("upstream header is %q", got)
+	}
+}
@@ -0,0 +1,86 @@
+//go:build !lk_minimal
+
+package stdlib_test
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/state"
+	"github.com/lollipopkit/lk/stdlib"
+)
+
+// lkHandler evaluates src, which returns a handler, and serves it like http.serve
+func lkHandler(t *testing.T, src string) http.Handler {
+	t.Helper()
+	ls := state.New()
+	ls.OpenLibs()
+	if ls.LoadString(src, "handler") != LK_OK || ls.PCall(0, 1, 0) != LK_OK {
+		t.Fatalf("load handler: %s", ls.ToString(-1))
+	}
+	idx := ls.GetTop()
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		stdlib.ServeLk(ls, idx, w, r)
+	})
+}
+
+func TestServeRouter(t *testing.T) {
+	h := lkHandler(t, `
+class Id { 'v': 0 }
+fn Id:__str() {
+    rt 'id-' + str(self.v)
+}
+
+r := http.router()
+r:get('/users/:id', fn(req) => {
+    'status': 201,
+    'headers': {'X-Id': req.params.id, 'X-Num': 7},
+    'body': req.params,
+})
+r:post('/echo', fn(req) => 200, req.body)
+r:get('/num', fn(req) => 200, 42)
+r:get('/obj', fn(req) {
+    shy id = new(Id)
+    id.v = 3
+    rt {'headers': {'X-Obj': id}, 'body': id}
+})
+r:get('/files/*', fn(req) => 200, req.params['*'])
+r:get('/fail', fn(req) => error('boom'))
+rt r
+`)
+	cases := []struct {
+		method, path, body string
+		code               int
+		want               string
+		headers            map[string]string
+	}{
+		{"GET", "/users/7", "", 201, `{"id":"7"}`, map[string]string{"X-Id": "7", "X-Num": "7"}},
+		{"POST", "/echo", "hi there", 200, "hi there", nil},
+		{"GET", "/num", "", 200, "42", nil},
+		{"GET", "/obj", "", 200, "id-3", map[string]string{"X-Obj": "id-3"}},
+		{"GET", "/files/a/b.txt", "", 200, "a/b.txt", nil},
+		{"GET", "/nope", "", 404, "Not Found", nil},
+		{"GET", "/echo", "", 404, "Not Found", nil},
+	}
+	for _, c := range cases {
+		w := httptest.NewRecorder()
+		h.ServeHTTP(w, httptest.NewRequest(c.method, c.path, strings.NewReader(c.body)))
+		if w.Code != c.code || strings.TrimSpace(w.Body.String()) != c.want {
+			t.Errorf("%s %s: got %d %q, want %d %q", c.method, c.path, w.Code, w.Body.String(), c.code, c.want)
+		}
+		for k, v := range c.headers {
+			if got := w.Header().Get(k); got != v {
+				t.Errorf("%s %s: header %s is %q, want %q", c.method, c.path, k, got, v)
+			}
+		}
+	}
+
+	w := httptest.NewRecorder()
+	h.ServeHTTP(w, httptest.NewRequest("GET", "/fail", nil))
+	if w.Code != http.StatusInternalServerError || !strings.Contains(w.Body.String(), "boom") {
+		t.Errorf("GET /fail: got %d %q", w.Code, w.Body.String())
+	}
+}
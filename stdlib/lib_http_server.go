@@ -0,0 +1,226 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"io"
+	"net/http"
+	"strings"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var routerMethods = map[string]GoFunction{
+	"get":    routerMethod("GET"),
+	"post":   routerMethod("POST"),
+	"put":    routerMethod("PUT"),
+	"delete": routerMethod("DELETE"),
+	"patch":  routerMethod("PATCH"),
+	"handle": routerHandle,
+}
+
+// http.serve(addr, handler)
+// handler can be a fn or a router, it receives a request table and returns
+// {'status': 200, 'headers': {...}, 'body': '...'}, or `code, body`.
+// Requests are handled one by one, because the VM is not thread-safe.
+// return err
+func httpServe(ls LkState) int {
+	addr := ls.CheckString(1)
+	if !ls.IsFunction(2) && ls.GetMetafield(2, "__call") == LK_TNIL {
+		return ls.ArgError(2, "handler must be callable")
+	}
+	ls.SetTop(2)
+
+	var mu sync.Mutex
+	err := http.ListenAndServe(addr, http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		mu.Lock()
+		defer mu.Unlock()
+		_serveLk(ls, 2, w, r)
+	}))
+	ls.PushString(err.Error())
+	return 1
+}
+
+// _serveLk calls handler at `idx` and writes its response to `w`
+func _serveLk(ls LkState, idx int, w http.ResponseWriter, r *http.Request) {
+	top := ls.GetTop()
+	defer ls.SetTop(top)
+
+	req, err := genServeReqTable(r)
+	if err != nil {
+		http.Error(w, err.Error(), http.StatusBadRequest)
+		return
+	}
+	ls.PushValue(idx)
+	pushTable(ls, req)
+	if ls.PCall(1, 2, 0) != LK_OK {
+		http.Error(w, ls.ToString2(-1), http.StatusInternalServerError)
+		return
+	}
+	_writeLkResp(ls, w)
+}
+
+// _writeLkResp writes the 2 results on the top of stack
+func _writeLkResp(ls LkState, w http.ResponseWriter) {
+	if !ls.IsTable(-2) {
+		code := int(ls.ToInteger(-2))
+		if code == 0 {
+			code = http.StatusOK
+		}
+		body := _respStr(ls, -1)
+		w.WriteHeader(code)
+		w.Write([]byte(body))
+		return
+	}
+
+	if ls.GetField(-2, "headers") == LK_TTABLE {
+		ls.PushNil()
+		for ls.Next(-2) {
+			w.Header().Set(_respStr(ls, -2), _respStr(ls, -1))
+			ls.Pop(1)
+		}
+	}
+	ls.Pop(1)
+	code := http.StatusOK
+	if ls.GetField(-2, "status") != LK_TNIL {
+		code = int(ls.ToInteger(-1))
+	}
+	ls.Pop(1)
+	ls.GetField(-2, "body")
+	body := _respStr(ls, -1)
+	ls.Pop(1)
+	w.WriteHeader(code)
+	w.Write([]byte(body))
+}
+
+// _respStr converts a body or header value at idx like str() does, nil is ""
+func _respStr(ls LkState, idx int) string {
+	if ls.IsNoneOrNil(idx) {
+		return ""
+	}
+	s := ls.ToString2(idx)
+	ls.Pop(1)
+	return s
+}
+
+func genServeReqTable(r *http.Request) (lkMap, error) {
+	body, err := io.ReadAll(r.Body)
+	if err != nil {
+		return nil, err
+	}
+	query := lkMap{}
+	for k, v := range r.URL.Query() {
+		query[k] = strings.Join(v, ";")
+	}
+	return lkMap{
+		"method":  r.Method,
+		"url":     r.URL.String(),
+		"path":    r.URL.Path,
+		"query":   query,
+		"headers": genHeaderMap(&r.Header),
+		"body":    string(body),
+		"remote":  r.RemoteAddr,
+		"params":  lkMap{},
+	}, nil
+}
+
+// http.router()
+// r := http.router()
+// r:get('/users/:id', fn(req) => 200, req.params.id)
+// http.serve(':8080', r)
+func httpRouter(ls LkState) int {
+	/* methods live in the router itself, like a class instance */
+	ls.NewLib(routerMethods)
+	ls.NewTable()
+	ls.SetField(-2, "routes")
+	ls.PushGoFunction(routerCall)
+	ls.SetField(-2, "__call")
+	return 1
+}
+
+func routerMethod(method string) GoFunction {
+	return func(ls LkState) int {
+		ls.PushString(method)
+		ls.Insert(2)
+		return routerHandle(ls)
+	}
+}
+
+// router:handle(method, pattern, handler)
+// method `*` matches any method
+// pattern: `/a/:name` captures a segment, `/a/*` matches the rest
+func routerHandle(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	method := strings.ToUpper(ls.CheckString(2))
+	pattern := ls.CheckString(3)
+	ls.CheckType(4, LK_TFUNCTION)
+
+	ls.GetField(1, "routes")
+	n := ls.Len2(-1)
+	pushTable(ls, lkMap{"method": method, "pattern": pattern})
+	ls.PushValue(4)
+	ls.SetField(-2, "handler")
+	ls.RawSetI(-2, n)
+	ls.SetTop(1)
+	return 1 /* return router for chaining */
+}
+
+// router(req)
+func routerCall(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	ls.CheckType(2, LK_TTABLE)
+	ls.GetField(2, "method")
+	method := ls.ToString(-1)
+	ls.GetField(2, "path")
+	path := ls.ToString(-1)
+	ls.Pop(2)
+
+	ls.GetField(1, "routes") /* 3 */
+	for i := int64(0); ls.RawGetI(3, i) != LK_TNIL; i++ {
+		ls.GetField(-1, "method")
+		m := ls.ToString(-1)
+		ls.GetField(-2, "pattern")
+		pattern := ls.ToString(-1)
+		ls.Pop(2)
+		if m != "*" && m != method {
+			ls.Pop(1)
+			continue
+		}
+		params, ok := matchRoute(pattern, path)
+		if !ok {
+			ls.Pop(1)
+			continue
+		}
+		pushTable(ls, params)
+		ls.SetField(2, "params")
+		ls.GetField(-1, "handler")
+		ls.PushValue(2)
+		ls.Call(1, 2)
+		return 2
+	}
+	ls.PushInteger(http.StatusNotFound)
+	ls.PushString(http.StatusText(http.StatusNotFound))
+	return 2
+}
+
+func matchRoute(pattern, path string) (map[string]string, bool) {
+	params := map[string]string{}
+	ps := strings.Split(strings.Trim(pattern, "/"), "/")
+	segs := strings.Split(strings.Trim(path, "/"), "/")
+	for i, p := range ps {
+		if p == "*" {
+			params["*"] = strings.Join(segs[i:], "/")
+			return params, true
+		}
+		if i >= len(segs) {
+			return nil, false
+		}
+		if strings.HasPrefix(p, ":") {
+			params[p[1:]] = segs[i]
+		} else if p != segs[i] {
+			return nil, false
+		}
+	}
+	return params, len(ps) == len(segs)
+}
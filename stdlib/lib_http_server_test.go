@@ -0,0 +1,31 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestMatchRoute(t *testing.T) {
+	cases := []struct {
+		pattern, path string
+		params        map[string]string
+	}{
+		{"/", "/", map[string]string{}},
+		{"/users", "/users/", map[string]string{}},
+		{"/users/:id", "/users/7", map[string]string{"id": "7"}},
+		{"/users/:id/posts/:post", "/users/7/posts/9", map[string]string{"id": "7", "post": "9"}},
+		{"/static/*", "/static/css/a.css", map[string]string{"*": "css/a.css"}},
+		{"/static/*", "/static", map[string]string{"*": ""}},
+		{"/users/:id", "/users", nil},
+		{"/users/:id", "/users/7/posts", nil},
+		{"/users", "/posts", nil},
+	}
+	for _, c := range cases {
+		params, ok := matchRoute(c.pattern, c.path)
+		if ok != (c.params != nil) || ok && !reflect.DeepEqual(params, c.params) {
+			t.Errorf("%s %s: got %v %v, want %v", c.pattern, c.path, params, ok, c.params)
+		}
+	}
+}
@@ -0,0 +1,213 @@
+package stdlib
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strings"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+)
+
+var i18nLib = map[string]GoFunction{
+	"load":       i18nLoad,
+	"set_locale": i18nSetLocale,
+	"locale":     i18nLocale,
+	"t":          i18nT,
+}
+
+var (
+	// locale -> key -> str / {plural category: str}
+	i18nCatalogs = map[string]map[string]any{}
+	i18nCurrent  = "en"
+	i18nFallback = "en"
+	i18nMu       sync.RWMutex
+	i18nArgRe    = regexp.MustCompile(`\{(\w+)\}`)
+)
+
+func OpenI18nLib(ls LkState) int {
+	ls.NewLib(i18nLib)
+	return 1
+}
+
+// i18n.load(dir)
+// loads `<locale>.json` files in dir, eg: `locales/zh-CN.json`:
+// {"hello": "你好 {name}", "apple": {"one": "{count} apple", "other": "{count} apples"}}
+// return err
+func i18nLoad(ls LkState) int {
+	dir := ls.CheckString(1)
+	files, err := filepath.Glob(filepath.Join(dir, "*.json"))
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	i18nMu.Lock()
+	defer i18nMu.Unlock()
+	for _, file := range files {
+		data, err := os.ReadFile(file)
+		if err != nil {
+			ls.PushString(err.Error())
+			return 1
+		}
+		catalog := map[string]any{}
+		if err := Json.Unmarshal(data, &catalog); err != nil {
+			ls.PushString(fmt.Sprintf("%s: %v", file, err))
+			return 1
+		}
+		locale := strings.TrimSuffix(filepath.Base(file), ".json")
+		if old, ok := i18nCatalogs[locale]; ok {
+			for k, v := range catalog {
+				old[k] = v
+			}
+		} else {
+			i18nCatalogs[locale] = catalog
+		}
+	}
+	ls.PushNil()
+	return 1
+}
+
+// i18n.set_locale(locale [, fallback])
+func i18nSetLocale(ls LkState) int {
+	i18nMu.Lock()
+	defer i18nMu.Unlock()
+	i18nCurrent = ls.CheckString(1)
+	i18nFallback = ls.OptString(2, i18nFallback)
+	return 0
+}
+
+// i18n.locale()
+func i18nLocale(ls LkState) int {
+	i18nMu.RLock()
+	defer i18nMu.RUnlock()
+	ls.PushString(i18nCurrent)
+	return 1
+}
+
+// i18n.t(key [, args])
+// `{name}` in message is replaced by args.name,
+// args.count selects the plural form.
+// Lookup order: locale, its language (zh-CN -> zh), fallback, then key itself.
+func i18nT(ls LkState) int {
+	key := ls.CheckString(1)
+	args := OptTable(ls, 2, lkMap{})
+	s, _ := i18nTranslate(key, args)
+	ls.PushString(s)
+	return 1
+}
+
+// i18nTranslate is i18n.t for Go side, ok is false if key is missing
+func i18nTranslate(key string, args map[string]any) (string, bool) {
+	i18nMu.RLock()
+	msg, locale := _i18nLookup(key)
+	i18nMu.RUnlock()
+	if s, ok := _i18nFormat(msg, locale, args); ok {
+		return s, true
+	}
+	s, _ := _i18nFormat(key, locale, args)
+	return s, false
+}
+
+// _i18nFormat picks the plural form of msg and fills `{name}` by args
+func _i18nFormat(msg any, locale string, args map[string]any) (string, bool) {
+	if forms, ok := msg.(map[string]any); ok {
+		count, _ := args["count"].(int64)
+		if f, ok := args["count"].(float64); ok {
+			count = int64(f)
+		}
+		msg = forms["other"]
+		if args["count"] != nil {
+			if exact, ok := forms[fmt.Sprintf("=%d", count)]; ok {
+				msg = exact
+			} else if form, ok := forms[pluralCategory(locale, count)]; ok {
+				msg = form
+			}
+		}
+	}
+	s, ok := msg.(string)
+	if !ok {
+		return "", false
+	}
+	return i18nArgRe.ReplaceAllStringFunc(s, func(m string) string {
+		if v, ok := args[m[1:len(m)-1]]; ok {
+			return fmt.Sprint(v)
+		}
+		return m
+	}), true
+}
+
+func _i18nLookup(key string) (any, string) {
+	locales := []string{i18nCurrent}
+	if lang, _, ok := strings.Cut(i18nCurrent, "-"); ok {
+		locales = append(locales, lang)
+	}
+	locales = append(locales, i18nFallback)
+	for _, l := range locales {
+		if msg, ok := i18nCatalogs[l][key]; ok {
+			return msg, l
+		}
+	}
+	return nil, i18nCurrent
+}
+
+// pluralCategory is a subset of CLDR plural rules for integers
+func pluralCategory(locale string, n int64) string {
+	lang, _, _ := strings.Cut(strings.ToLower(locale), "-")
+	if n < 0 {
+		n = -n
+	}
+	switch lang {
+	case "zh", "ja", "ko", "vi", "th", "id":
+		return "other"
+	case "fr", "pt":
+		if n <= 1 {
+			return "one"
+		}
+		return "other"
+	case "ru", "uk", "be", "sr", "hr", "bs":
+		switch {
+		case n%10 == 1 && n%100 != 11:
+			return "one"
+		case n%10 >= 2 && n%10 <= 4 && (n%100 < 12 || n%100 > 14):
+			return "few"
+		}
+		return "many"
+	case "pl":
+		switch {
+		case n == 1:
+			return "one"
+		case n%10 >= 2 && n%10 <= 4 && (n%100 < 12 || n%100 > 14):
+			return "few"
+		}
+		return "many"
+	case "cs", "sk":
+		switch {
+		case n == 1:
+			return "one"
+		case n >= 2 && n <= 4:
+			return "few"
+		}
+		return "other"
+	case "ar":
+		switch {
+		case n == 0:
+			return "zero"
+		case n == 1:
+			return "one"
+		case n == 2:
+			return "two"
+		case n%100 >= 3 && n%100 <= 10:
+			return "few"
+		case n%100 >= 11:
+			return "many"
+		}
+		return "other"
+	}
+	if n == 1 {
+		return "one"
+	}
+	return "other"
+}
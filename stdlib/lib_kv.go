@@ -0,0 +1,385 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"bufio"
+	"encoding/json"
+	"errors"
+	"os"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	jsoniter "github.com/json-iterator/go"
+	. "github.com/lollipopkit/lk/api"
+	. "github.com/lollipopkit/lk/json"
+)
+
+var kvLib = map[string]GoFunction{
+	"open": kvOpen,
+}
+
+var kvMethods = map[string]GoFunction{
+	"get":    kvGet,
+	"set":    kvSet,
+	"delete": kvDelete,
+	"scan":   kvScan,
+	"ttl":    kvTtl,
+	"close":  kvClose,
+}
+
+var (
+	// abs path -> opened store
+	kvStores   = map[string]*kvStore{}
+	kvStoresMu sync.Mutex
+	// keeps ints and floats apart when reading values back
+	kvJson = jsoniter.Config{UseNumber: true}.Froze()
+)
+
+// kvStore keeps all entries in memory and appends every change
+// to a log file. The log is compacted when the store is opened.
+type kvStore struct {
+	mu   sync.Mutex
+	path string
+	file *os.File
+	data map[string]kvRecord
+}
+
+// one line of the log
+type kvRecord struct {
+	Key string `json:"k"`
+	// value encoded as json
+	Val string `json:"v,omitempty"`
+	// expire time in unix nano, 0 means never
+	Exp int64 `json:"e,omitempty"`
+	Del bool  `json:"d,omitempty"`
+}
+
+func (r *kvRecord) expired() bool {
+	return r.Exp != 0 && time.Now().UnixNano() >= r.Exp
+}
+
+func OpenKvLib(ls LkState) int {
+	ls.NewLib(kvLib)
+	return 1
+}
+
+// kv.open(path)
+// return store, err
+func kvOpen(ls LkState) int {
+	path, err := filepath.Abs(ls.CheckString(1))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+
+	kvStoresMu.Lock()
+	defer kvStoresMu.Unlock()
+	if _, ok := kvStores[path]; !ok {
+		store, err := _kvLoad(path)
+		if err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+		kvStores[path] = store
+		_trackOpen(ls, "kv store", path)
+	}
+
+	ls.NewLib(kvMethods)
+	ls.PushString(path)
+	ls.SetField(-2, "path")
+	ls.PushNil()
+	return 2
+}
+
+// _kvLoad replays the log, then rewrites it with live entries only
+func _kvLoad(path string) (*kvStore, error) {
+	store := &kvStore{path: path, data: map[string]kvRecord{}}
+	if f, err := os.Open(path); err == nil {
+		scanner := bufio.NewScanner(f)
+		scanner.Buffer(nil, 64<<20)
+		for scanner.Scan() {
+			var r kvRecord
+			/* a torn last line after a crash is skipped */
+			if Json.Unmarshal(scanner.Bytes(), &r) != nil {
+				continue
+			}
+			if r.Del {
+				delete(store.data, r.Key)
+			} else {
+				store.data[r.Key] = r
+			}
+		}
+		f.Close()
+		if err := scanner.Err(); err != nil {
+			return nil, err
+		}
+	} else if !os.IsNotExist(err) {
+		return nil, err
+	}
+
+	tmp := path + ".tmp"
+	f, err := os.Create(tmp)
+	if err != nil {
+		return nil, err
+	}
+	w := bufio.NewWriter(f)
+	for k, r := range store.data {
+		if r.expired() {
+			delete(store.data, k)
+			continue
+		}
+		line, _ := Json.Marshal(r)
+		w.Write(append(line, '\n'))
+	}
+	if err := w.Flush(); err != nil {
+		f.Close()
+		return nil, err
+	}
+	f.Close()
+	if err := os.Rename(tmp, path); err != nil {
+		return nil, err
+	}
+
+	store.file, err = os.OpenFile(path, os.O_APPEND|os.O_WRONLY, 0644)
+	if err != nil {
+		return nil, err
+	}
+	return store, nil
+}
+
+func (s *kvStore) write(r kvRecord) error {
+	if s.file == nil {
+		return errors.New("kv store is closed")
+	}
+	line, err := Json.Marshal(r)
+	if err != nil {
+		return err
+	}
+	if _, err := s.file.Write(append(line, '\n')); err != nil {
+		return err
+	}
+	if r.Del {
+		delete(s.data, r.Key)
+	} else {
+		s.data[r.Key] = r
+	}
+	return nil
+}
+
+// get returns the live record of key
+func (s *kvStore) get(key string) (kvRecord, bool) {
+	r, ok := s.data[key]
+	if ok && r.expired() {
+		delete(s.data, key)
+		return r, false
+	}
+	return r, ok
+}
+
+func _kvSelf(ls LkState) *kvStore {
+	ls.CheckType(1, LK_TTABLE)
+	ls.GetField(1, "path")
+	path := ls.ToString(-1)
+	ls.Pop(1)
+	kvStoresMu.Lock()
+	defer kvStoresMu.Unlock()
+	store, ok := kvStores[path]
+	if !ok {
+		ls.Error2("kv store is closed")
+	}
+	return store
+}
+
+// _kvEncode encodes value at idx as json
+func _kvEncode(ls LkState, idx int) (string, error) {
+	switch ls.Type(idx) {
+	case LK_TSTRING:
+		return Json.MarshalToString(ls.ToString(idx))
+	case LK_TNUMBER:
+		if ls.IsInteger(idx) {
+			return strconv.FormatInt(ls.ToInteger(idx), 10), nil
+		}
+		s := strconv.FormatFloat(ls.ToNumber(idx), 'f', -1, 64)
+		if !strings.ContainsAny(s, ".eIN") {
+			s += ".0"
+		}
+		return s, nil
+	case LK_TBOOLEAN:
+		return strconv.FormatBool(ls.ToBoolean(idx)), nil
+	case LK_TTABLE:
+		s := ls.ToString2(idx)
+		ls.Pop(1)
+		return s, nil
+	}
+	return "", errors.New("can't store " + ls.TypeName2(idx))
+}
+
+func _kvPushValue(ls LkState, raw string) {
+	var v any
+	if kvJson.UnmarshalFromString(raw, &v) != nil {
+		ls.PushNil()
+		return
+	}
+	pushValue(ls, _kvNumbers(v))
+}
+
+// _kvNumbers turns json numbers into int64 or float64
+func _kvNumbers(v any) any {
+	switch v := v.(type) {
+	case json.Number:
+		if i, err := v.Int64(); err == nil {
+			return i
+		}
+		f, _ := v.Float64()
+		return f
+	case []any:
+		for i := range v {
+			v[i] = _kvNumbers(v[i])
+		}
+	case map[string]any:
+		for k := range v {
+			v[k] = _kvNumbers(v[k])
+		}
+	}
+	return v
+}
+
+// store:get(key)
+// return value or nil if missing or expired
+func kvGet(ls LkState) int {
+	s := _kvSelf(ls)
+	key := ls.CheckString(2)
+	s.mu.Lock()
+	r, ok := s.get(key)
+	s.mu.Unlock()
+	if !ok {
+		ls.PushNil()
+		return 1
+	}
+	_kvPushValue(ls, r.Val)
+	return 1
+}
+
+// store:set(key, value [, ttl])
+// ttl is in seconds, value can be str, num, bool or table
+// return err
+func kvSet(ls LkState) int {
+	s := _kvSelf(ls)
+	key := ls.CheckString(2)
+	ls.CheckAny(3)
+	ttl := ls.OptNumber(4, 0)
+	val, err := _kvEncode(ls, 3)
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	r := kvRecord{Key: key, Val: val}
+	if ttl > 0 {
+		r.Exp = time.Now().Add(time.Duration(ttl * float64(time.Second))).UnixNano()
+	}
+	s.mu.Lock()
+	err = s.write(r)
+	s.mu.Unlock()
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// store:delete(key)
+// return err
+func kvDelete(ls LkState) int {
+	s := _kvSelf(ls)
+	key := ls.CheckString(2)
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if _, ok := s.data[key]; ok {
+		if err := s.write(kvRecord{Key: key, Del: true}); err != nil {
+			ls.PushString(err.Error())
+			return 1
+		}
+	}
+	ls.PushNil()
+	return 1
+}
+
+// store:scan([prefix])
+// return {key: value} of live keys starting with prefix, and the sorted keys
+func kvScan(ls LkState) int {
+	s := _kvSelf(ls)
+	prefix := ls.OptString(2, "")
+	s.mu.Lock()
+	keys := []string{}
+	records := map[string]kvRecord{}
+	for k := range s.data {
+		if !strings.HasPrefix(k, prefix) {
+			continue
+		}
+		if r, ok := s.get(k); ok {
+			keys = append(keys, k)
+			records[k] = r
+		}
+	}
+	s.mu.Unlock()
+	sort.Strings(keys)
+
+	ls.CreateTable(0, len(keys))
+	for _, k := range keys {
+		_kvPushValue(ls, records[k].Val)
+		ls.SetField(-2, k)
+	}
+	pushList(ls, keys)
+	return 2
+}
+
+// store:ttl(key)
+// return remaining seconds, -1 if key never expires, nil if missing
+func kvTtl(ls LkState) int {
+	s := _kvSelf(ls)
+	key := ls.CheckString(2)
+	s.mu.Lock()
+	r, ok := s.get(key)
+	s.mu.Unlock()
+	switch {
+	case !ok:
+		ls.PushNil()
+	case r.Exp == 0:
+		ls.PushInteger(-1)
+	default:
+		ls.PushNumber(time.Until(time.Unix(0, r.Exp)).Seconds())
+	}
+	return 1
+}
+
+// store:close()
+// return err
+func kvClose(ls LkState) int {
+	s := _kvSelf(ls)
+	kvStoresMu.Lock()
+	delete(kvStores, s.path)
+	kvStoresMu.Unlock()
+	_trackClose("kv store", s.path)
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if s.file == nil {
+		ls.PushNil()
+		return 1
+	}
+	err := s.file.Close()
+	s.file = nil
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
@@ -2,10 +2,12 @@ package stdlib
 
 import (
 	"math"
+	"math/big"
 	"math/rand"
 	"time"
 
 	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/decimal"
 	"github.com/lollipopkit/lk/utils"
 )
 
@@ -300,10 +302,15 @@ func mathUlt(ls LkState) int {
 // math.type (x)
 // http://www.lua.org/manual/5.3/manual.html#pdf-math.type
 // lua-5.3.4/src/lmathlib.c#math_type()
+// ints overflowing int64 are 'bigint', `12.34d` is 'decimal'
 func mathType(ls LkState) int {
 	if ls.Type(1) == LK_TNUMBER {
 		if ls.IsInteger(1) {
 			ls.PushString("integer")
+		} else if _, ok := ls.ToPointer(1).(*big.Int); ok {
+			ls.PushString("bigint")
+		} else if _, ok := ls.ToPointer(1).(*decimal.Decimal); ok {
+			ls.PushString("decimal")
 		} else {
 			ls.PushString("float")
 		}
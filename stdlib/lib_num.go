@@ -7,9 +7,13 @@ import (
 )
 
 var numLib = map[string]GoFunction{
-	"abs":  numAbs,
-	"len":  numLen,
-	"char": numChar,
+	"abs":      numAbs,
+	"len":      numLen,
+	"char":     numChar,
+	"currency": numCurrency,
+	"duration": numDuration,
+	"bytes":    numBytes,
+	"ago":      numAgo,
 }
 
 func OpenNumLib(ls LkState) int {
@@ -0,0 +1,140 @@
+package stdlib
+
+import (
+	"fmt"
+	"math/big"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/decimal"
+)
+
+type currencyLocale struct {
+	group, decimal string
+	// symbol after amount, eg: `1.234,50 €`
+	suffix bool
+}
+
+var currencyLocales = map[string]currencyLocale{
+	"en": {",", ".", false},
+	"zh": {",", ".", false},
+	"ja": {",", ".", false},
+	"ko": {",", ".", false},
+	"de": {".", ",", true},
+	"es": {".", ",", true},
+	"it": {".", ",", true},
+	"nl": {".", ",", false},
+	"pt": {".", ",", true},
+	"ru": {" ", ",", true},
+	"fr": {" ", ",", true},
+}
+
+var currencySymbols = map[string]string{
+	"USD": "$", "EUR": "€", "GBP": "£", "JPY": "¥", "CNY": "¥",
+	"KRW": "₩", "INR": "₹", "RUB": "₽", "HKD": "HK$", "CAD": "CA$",
+	"AUD": "A$",
+}
+
+// digits after the decimal point, 2 if absent
+var currencyDigits = map[string]int{
+	"JPY": 0, "KRW": 0, "VND": 0, "CLP": 0, "ISK": 0,
+	"BHD": 3, "KWD": 3, "OMR": 3, "TND": 3, "JOD": 3,
+}
+
+// num.currency(amount, code [, {locale}])
+// amount can be a number, a decimal or a decimal str like '1234.50',
+// use decimals or strs to avoid float errors in money.
+// Rounds half away from zero to the minor unit of currency.
+var numCurrency = NewFn("num.currency").
+	Pos("amount", ArgAny).
+	Pos("code", ArgStr).
+	Named("locale", ArgStr, "en-US").
+	Doc("formats amount in currency `code`, eg: (1234.5):currency('EUR', {'locale': 'de'})").
+	Build(func(ls LkState, args *FnArgs) int {
+		amount, ok := _exactAmount(ls, args.Idx("amount"))
+		if !ok {
+			return ls.ArgError(1, "number or decimal str expected")
+		}
+		code := strings.ToUpper(args.Str("code"))
+		ls.PushString(formatCurrency(amount, code, args.Str("locale")))
+		return 1
+	})
+
+// _exactAmount reads number, decimal or numeric str at idx as an exact rational
+func _exactAmount(ls LkState, idx int) (*big.Rat, bool) {
+	switch x := ls.ToPointer(idx).(type) {
+	case *decimal.Decimal:
+		return x.Rat(), true
+	case *big.Int:
+		return new(big.Rat).SetInt(x), true
+	}
+	var s string
+	switch ls.Type(idx) {
+	case LK_TSTRING:
+		s = strings.TrimSpace(ls.ToString(idx))
+	case LK_TNUMBER:
+		if ls.IsInteger(idx) {
+			s = strconv.FormatInt(ls.ToInteger(idx), 10)
+		} else {
+			/* shortest repr, so 0.1 is 0.1 instead of 0.1000000000000000055... */
+			s = strconv.FormatFloat(ls.ToNumber(idx), 'f', -1, 64)
+		}
+	default:
+		return nil, false
+	}
+	return new(big.Rat).SetString(s)
+}
+
+func formatCurrency(amount *big.Rat, code, locale string) string {
+	digits, ok := currencyDigits[code]
+	if !ok {
+		digits = 2
+	}
+	lang, _, _ := strings.Cut(strings.ToLower(locale), "-")
+	loc, ok := currencyLocales[lang]
+	if !ok {
+		loc = currencyLocales["en"]
+	}
+	symbol, ok := currencySymbols[code]
+	if !ok {
+		symbol = code
+	}
+
+	neg := amount.Sign() < 0
+	abs := new(big.Rat).Abs(amount)
+	/* round half away from zero: floor(abs * 10^digits + 1/2) */
+	scale := new(big.Int).Exp(big.NewInt(10), big.NewInt(int64(digits)), nil)
+	scaled := new(big.Rat).Mul(abs, new(big.Rat).SetInt(scale))
+	scaled.Add(scaled, big.NewRat(1, 2))
+	minor := new(big.Int).Quo(scaled.Num(), scaled.Denom())
+
+	s := minor.String()
+	if len(s) <= digits {
+		s = strings.Repeat("0", digits-len(s)+1) + s
+	}
+	intPart, frac := s[:len(s)-digits], s[len(s)-digits:]
+	var b strings.Builder
+	for i, c := range intPart {
+		if i > 0 && (len(intPart)-i)%3 == 0 {
+			b.WriteString(loc.group)
+		}
+		b.WriteRune(c)
+	}
+	num := b.String()
+	if digits > 0 {
+		num += loc.decimal + frac
+	}
+
+	sign := ""
+	if neg && minor.Sign() != 0 {
+		sign = "-"
+	}
+	if loc.suffix {
+		return fmt.Sprintf("%s%s %s", sign, num, symbol)
+	}
+	if len(symbol) > 1 && symbol == code {
+		return fmt.Sprintf("%s%s %s", sign, symbol, num)
+	}
+	return sign + symbol + num
+}
@@ -0,0 +1,181 @@
+package stdlib
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var durationUnits = []struct {
+	name string
+	ms   int64
+}{
+	{"d", 24 * 60 * 60 * 1000},
+	{"h", 60 * 60 * 1000},
+	{"m", 60 * 1000},
+	{"s", 1000},
+	{"ms", 1},
+}
+
+// built-in messages of num.ago, can be overridden by i18n catalogs
+// with the same keys, eg: `{"ago.minutes": {"one": "...", "other": "..."}}`
+var agoMsgs = map[string]map[string]any{
+	"en": {
+		"ago.now":     "just now",
+		"ago.seconds": map[string]any{"one": "{count} second ago", "other": "{count} seconds ago"},
+		"ago.minutes": map[string]any{"one": "{count} minute ago", "other": "{count} minutes ago"},
+		"ago.hours":   map[string]any{"one": "{count} hour ago", "other": "{count} hours ago"},
+		"ago.days":    map[string]any{"one": "{count} day ago", "other": "{count} days ago"},
+		"ago.months":  map[string]any{"one": "{count} month ago", "other": "{count} months ago"},
+		"ago.years":   map[string]any{"one": "{count} year ago", "other": "{count} years ago"},
+		"ago.future":  "in {time}",
+	},
+	"zh": {
+		"ago.now":     "刚刚",
+		"ago.seconds": "{count} 秒前",
+		"ago.minutes": "{count} 分钟前",
+		"ago.hours":   "{count} 小时前",
+		"ago.days":    "{count} 天前",
+		"ago.months":  "{count} 个月前",
+		"ago.years":   "{count} 年前",
+		"ago.future":  "{time}后",
+	},
+}
+
+// num.duration(ms)
+// return two largest units, eg: 3723000 -> `1h 2m`, 3500 -> `3s 500ms`
+func numDuration(ls LkState) int {
+	ms := ls.CheckInteger(1)
+	if ms == 0 {
+		ls.PushString("0ms")
+		return 1
+	}
+	sign := ""
+	if ms < 0 {
+		sign, ms = "-", -ms
+	}
+	parts := []string{}
+	for _, u := range durationUnits {
+		n := ms / u.ms
+		ms %= u.ms
+		if n > 0 {
+			parts = append(parts, strconv.FormatInt(n, 10)+u.name)
+		} else if len(parts) > 0 {
+			/* `1h 0m 5s` is just `1h` */
+			break
+		}
+		if len(parts) == 2 {
+			break
+		}
+	}
+	ls.PushString(sign + strings.Join(parts, " "))
+	return 1
+}
+
+// num.bytes(n [, si])
+// return size in IEC units like `1.4 GiB`, or SI units like `1.5 GB` if si.
+// Decimal mark follows i18n.locale().
+func numBytes(ls LkState) int {
+	n := ls.CheckNumber(1)
+	si := ls.OptBool(2, false)
+	base, units := 1024.0, []string{"B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"}
+	if si {
+		base, units = 1000.0, []string{"B", "kB", "MB", "GB", "TB", "PB", "EB"}
+	}
+	sign := ""
+	if n < 0 {
+		sign, n = "-", -n
+	}
+	i := 0
+	for n >= base && i < len(units)-1 {
+		n /= base
+		i++
+	}
+	var s string
+	if i == 0 {
+		s = strconv.FormatFloat(n, 'f', -1, 64)
+	} else {
+		s = strconv.FormatFloat(n, 'f', 1, 64)
+		s = strings.Replace(s, ".", _localeDecimal(), 1)
+	}
+	ls.PushString(sign + s + " " + units[i])
+	return 1
+}
+
+// num.ago(ms [, now])
+// ms is a unix time in milliseconds like os.time(),
+// now defaults to os.time().
+// return localized relative time, eg: `3 minutes ago`, `in 2 days`
+func numAgo(ls LkState) int {
+	t := ls.CheckInteger(1)
+	now := ls.OptInteger(2, time.Now().UnixMilli())
+	diff := now - t
+	future := diff < 0
+	if future {
+		diff = -diff
+	}
+	sec := diff / 1000
+
+	key, count := "ago.now", int64(0)
+	switch {
+	case sec < 10:
+	case sec < 60:
+		key, count = "ago.seconds", sec
+	case sec < 60*60:
+		key, count = "ago.minutes", sec/60
+	case sec < 24*60*60:
+		key, count = "ago.hours", sec/(60*60)
+	case sec < 30*24*60*60:
+		key, count = "ago.days", sec/(24*60*60)
+	case sec < 365*24*60*60:
+		key, count = "ago.months", sec/(30*24*60*60)
+	default:
+		key, count = "ago.years", sec/(365*24*60*60)
+	}
+
+	s := _agoMsg(key, map[string]any{"count": count})
+	if future && key != "ago.now" {
+		/* reuse the past form without its suffix: `3 minutes ago` -> `in 3 minutes` */
+		for _, suffix := range []string{" ago", "前"} {
+			s = strings.TrimSuffix(s, suffix)
+		}
+		s = _agoMsg("ago.future", map[string]any{"time": s})
+	}
+	ls.PushString(s)
+	return 1
+}
+
+// _agoMsg looks up key in i18n catalogs, then in agoMsgs
+func _agoMsg(key string, args map[string]any) string {
+	if s, ok := i18nTranslate(key, args); ok {
+		return s
+	}
+	locale := _currentLocale()
+	lang, _, _ := strings.Cut(strings.ToLower(locale), "-")
+	msgs, ok := agoMsgs[lang]
+	if !ok {
+		msgs = agoMsgs["en"]
+	}
+	if s, ok := _i18nFormat(msgs[key], locale, args); ok {
+		return s
+	}
+	return fmt.Sprint(args["count"])
+}
+
+func _currentLocale() string {
+	i18nMu.RLock()
+	defer i18nMu.RUnlock()
+	return i18nCurrent
+}
+
+// _localeDecimal is the decimal mark of i18n.locale()
+func _localeDecimal() string {
+	lang, _, _ := strings.Cut(strings.ToLower(_currentLocale()), "-")
+	if loc, ok := currencyLocales[lang]; ok {
+		return loc.decimal
+	}
+	return "."
+}
@@ -21,25 +21,37 @@ var (
 )
 
 var sysLib = map[string]GoFunction{
-	"time":      osTime,
-	"stat":      osStat,
-	"date":      osDate,
-	"rm":        osRemove,
-	"mv":        osRename,
-	"cp":        osCp,
-	"link":      osLink,
-	"tmp":       osTmpName,
-	"get_env":   osGetEnv,
-	"set_env":   osSetEnv,
-	"exec":      osExecute,
-	"exit":      osExit,
-	"ls":        osLs,
-	"read":      osRead,
-	"write":     osWrite,
-	"sleep":     osSleep,
-	"mkdir":     osMkdir,
-	"rand":      randRandom,
-	"rand_seed": randSeed,
+	"time":       osTime,
+	"stat":       osStat,
+	"date":       osDate,
+	"rm":         osRemove,
+	"mv":         osRename,
+	"cp":         osCp,
+	"link":       osLink,
+	"tmp":        osTmpName,
+	"env":        osEnv,
+	"get_env":    osGetEnv,
+	"set_env":    osSetEnv,
+	"exec":       osExecute,
+	"run":        osRun,
+	"spawn":      osSpawn,
+	"exit":       osExit,
+	"on_error":   osOnError,
+	"verbosity":  osVerbosity,
+	"ls":         osLs,
+	"read":       osRead,
+	"write":      osWrite,
+	"sleep":      osSleep,
+	"mkdir":      osMkdir,
+	"rand":       Deprecated("os.rand", "use rand.int or rand.float", randRandom),
+	"rand_seed":  Deprecated("os.rand_seed", "use rand.seed", randSeed),
+	"cpu_usage":  osCpuUsage,
+	"mem_info":   osMemInfo,
+	"disk_usage": osDiskUsage,
+	"net_ifaces": osNetIfaces,
+	"processes":  osProcesses,
+	"walk":       osWalk,
+	"glob":       osGlob,
 }
 
 func OpenOSLib(ls LkState) int {
@@ -48,8 +60,19 @@ func OpenOSLib(ls LkState) int {
 	return 1
 }
 
+// Args is os.args, os.Args if nil.
+// The cli sets it to `lk`, the file and args after it.
+var Args []string
+
+func _args() []string {
+	if Args == nil {
+		return os.Args
+	}
+	return Args
+}
+
 func pushArgs(ls LkState) {
-	pushList(ls, os.Args)
+	pushList(ls, _args())
 	ls.SetField(-2, "args")
 }
 
@@ -286,6 +309,28 @@ func osGetEnv(ls LkState) int {
 	return 1
 }
 
+// os.env([name])
+// return value of env name, nil if it's not set.
+// Without name, return a table of all env
+func osEnv(ls LkState) int {
+	if ls.IsNoneOrNil(1) {
+		env := map[string]string{}
+		for _, kv := range os.Environ() {
+			if k, v, ok := strings.Cut(kv, "="); ok {
+				env[k] = v
+			}
+		}
+		pushTable(ls, env)
+		return 1
+	}
+	if v, ok := os.LookupEnv(ls.CheckString(1)); ok {
+		ls.PushString(v)
+	} else {
+		ls.PushNil()
+	}
+	return 1
+}
+
 func osSetEnv(ls LkState) int {
 	key := ls.CheckString(1)
 	value := ls.CheckString(2)
@@ -333,6 +378,84 @@ func osExit(ls LkState) int {
 	return 0
 }
 
+// registry key of the fn set by os.on_error
+const LK_ON_ERROR = "_ON_ERROR"
+
+// os.on_error(fn)
+// fn(err) is called instead of printing an uncaught error,
+// the script exits with the code fn returns, 1 if it's not an int.
+// nil removes the handler.
+func osOnError(ls LkState) int {
+	if !ls.IsNil(1) {
+		ls.CheckType(1, LK_TFUNCTION)
+	}
+	ls.SetTop(1)
+	ls.SetField(LK_REGISTRYINDEX, LK_ON_ERROR)
+	return 0
+}
+
+// OnError calls the fn of os.on_error with err.
+// Returns the exit code, false if there is no handler.
+// An error in the handler itself is printed.
+func OnError(ls LkState, err any) (int, bool) {
+	if ls.GetField(LK_REGISTRYINDEX, LK_ON_ERROR) != LK_TFUNCTION {
+		ls.Pop(1)
+		return 0, false
+	}
+	ls.Push(err)
+	if ls.PCall(1, 1, 0) != LK_OK {
+		ls.PrintError(ls.ToString(-1), false)
+		return 1, true
+	}
+	if code, ok := ls.ToIntegerX(-1); ok {
+		return int(code), true
+	}
+	return 1, true
+}
+
+// os.verbosity([args])
+// reads `-q` `--quiet` (-1), `-v` `--verbose` (+1 each, `-vv` is 2) from args,
+// args defaults to os.args.
+// return level, args without these flags
+func osVerbosity(ls LkState) int {
+	var args []string
+	if ls.IsNoneOrNil(1) {
+		args = _args()
+	} else {
+		ls.CheckType(1, LK_TTABLE)
+		for i := int64(0); i < ls.Len2(1); i++ {
+			ls.GetI(1, i)
+			args = append(args, ls.ToString(-1))
+			ls.Pop(1)
+		}
+	}
+	level := 0
+	rest := []any{}
+	for i := 0; i < len(args); i++ {
+		arg := args[i]
+		if arg == "--" {
+			/* flags end here */
+			for _, a := range args[i:] {
+				rest = append(rest, a)
+			}
+			break
+		}
+		switch {
+		case arg == "-q" || arg == "--quiet":
+			level = -1
+		case arg == "--verbose":
+			level++
+		case len(arg) > 1 && strings.Trim(arg, "v") == "-":
+			level += len(arg) - 1
+		default:
+			rest = append(rest, arg)
+		}
+	}
+	ls.PushInteger(int64(level))
+	pushList(ls, rest)
+	return 2
+}
+
 // rand.random ([m [, n]])
 // http://www.lua.org/manual/5.3/manual.html#pdf-math.random
 // lua-5.3.4/src/lmathlib.c#math_random()
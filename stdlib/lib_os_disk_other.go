@@ -0,0 +1,7 @@
+//go:build !linux && !darwin
+
+package stdlib
+
+func _diskUsage(path string) (total, free, avail uint64, err error) {
+	return 0, 0, 0, errMetricsUnsupported
+}
@@ -0,0 +1,14 @@
+//go:build linux || darwin
+
+package stdlib
+
+import "syscall"
+
+func _diskUsage(path string) (total, free, avail uint64, err error) {
+	var st syscall.Statfs_t
+	if err = syscall.Statfs(path, &st); err != nil {
+		return
+	}
+	bsize := uint64(st.Bsize)
+	return st.Blocks * bsize, st.Bfree * bsize, st.Bavail * bsize, nil
+}
@@ -0,0 +1,208 @@
+package stdlib
+
+import (
+	"bufio"
+	"errors"
+	"net"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strconv"
+	"strings"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var errMetricsUnsupported = errors.New("unsupported on " + runtime.GOOS)
+
+// os.cpu_usage ([interval])
+// returns usage percent of all cpus in `interval` ms (default 100)
+func osCpuUsage(ls LkState) int {
+	interval := ls.OptInteger(1, 100)
+	idle1, total1, err := _cpuTimes()
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	time.Sleep(time.Duration(interval) * time.Millisecond)
+	idle2, total2, err := _cpuTimes()
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	usage := 0.0
+	if total2 > total1 {
+		usage = 100 * (1 - float64(idle2-idle1)/float64(total2-total1))
+	}
+	ls.PushNumber(usage)
+	ls.PushNil()
+	return 2
+}
+
+// os.mem_info ()
+// sizes are in bytes
+func osMemInfo(ls LkState) int {
+	info, err := _readKV("/proc/meminfo")
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	total := info["MemTotal"] * 1024
+	free := info["MemFree"] * 1024
+	avail := info["MemAvailable"] * 1024
+	pushTable(ls, lkMap{
+		"total":      total,
+		"free":       free,
+		"available":  avail,
+		"used":       total - avail,
+		"swap_total": info["SwapTotal"] * 1024,
+		"swap_free":  info["SwapFree"] * 1024,
+	})
+	ls.PushNil()
+	return 2
+}
+
+// os.disk_usage ([path])
+func osDiskUsage(ls LkState) int {
+	total, free, avail, err := _diskUsage(ls.OptString(1, "/"))
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	pushTable(ls, lkMap{
+		"total":     int64(total),
+		"free":      int64(free),
+		"available": int64(avail),
+		"used":      int64(total - free),
+	})
+	ls.PushNil()
+	return 2
+}
+
+// os.net_ifaces ()
+func osNetIfaces(ls LkState) int {
+	ifaces, err := net.Interfaces()
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	list := make([]any, 0, len(ifaces))
+	for _, iface := range ifaces {
+		addrs := []string{}
+		if as, err := iface.Addrs(); err == nil {
+			for _, a := range as {
+				addrs = append(addrs, a.String())
+			}
+		}
+		list = append(list, lkMap{
+			"name":  iface.Name,
+			"mac":   iface.HardwareAddr.String(),
+			"mtu":   iface.MTU,
+			"up":    iface.Flags&net.FlagUp != 0,
+			"addrs": addrs,
+		})
+	}
+	pushList(ls, list)
+	ls.PushNil()
+	return 2
+}
+
+// os.processes ()
+// rss is in bytes
+func osProcesses(ls LkState) int {
+	if runtime.GOOS != "linux" {
+		return _pushMetricsErr(ls, errMetricsUnsupported)
+	}
+	dirs, err := os.ReadDir("/proc")
+	if err != nil {
+		return _pushMetricsErr(ls, err)
+	}
+	pageSize := int64(os.Getpagesize())
+	list := []any{}
+	for _, dir := range dirs {
+		pid, err := strconv.ParseInt(dir.Name(), 10, 64)
+		if err != nil {
+			continue
+		}
+		stat, err := os.ReadFile(filepath.Join("/proc", dir.Name(), "stat"))
+		if err != nil {
+			continue /* process exited */
+		}
+		// pid (comm) state ppid ... , comm may contain spaces
+		s := string(stat)
+		l, r := strings.IndexByte(s, '('), strings.LastIndexByte(s, ')')
+		if l < 0 || r < l {
+			continue
+		}
+		fields := strings.Fields(s[r+1:])
+		if len(fields) < 22 {
+			continue
+		}
+		ppid, _ := strconv.ParseInt(fields[1], 10, 64)
+		rss, _ := strconv.ParseInt(fields[21], 10, 64)
+		list = append(list, lkMap{
+			"pid":   pid,
+			"ppid":  ppid,
+			"name":  s[l+1 : r],
+			"state": fields[0],
+			"rss":   rss * pageSize,
+		})
+	}
+	pushList(ls, list)
+	ls.PushNil()
+	return 2
+}
+
+func _pushMetricsErr(ls LkState, err error) int {
+	ls.PushNil()
+	ls.PushString(err.Error())
+	return 2
+}
+
+// _cpuTimes reads the aggregated `cpu` line of /proc/stat
+func _cpuTimes() (idle, total uint64, err error) {
+	if runtime.GOOS != "linux" {
+		return 0, 0, errMetricsUnsupported
+	}
+	f, err := os.Open("/proc/stat")
+	if err != nil {
+		return 0, 0, err
+	}
+	defer f.Close()
+	sc := bufio.NewScanner(f)
+	for sc.Scan() {
+		fields := strings.Fields(sc.Text())
+		if len(fields) < 5 || fields[0] != "cpu" {
+			continue
+		}
+		for i, field := range fields[1:] {
+			v, _ := strconv.ParseUint(field, 10, 64)
+			total += v
+			if i == 3 || i == 4 { /* idle, iowait */
+				idle += v
+			}
+		}
+		return idle, total, nil
+	}
+	return 0, 0, errors.New("no cpu line in /proc/stat")
+}
+
+// _readKV reads `Key: value unit` lines, eg: /proc/meminfo
+func _readKV(path string) (map[string]int64, error) {
+	if runtime.GOOS != "linux" {
+		return nil, errMetricsUnsupported
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	kv := map[string]int64{}
+	for _, line := range strings.Split(string(data), "\n") {
+		key, val, ok := strings.Cut(line, ":")
+		if !ok {
+			continue
+		}
+		fields := strings.Fields(val)
+		if len(fields) == 0 {
+			continue
+		}
+		kv[key], _ = strconv.ParseInt(fields[0], 10, 64)
+	}
+	return kv, nil
+}
@@ -0,0 +1,16 @@
+//go:build lk_minimal
+
+package stdlib
+
+// fns of os left out by the minimal build, which can't touch files
+// or start processes
+var osFullOnly = []string{
+	"rm", "mv", "cp", "link", "tmp", "read", "write", "mkdir",
+	"ls", "stat", "walk", "glob", "exec", "run", "spawn",
+}
+
+func init() {
+	for _, name := range osFullOnly {
+		delete(sysLib, name)
+	}
+}
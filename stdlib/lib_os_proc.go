@@ -0,0 +1,299 @@
+package stdlib
+
+import (
+	"bufio"
+	"bytes"
+	"io"
+	"os"
+	"os/exec"
+	"strings"
+	"sync"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var procMethods = map[string]GoFunction{
+	"read_line":   procReadLine,
+	"lines":       procLines,
+	"read":        procRead,
+	"write":       procWrite,
+	"close_stdin": procCloseStdin,
+	"wait":        procWait,
+	"kill":        procKill,
+}
+
+type process struct {
+	cmd    *exec.Cmd
+	stdin  io.WriteCloser
+	stdout *bufio.Reader
+	stderr *bytes.Buffer
+}
+
+var (
+	procs      = map[int64]*process{}
+	procsMu    sync.Mutex
+	procNextId int64
+)
+
+// os.run(cmd [, args, opts])
+// runs cmd without a shell and waits for it.
+// opts: {env: {k: v}, cwd, stdin}. env is added to the current env.
+// return {status, stdout, stderr}, err. err is set only if cmd can't start
+func osRun(ls LkState) int {
+	cmd := _procCmd(ls)
+	if ls.IsTable(3) {
+		if ls.GetField(3, "stdin") != LK_TNIL {
+			cmd.Stdin = strings.NewReader(ls.ToString(-1))
+		}
+		ls.Pop(1)
+	}
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		if _, ok := err.(*exec.ExitError); !ok {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+	}
+	pushTable(ls, map[string]any{
+		"status": cmd.ProcessState.ExitCode(),
+		"stdout": stdout.String(),
+		"stderr": stderr.String(),
+	})
+	ls.PushNil()
+	return 2
+}
+
+// os.spawn(cmd [, args, opts])
+// starts cmd without waiting, opts are like os.run except `stdin`:
+// write to the process by p:write() instead.
+// for line in p:lines() {} reads stdout as it's printed.
+// return p, err. p.pid is the process id
+func osSpawn(ls LkState) int {
+	cmd := _procCmd(ls)
+	p := &process{cmd: cmd, stderr: &bytes.Buffer{}}
+	cmd.Stderr = p.stderr
+	stdin, err := cmd.StdinPipe()
+	if err == nil {
+		p.stdin = stdin
+		var stdout io.ReadCloser
+		stdout, err = cmd.StdoutPipe()
+		p.stdout = bufio.NewReader(stdout)
+	}
+	if err == nil {
+		err = cmd.Start()
+	}
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+
+	procsMu.Lock()
+	procNextId++
+	id := procNextId
+	procs[id] = p
+	procsMu.Unlock()
+	_trackOpen(ls, "process", cmd.Process.Pid)
+
+	ls.NewLib(procMethods)
+	ls.PushInteger(id)
+	ls.SetField(-2, "id")
+	ls.PushInteger(int64(cmd.Process.Pid))
+	ls.SetField(-2, "pid")
+	ls.PushNil()
+	return 2
+}
+
+// _procCmd reads cmd, args and opts (but stdin) at 1, 2, 3
+func _procCmd(ls LkState) *exec.Cmd {
+	name := ls.CheckString(1)
+	args := []string{}
+	if !ls.IsNoneOrNil(2) {
+		ls.CheckType(2, LK_TTABLE)
+		for i := int64(0); i < ls.Len2(2); i++ {
+			ls.GetI(2, i)
+			args = append(args, _cellStr(ls))
+		}
+	}
+	cmd := exec.Command(name, args...)
+	if ls.IsNoneOrNil(3) {
+		return cmd
+	}
+	ls.CheckType(3, LK_TTABLE)
+	if ls.GetField(3, "cwd") == LK_TSTRING {
+		cmd.Dir = ls.ToString(-1)
+	}
+	ls.Pop(1)
+	if ls.GetField(3, "env") == LK_TTABLE {
+		cmd.Env = os.Environ()
+		ls.PushNil()
+		for ls.Next(-2) {
+			key := ls.ToString2(-2)
+			ls.Pop(1)
+			cmd.Env = append(cmd.Env, key+"="+_cellStr(ls))
+		}
+	}
+	ls.Pop(1)
+	return cmd
+}
+
+func _procSelf(ls LkState) *process {
+	ls.CheckType(1, LK_TTABLE)
+	ls.GetField(1, "id")
+	id := ls.ToInteger(-1)
+	ls.Pop(1)
+	procsMu.Lock()
+	defer procsMu.Unlock()
+	p, ok := procs[id]
+	if !ok {
+		ls.Error2("process is already waited")
+	}
+	return p
+}
+
+// p:read_line()
+// return line without `\n`, nil at the end of stdout
+func procReadLine(ls LkState) int {
+	p := _procSelf(ls)
+	line, err := p.stdout.ReadString('\n')
+	if err != nil && line == "" {
+		ls.PushNil()
+		return 1
+	}
+	ls.PushString(strings.TrimSuffix(line, "\n"))
+	return 1
+}
+
+// p:lines()
+// returns an iterable over lines of stdout:
+// for line in p:lines() {}
+func procLines(ls LkState) int {
+	_procSelf(ls)
+	ls.CreateTable(0, 1)
+	ls.PushValue(1)
+	ls.SetField(-2, "proc")
+	ls.PushGoFunction(procLinesIter)
+	ls.SetField(-2, "__iter")
+	return 1
+}
+
+func procLinesIter(ls LkState) int {
+	ls.GetField(1, "proc")
+	ls.Replace(1)
+	ls.PushGoFunction(procReadLine)
+	ls.PushValue(1)
+	ls.PushNil()
+	return 3
+}
+
+// p:read()
+// return the rest of stdout, err
+func procRead(ls LkState) int {
+	p := _procSelf(ls)
+	data, err := io.ReadAll(p.stdout)
+	ls.PushString(string(data))
+	if err != nil {
+		ls.PushString(err.Error())
+	} else {
+		ls.PushNil()
+	}
+	return 2
+}
+
+// p:write(data)
+// return err
+func procWrite(ls LkState) int {
+	p := _procSelf(ls)
+	if _, err := io.WriteString(p.stdin, ls.CheckString(2)); err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// p:close_stdin()
+// the process reads EOF after it
+// return err
+func procCloseStdin(ls LkState) int {
+	p := _procSelf(ls)
+	if err := p.stdin.Close(); err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// p:wait()
+// closes stdin and waits for the process, stdout not read is dropped.
+// return status, stderr. status is -1 if it's killed by a signal
+func procWait(ls LkState) int {
+	p := _procSelf(ls)
+	p.stdin.Close()
+	io.Copy(io.Discard, p.stdout)
+	p.cmd.Wait()
+	_trackClose("process", p.cmd.Process.Pid)
+	ls.GetField(1, "id")
+	procsMu.Lock()
+	delete(procs, ls.ToInteger(-1))
+	procsMu.Unlock()
+	ls.Pop(1)
+	ls.PushInteger(int64(p.cmd.ProcessState.ExitCode()))
+	ls.PushString(p.stderr.String())
+	return 2
+}
+
+// _drainProcs closes stdin of processes not waited yet, and waits
+// for them to exit. The ones left after timeout are killed.
+// return count of killed processes
+func _drainProcs(timeout time.Duration) int {
+	procsMu.Lock()
+	left := procs
+	procs = map[int64]*process{}
+	procsMu.Unlock()
+
+	done := make(chan *process, len(left))
+	for _, p := range left {
+		p := p
+		_trackClose("process", p.cmd.Process.Pid)
+		p.stdin.Close()
+		go func() {
+			p.cmd.Wait()
+			done <- p
+		}()
+	}
+	deadline := time.After(timeout)
+	for len(left) > 0 {
+		select {
+		case p := <-done:
+			for id := range left {
+				if left[id] == p {
+					delete(left, id)
+				}
+			}
+		case <-deadline:
+			for _, p := range left {
+				p.cmd.Process.Kill()
+			}
+			return len(left)
+		}
+	}
+	return 0
+}
+
+// p:kill()
+// return err
+func procKill(ls LkState) int {
+	p := _procSelf(ls)
+	if err := p.cmd.Process.Kill(); err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
@@ -0,0 +1,183 @@
+package stdlib
+
+import (
+	"io/fs"
+	"os"
+	"path"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// os.walk(dir)
+// returns an iterable, entries are read lazily in lexical order:
+// for path, entry in os.walk('src') {}
+// entry: {path, name, size, mode, time, is_dir}, unreadable dirs are skipped
+func osWalk(ls LkState) int {
+	root := ls.CheckString(1)
+	ls.CreateTable(0, 2)
+	ls.PushString(root)
+	ls.SetField(-2, "root")
+	ls.PushGoFunction(osWalkIter)
+	ls.SetField(-2, "__iter")
+	return 1
+}
+
+func osWalkIter(ls LkState) int {
+	ls.GetField(1, "root")
+	root := ls.ToString(-1)
+	ls.Pop(1)
+
+	/* paths left to visit, popped from the end */
+	pending := []string{}
+	if entries, err := os.ReadDir(root); err == nil {
+		pending = _walkPush(pending, root, entries)
+	}
+	next := func(ls LkState) int {
+		for len(pending) > 0 {
+			p := pending[len(pending)-1]
+			pending = pending[:len(pending)-1]
+			info, err := os.Lstat(p)
+			if err != nil {
+				continue
+			}
+			if info.IsDir() {
+				if entries, err := os.ReadDir(p); err == nil {
+					pending = _walkPush(pending, p, entries)
+				}
+			}
+			ls.PushString(p)
+			pushTable(ls, _walkEntry(p, info))
+			return 2
+		}
+		ls.PushNil()
+		return 1
+	}
+	ls.PushGoFunction(next)
+	ls.PushNil()
+	ls.PushNil()
+	return 3
+}
+
+// _walkPush pushes entries in reverse, so they pop in order
+func _walkPush(pending []string, dir string, entries []fs.DirEntry) []string {
+	for i := len(entries) - 1; i >= 0; i-- {
+		pending = append(pending, filepath.Join(dir, entries[i].Name()))
+	}
+	return pending
+}
+
+func _walkEntry(p string, info fs.FileInfo) lkMap {
+	return lkMap{
+		"path":   p,
+		"name":   info.Name(),
+		"size":   info.Size(),
+		"mode":   info.Mode().String(),
+		"time":   info.ModTime().UnixMilli(),
+		"is_dir": info.IsDir(),
+	}
+}
+
+// os.glob(pattern)
+// `*`, `?` and `[...]` match inside one path segment,
+// `**` matches zero or more directories: `src/**/*.lk`
+// return sorted paths, err
+func osGlob(ls LkState) int {
+	pattern := filepath.ToSlash(ls.CheckString(1))
+	segs := strings.Split(pattern, "/")
+
+	/* walk from the longest literal prefix */
+	n := 0
+	for n < len(segs)-1 && !_hasGlobMeta(segs[n]) {
+		n++
+	}
+	root := strings.Join(segs[:n], "/")
+	if root == "" && n > 0 {
+		root = "/"
+	}
+	pat := segs[n:]
+	for _, seg := range pat {
+		if _, err := path.Match(seg, ""); err != nil {
+			ls.PushNil()
+			ls.PushString(err.Error())
+			return 2
+		}
+	}
+
+	walkRoot := root
+	if walkRoot == "" {
+		walkRoot = "."
+	}
+	matches := []string{}
+	err := filepath.WalkDir(walkRoot, func(p string, d fs.DirEntry, err error) error {
+		if err != nil {
+			/* skip unreadable dirs, fail on a missing root */
+			if p == walkRoot {
+				return err
+			}
+			return nil
+		}
+		rel, _ := filepath.Rel(walkRoot, p)
+		if rel == "." {
+			return nil
+		}
+		relSegs := strings.Split(filepath.ToSlash(rel), "/")
+		if _globMatch(pat, relSegs) {
+			if root == "" {
+				matches = append(matches, filepath.ToSlash(rel))
+			} else {
+				matches = append(matches, path.Join(root, filepath.ToSlash(rel)))
+			}
+		}
+		if d.IsDir() && !_globCanPrefix(pat, relSegs) {
+			return filepath.SkipDir
+		}
+		return nil
+	})
+	if err != nil && !os.IsNotExist(err) {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	sort.Strings(matches)
+	pushList(ls, matches)
+	ls.PushNil()
+	return 2
+}
+
+func _hasGlobMeta(seg string) bool {
+	return seg == "**" || strings.ContainsAny(seg, "*?[")
+}
+
+func _globMatch(pat, segs []string) bool {
+	if len(pat) == 0 {
+		return len(segs) == 0
+	}
+	if pat[0] == "**" {
+		for i := 0; i <= len(segs); i++ {
+			if _globMatch(pat[1:], segs[i:]) {
+				return true
+			}
+		}
+		return false
+	}
+	if len(segs) == 0 {
+		return false
+	}
+	ok, _ := path.Match(pat[0], segs[0])
+	return ok && _globMatch(pat[1:], segs[1:])
+}
+
+// _globCanPrefix reports whether files under dir segs may still match
+func _globCanPrefix(pat, segs []string) bool {
+	if len(segs) == 0 || (len(pat) > 0 && pat[0] == "**") {
+		return true
+	}
+	if len(pat) == 0 {
+		return false
+	}
+	ok, _ := path.Match(pat[0], segs[0])
+	return ok && _globCanPrefix(pat[1:], segs[1:])
+}
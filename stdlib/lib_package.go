@@ -6,7 +6,9 @@ import (
 
 	. "github.com/lollipopkit/lk/api"
 	"github.com/lollipopkit/lk/consts"
+	"github.com/lollipopkit/lk/exe"
 	"github.com/lollipopkit/lk/mods"
+	"github.com/lollipopkit/lk/project"
 )
 
 /* key, in the registry, for table of loaded modules */
@@ -15,6 +17,15 @@ const LUA_LOADED_TABLE = "_LOADED"
 /* key, in the registry, for table of preloaded loaders */
 const LUA_PRELOAD_TABLE = "_PRELOAD"
 
+/* key, in the registry, for table of scheme handlers */
+const LK_SCHEMES_TABLE = "_SCHEMES"
+
+/* key, in the registry, for table of sources fetched by scheme handlers */
+const LK_SCHEME_CACHE_TABLE = "_SCHEME_CACHE"
+
+/* key, in the registry, for table of pinned sha256 */
+const LK_INTEGRITY_TABLE = "_INTEGRITY"
+
 const (
 	LUA_DIRSEP    = string(os.PathSeparator)
 	LUA_PATH_SEP  = ";"
@@ -24,13 +35,18 @@ const (
 )
 
 var pkgFuncs = map[string]GoFunction{
-	"search": pkgSearchPath,
+	"search":          pkgSearchPath,
+	"register_scheme": pkgRegisterScheme,
+	"pin":             pkgPin,
+	"view":            pkgView,
 	/* placeholders */
-	"preload":   nil,
-	"cpath":     nil,
-	"path":      nil,
-	"searchers": nil,
-	"loaded":    nil,
+	"preload":    nil,
+	"cpath":      nil,
+	"path":       nil,
+	"searchers":  nil,
+	"loaded":     nil,
+	"integrity":  nil,
+	"allow_http": nil,
 }
 
 var llFuncs = map[string]GoFunction{
@@ -53,6 +69,12 @@ func OpenPackageLib(ls LkState) int {
 	/* set field 'preload' */
 	ls.GetSubTable(LK_REGISTRYINDEX, LUA_PRELOAD_TABLE)
 	ls.SetField(-2, "preload")
+	/* set field 'integrity' */
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_INTEGRITY_TABLE)
+	ls.SetField(-2, "integrity")
+	/* remote imports need https unless the script opts in */
+	ls.PushBoolean(false)
+	ls.SetField(-2, "allow_http")
 	ls.PushGlobalTable()
 	ls.PushValue(-2)        /* set 'package' as upvalue for next lib */
 	ls.SetFuncs(llFuncs, 1) /* open lib into global table */
@@ -63,6 +85,9 @@ func OpenPackageLib(ls LkState) int {
 func createSearchersTable(ls LkState) {
 	searchers := []GoFunction{
 		preloadSearcher,
+		bundleSearcher,
+		schemeSearcher,
+		projectSearcher,
 		lkSearcher,
 	}
 	/* create 'searchers' table */
@@ -85,6 +110,57 @@ func preloadSearcher(ls LkState) int {
 	return 1
 }
 
+// bundleSearcher loads modules compiled into the running bundle or executable
+func bundleSearcher(ls LkState) int {
+	name := ls.CheckString(1)
+	p, _ := exe.Current()
+	if p == nil {
+		return 0
+	}
+	chunk, ok := p.Module(name)
+	if !ok {
+		return 0
+	}
+	if ls.Load(chunk, name, "b") != LK_OK {
+		return ls.Error2("error loading bundled module '%s':\n\t%s", name, ls.CheckString(-1))
+	}
+	ls.PushString(name)
+	return 2
+}
+
+// Project is the manifest imports are resolved through,
+// the cli sets it to the lk.json found from the script dir.
+var Project *project.Manifest
+
+func projectSearcher(ls LkState) int {
+	name := ls.CheckString(1)
+	if Project == nil {
+		return 0
+	}
+	errMsg := ""
+	for _, filename := range Project.Candidates(name) {
+		c, err := os.ReadFile(filename)
+		if os.IsNotExist(err) {
+			errMsg += "\n\tno file '" + filename + "'"
+			continue
+		}
+		if err != nil {
+			return ls.Error2("error loading module '%s' from file '%s':\n\t%v", name, filename, err)
+		}
+		if ls.Load(c, filename, "bt") != LK_OK {
+			return ls.Error2("error loading module '%s' from file '%s':\n\t%s",
+				name, filename, ls.CheckString(-1))
+		}
+		ls.PushString(filename)
+		return 2
+	}
+	if errMsg == "" {
+		return 0
+	}
+	ls.PushString(errMsg)
+	return 1
+}
+
 func lkSearcher(ls LkState) int {
 	name := ls.CheckString(1)
 	ls.GetField(LkUpvalueIndex(1), "path")
@@ -154,10 +230,20 @@ func _searchPath(name, path, sep, dirSep string) (content []byte, fname, errMsg
 	return nil, "", errMsg
 }
 
-// require (modname)
+// require (modname [, opts])
 // http://www.lua.org/manual/5.3/manual.html#pdf-require
+// with opts, a view of the module is returned, see pkg.view
 func pkgImport(ls LkState) int {
 	name := ls.CheckString(1)
+	if !ls.IsNoneOrNil(2) { /* import a view of the module */
+		ls.CheckType(2, LK_TTABLE)
+		ls.SetTop(2)
+		ls.PushValue(LkUpvalueIndex(1))
+		ls.PushGoClosure(pkgImport, 1)
+		ls.PushValue(1)
+		ls.Call(1, 1)
+		return _pushView(ls, 3, 2)
+	}
 	ls.SetTop(1) /* LOADED table will be at index 2 */
 	ls.GetField(LK_REGISTRYINDEX, LUA_LOADED_TABLE)
 	ls.GetField(2, name)  /* LOADED[name] */
@@ -0,0 +1,61 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+)
+
+func init() {
+	schemeHandlers["http"] = fetchRemote
+	schemeHandlers["https"] = fetchRemote
+	schemeStores["http"] = storeRemote
+	schemeStores["https"] = storeRemote
+}
+
+// fetchRemote downloads module from url, or reads it from the disk cache.
+// Remote modules are cached forever, use `pkg.pin` to detect changes.
+func fetchRemote(url string) ([]byte, error) {
+	if file := remoteCacheFile(url); file != "" {
+		if src, err := os.ReadFile(file); err == nil {
+			return src, nil
+		}
+	}
+
+	resp, err := client.Get(url)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		return nil, fmt.Errorf("GET %s: %s", url, resp.Status)
+	}
+	return io.ReadAll(resp.Body)
+}
+
+// storeRemote caches the module on disk, only called after `pkg.pin` passes
+func storeRemote(url string, src []byte) {
+	file := remoteCacheFile(url)
+	if file == "" {
+		return
+	}
+	if _, err := os.Stat(file); err == nil {
+		return /* read from it */
+	}
+	if err := os.MkdirAll(filepath.Dir(file), 0755); err == nil {
+		os.WriteFile(file, src, 0644)
+	}
+}
+
+func remoteCacheFile(url string) string {
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "lk", "modules", fmt.Sprintf("%x.lk", sha256.Sum256([]byte(url))))
+}
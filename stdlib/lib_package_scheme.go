@@ -0,0 +1,170 @@
+package stdlib
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"regexp"
+	"strings"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// SchemeHandler returns the source of module `path`,
+// eg: `pkg:utils` calls the handler of `pkg` with `utils`.
+type SchemeHandler func(path string) ([]byte, error)
+
+var (
+	schemeRe         = regexp.MustCompile(`^([a-zA-Z][a-zA-Z0-9+.-]*):(.+)$`)
+	schemeHandlers   = map[string]SchemeHandler{}
+	schemeHandlersMu sync.Mutex
+	/* called with sources of Go handlers once they pass `pkg.pin`, eg: to cache them on disk */
+	schemeStores = map[string]func(path string, src []byte){}
+)
+
+// RegisterScheme makes `import 'scheme:path'` call h.
+// Handlers registered by scripts via `pkg.register_scheme` take precedence.
+func RegisterScheme(scheme string, h SchemeHandler) {
+	schemeHandlersMu.Lock()
+	defer schemeHandlersMu.Unlock()
+	schemeHandlers[scheme] = h
+}
+
+// pkg.register_scheme (scheme, handler)
+// handler(path) returns source string, or nil and an error message
+func pkgRegisterScheme(ls LkState) int {
+	scheme := ls.CheckString(1)
+	if !schemeRe.MatchString(scheme + ":_") {
+		return ls.ArgError(1, "invalid scheme")
+	}
+	ls.CheckType(2, LK_TFUNCTION)
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_SCHEMES_TABLE)
+	ls.PushValue(2)
+	ls.SetField(-2, scheme)
+	return 0
+}
+
+// pkg.pin (name, sha256)
+// the module `name` will only be loaded if its sha256 matches
+func pkgPin(ls LkState) int {
+	name := ls.CheckString(1)
+	hash := strings.ToLower(ls.CheckString(2))
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_INTEGRITY_TABLE)
+	ls.PushString(hash)
+	ls.SetField(-2, name)
+	return 0
+}
+
+func schemeSearcher(ls LkState) int {
+	name := ls.CheckString(1)
+	m := schemeRe.FindStringSubmatch(name)
+	if m == nil {
+		return 0 /* not a scheme import, leave it to other searchers */
+	}
+	scheme, path := m[1], m[2]
+
+	var store func()
+	src, cached := _schemeCached(ls, name)
+	if !cached {
+		var err error
+		src, store, err = _schemeFetch(ls, scheme, path, name)
+		if err != nil {
+			return ls.Error2("error loading module '%s':\n\t%v", name, err)
+		}
+		if src == nil {
+			ls.PushString("\n\tno handler for scheme '" + scheme + "'")
+			return 1
+		}
+	}
+
+	if err := _checkIntegrity(ls, name, src); err != nil {
+		return ls.Error2("error loading module '%s':\n\t%v", name, err)
+	}
+	if store != nil {
+		store()
+	}
+	/* fetched once per state */
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_SCHEME_CACHE_TABLE)
+	ls.PushString(string(src))
+	ls.SetField(-2, name)
+	ls.Pop(1)
+
+	if ls.Load(src, name, "t") == LK_OK {
+		ls.PushString(name)
+		return 2
+	}
+	return ls.Error2("error loading module '%s':\n\t%s", name, ls.CheckString(-1))
+}
+
+func _schemeCached(ls LkState, name string) ([]byte, bool) {
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_SCHEME_CACHE_TABLE)
+	defer ls.Pop(2)
+	if ls.GetField(-1, name) != LK_TSTRING {
+		return nil, false
+	}
+	return []byte(ls.ToString(-1)), true
+}
+
+// _schemeFetch returns nil source without error if no handler is registered.
+// `store` saves the source, call it after the integrity check.
+func _schemeFetch(ls LkState, scheme, path, name string) (src []byte, store func(), err error) {
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_SCHEMES_TABLE)
+	defer ls.Pop(1)
+	if ls.GetField(-1, scheme) == LK_TFUNCTION {
+		ls.PushString(path)
+		ls.Call(1, 2)
+		defer ls.Pop(2)
+		if src, ok := ls.ToStringX(-2); ok && !ls.IsNil(-2) {
+			return []byte(src), nil, nil
+		}
+		if ls.IsNil(-1) {
+			return nil, nil, fmt.Errorf("handler of '%s' returned nothing", scheme)
+		}
+		return nil, nil, fmt.Errorf("%s", ls.ToString(-1))
+	}
+	ls.Pop(1)
+
+	schemeHandlersMu.Lock()
+	h, ok := schemeHandlers[scheme]
+	save := schemeStores[scheme]
+	schemeHandlersMu.Unlock()
+	if !ok {
+		return nil, nil, nil
+	}
+	if scheme == "http" {
+		ls.GetField(LkUpvalueIndex(1), "allow_http")
+		allow := ls.ToBoolean(-1)
+		ls.Pop(1)
+		if !allow {
+			return nil, nil, fmt.Errorf("plain http import is refused, use https or set `pkg.allow_http = true`")
+		}
+	}
+	if scheme == "http" || scheme == "https" {
+		// remote handlers need the whole url
+		path = name
+	}
+	src, err = h(path)
+	if err == nil && src == nil {
+		src = []byte{}
+	}
+	if err == nil && save != nil {
+		store = func() { save(path, src) }
+	}
+	return src, store, err
+}
+
+func _checkIntegrity(ls LkState, name string, src []byte) error {
+	ls.GetSubTable(LK_REGISTRYINDEX, LK_INTEGRITY_TABLE)
+	defer ls.Pop(1)
+	if ls.GetField(-1, name) == LK_TNIL {
+		ls.Pop(1)
+		return nil
+	}
+	want := strings.ToLower(ls.ToString(-1))
+	ls.Pop(1)
+	want = strings.TrimPrefix(want, "sha256-")
+	if got := fmt.Sprintf("%x", sha256.Sum256(src)); got != want {
+		return fmt.Errorf("integrity check failed: expect sha256 %s, got %s", want, got)
+	}
+	return nil
+}
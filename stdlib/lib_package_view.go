@@ -0,0 +1,126 @@
+package stdlib
+
+import (
+	. "github.com/lollipopkit/lk/api"
+)
+
+// readOnlyHidden are fns hidden by `read_only` views of a lib,
+// the ones changing files, env or processes
+var readOnlyHidden = map[string][]string{
+	"os": {
+		"rm", "mv", "cp", "link", "write", "mkdir", "tmp",
+		"set_env", "exec", "run", "spawn", "exit", "on_error",
+	},
+}
+
+// pkg.view (mod, opts)
+// returns a copy of module `mod` with fewer capabilities,
+// to be passed to code which is not fully trusted.
+// opts:
+//   - only: list of names, the others are hidden
+//   - hide: list of names to hide
+//   - read_only: hides fns changing things outside the script,
+//     only known for `os`, other modules raise an error
+//   - check: fn(name, ...args) => ok, reason. Called before each fn,
+//     the call raises an error if ok is false
+//
+// Nested tables are not copied, so they are shared with `mod`.
+// return view
+func pkgView(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	ls.CheckType(2, LK_TTABLE)
+	return _pushView(ls, 1, 2)
+}
+
+func _pushView(ls LkState, mod, opts int) int {
+	mod, opts = ls.AbsIndex(mod), ls.AbsIndex(opts)
+	hidden := map[string]bool{}
+	if ls.GetField(opts, "read_only") == LK_TBOOLEAN && ls.ToBoolean(-1) {
+		name := _loadedName(ls, mod)
+		fns, ok := readOnlyHidden[name]
+		if !ok {
+			return ls.Error2("pkg.view: read_only is unknown for module '%s'", name)
+		}
+		for _, fn := range fns {
+			hidden[fn] = true
+		}
+	}
+	ls.Pop(1)
+	for _, fn := range _strListField(ls, opts, "hide") {
+		hidden[fn] = true
+	}
+	var only map[string]bool
+	if ls.GetField(opts, "only") == LK_TTABLE {
+		only = map[string]bool{}
+		for _, fn := range _strListField(ls, opts, "only") {
+			only[fn] = true
+		}
+	}
+	ls.Pop(1)
+	hasCheck := ls.GetField(opts, "check") == LK_TFUNCTION
+	check := ls.GetTop()
+
+	ls.NewTable()
+	ls.PushNil()
+	for ls.Next(mod) {
+		if ls.Type(-2) == LK_TSTRING {
+			name := ls.ToString(-2)
+			if hidden[name] || only != nil && !only[name] {
+				ls.Pop(1)
+				continue
+			}
+			if hasCheck && ls.IsFunction(-1) {
+				ls.PushValue(check)
+				ls.Insert(-2)
+				ls.PushString(name)
+				ls.PushGoClosure(_viewCall, 3)
+			}
+		}
+		ls.PushValue(-2)
+		ls.Insert(-2)
+		ls.SetTable(-4)
+	}
+	ls.Replace(check)
+	ls.SetTop(check)
+	return 1
+}
+
+// _viewCall calls the check fn, then the wrapped one.
+// upvalues: check, fn, name
+func _viewCall(ls LkState) int {
+	n := ls.GetTop()
+	ls.PushValue(LkUpvalueIndex(1))
+	ls.PushValue(LkUpvalueIndex(3))
+	for i := 1; i <= n; i++ {
+		ls.PushValue(i)
+	}
+	ls.Call(n+1, 2)
+	if !ls.ToBoolean(-2) {
+		reason := "denied"
+		if ls.IsString(-1) {
+			reason = ls.ToString(-1)
+		}
+		return ls.Error2("'%s' is not allowed: %s", ls.ToString(LkUpvalueIndex(3)), reason)
+	}
+	ls.Pop(2)
+	ls.PushValue(LkUpvalueIndex(2))
+	ls.Insert(1)
+	ls.Call(n, LK_MULTRET)
+	return ls.GetTop()
+}
+
+// _loadedName is the name `mod` is loaded as, "?" if it's not loaded
+func _loadedName(ls LkState, mod int) string {
+	defer ls.Pop(1)
+	ls.GetField(LK_REGISTRYINDEX, LUA_LOADED_TABLE)
+	ls.PushNil()
+	for ls.Next(-2) {
+		if ls.Type(-2) == LK_TSTRING && ls.Compare(-1, mod, LK_OPEQ) {
+			name := ls.ToString(-2)
+			ls.Pop(2)
+			return name
+		}
+		ls.Pop(1)
+	}
+	return "?"
+}
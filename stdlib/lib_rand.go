@@ -0,0 +1,76 @@
+package stdlib
+
+import (
+	"math"
+	"math/rand"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// OpenRandLib gives each state its own generator,
+// states on other goroutines never wait for each other.
+func OpenRandLib(ls LkState) int {
+	r := rand.New(rand.NewSource(time.Now().UnixNano()))
+	ls.NewLib(map[string]GoFunction{
+		"int":     func(ls LkState) int { return randInt(ls, r) },
+		"float":   func(ls LkState) int { return randFloat(ls, r) },
+		"choice":  func(ls LkState) int { return randChoice(ls, r) },
+		"shuffle": func(ls LkState) int { return randShuffle(ls, r) },
+		"seed": func(ls LkState) int {
+			r.Seed(ls.CheckInteger(1))
+			return 0
+		},
+	})
+	return 1
+}
+
+// rand.int(lo, hi)
+// return an int in [lo, hi]
+func randInt(ls LkState, r *rand.Rand) int {
+	lo := ls.CheckInteger(1)
+	hi := ls.CheckInteger(2)
+	ls.ArgCheck(lo <= hi, 2, "interval is empty")
+	/* hi-lo overflows to negative */
+	ls.ArgCheck(hi-lo >= 0 && hi-lo < math.MaxInt64, 2, "interval too large")
+	ls.PushInteger(lo + r.Int63n(hi-lo+1))
+	return 1
+}
+
+// rand.float([lo, hi])
+// return a num in [lo, hi), [0, 1) by default
+func randFloat(ls LkState, r *rand.Rand) int {
+	lo := ls.OptNumber(1, 0)
+	hi := ls.OptNumber(2, 1)
+	ls.PushNumber(lo + r.Float64()*(hi-lo))
+	return 1
+}
+
+// rand.choice(list)
+// return a random item, nil if list is empty
+func randChoice(ls LkState, r *rand.Rand) int {
+	ls.CheckType(1, LK_TTABLE)
+	n := ls.Len2(1)
+	if n == 0 {
+		ls.PushNil()
+		return 1
+	}
+	ls.GetI(1, r.Int63n(n))
+	return 1
+}
+
+// rand.shuffle(list)
+// shuffles list in place
+// return list
+func randShuffle(ls LkState, r *rand.Rand) int {
+	ls.CheckType(1, LK_TTABLE)
+	n := ls.Len2(1)
+	r.Shuffle(int(n), func(i, j int) {
+		ls.GetI(1, int64(i))
+		ls.GetI(1, int64(j))
+		ls.SetI(1, int64(i))
+		ls.SetI(1, int64(j))
+	})
+	ls.SetTop(1)
+	return 1
+}
@@ -0,0 +1,182 @@
+package stdlib
+
+import (
+	"regexp"
+	"sync"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var regexLib = map[string]GoFunction{
+	"match":    regexMatch,
+	"find":     regexFind,
+	"find_all": regexFindAll,
+	"replace":  regexReplace,
+	"split":    regexSplit,
+	"escape":   regexEscape,
+}
+
+const regexCacheSize = 256
+
+var (
+	regexCache   = map[string]*regexp.Regexp{}
+	regexCacheMu sync.Mutex
+)
+
+func OpenRegexLib(ls LkState) int {
+	ls.NewLib(regexLib)
+	return 1
+}
+
+// compileRegex returns the cached regexp of pattern.
+// The cache is dropped as a whole once it's full.
+func compileRegex(pattern string) (*regexp.Regexp, error) {
+	regexCacheMu.Lock()
+	defer regexCacheMu.Unlock()
+	if exp, ok := regexCache[pattern]; ok {
+		return exp, nil
+	}
+	exp, err := regexp.Compile(pattern)
+	if err != nil {
+		return nil, err
+	}
+	if len(regexCache) >= regexCacheSize {
+		regexCache = map[string]*regexp.Regexp{}
+	}
+	regexCache[pattern] = exp
+	return exp, nil
+}
+
+// _checkRegex compiles arg at idx, or pushes nil, err and returns nil
+func _checkRegex(ls LkState, idx int) *regexp.Regexp {
+	exp, err := compileRegex(ls.CheckString(idx))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+	}
+	return exp
+}
+
+// _pushGroups pushes a list of groups, [0] is the whole match.
+// Named groups are also set by name, unmatched groups are nil.
+func _pushGroups(ls LkState, exp *regexp.Regexp, s string, loc []int) {
+	names := exp.SubexpNames()
+	ls.CreateTable(len(names), 0)
+	for i := range names {
+		if loc[2*i] < 0 {
+			continue
+		}
+		ls.PushString(s[loc[2*i]:loc[2*i+1]])
+		ls.SetI(-2, int64(i))
+		if names[i] != "" {
+			ls.PushString(s[loc[2*i]:loc[2*i+1]])
+			ls.SetField(-2, names[i])
+		}
+	}
+}
+
+// regex.match(s, pattern)
+// return matched, err
+func regexMatch(ls LkState) int {
+	s := ls.CheckString(1)
+	exp := _checkRegex(ls, 2)
+	if exp == nil {
+		return 2
+	}
+	ls.PushBoolean(exp.MatchString(s))
+	ls.PushNil()
+	return 2
+}
+
+// regex.find(s, pattern)
+// return groups of the first match or nil, err
+func regexFind(ls LkState) int {
+	s := ls.CheckString(1)
+	exp := _checkRegex(ls, 2)
+	if exp == nil {
+		return 2
+	}
+	if loc := exp.FindStringSubmatchIndex(s); loc != nil {
+		_pushGroups(ls, exp, s, loc)
+	} else {
+		ls.PushNil()
+	}
+	ls.PushNil()
+	return 2
+}
+
+// regex.find_all(s, pattern [, n])
+// n < 0 (default) means all matches
+// return [groups], err
+func regexFindAll(ls LkState) int {
+	s := ls.CheckString(1)
+	exp := _checkRegex(ls, 2)
+	if exp == nil {
+		return 2
+	}
+	locs := exp.FindAllStringSubmatchIndex(s, int(ls.OptInteger(3, -1)))
+	ls.CreateTable(len(locs), 0)
+	for i, loc := range locs {
+		_pushGroups(ls, exp, s, loc)
+		ls.SetI(-2, int64(i))
+	}
+	ls.PushNil()
+	return 2
+}
+
+// regex.replace(s, pattern, repl)
+// repl is a str with `$1` / `${name}`, or a fn receiving the groups
+// return str, err
+func regexReplace(ls LkState) int {
+	s := ls.CheckString(1)
+	exp := _checkRegex(ls, 2)
+	if exp == nil {
+		return 2
+	}
+	if !ls.IsFunction(3) {
+		ls.PushString(exp.ReplaceAllString(s, ls.CheckString(3)))
+		ls.PushNil()
+		return 2
+	}
+
+	result := []byte{}
+	last := 0
+	for _, loc := range exp.FindAllStringSubmatchIndex(s, -1) {
+		result = append(result, s[last:loc[0]]...)
+		ls.PushValue(3)
+		_pushGroups(ls, exp, s, loc)
+		ls.Call(1, 1)
+		if ls.IsNil(-1) {
+			/* keep the match */
+			result = append(result, s[loc[0]:loc[1]]...)
+		} else {
+			result = append(result, ls.ToString(-1)...)
+		}
+		ls.Pop(1)
+		last = loc[1]
+	}
+	result = append(result, s[last:]...)
+	ls.PushString(string(result))
+	ls.PushNil()
+	return 2
+}
+
+// regex.split(s, pattern [, n])
+// return [str], err
+func regexSplit(ls LkState) int {
+	s := ls.CheckString(1)
+	exp := _checkRegex(ls, 2)
+	if exp == nil {
+		return 2
+	}
+	pushList(ls, exp.Split(s, int(ls.OptInteger(3, -1))))
+	ls.PushNil()
+	return 2
+}
+
+// regex.escape(s)
+// quotes all regex metacharacters in s
+func regexEscape(ls LkState) int {
+	ls.PushString(regexp.QuoteMeta(ls.CheckString(1)))
+	return 1
+}
@@ -0,0 +1,385 @@
+package stdlib
+
+import (
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var semverLib = map[string]GoFunction{
+	"parse":     semverParse,
+	"cmp":       semverCmp,
+	"satisfies": semverSatisfies,
+}
+
+// https://semver.org
+type semVer struct {
+	nums  [3]int64 // major, minor, patch
+	pre   []string
+	build string
+}
+
+// a primitive comparator: `op ver`, op is one of `<` `<=` `>` `>=` `=`
+type semverCmpr struct {
+	op  string
+	ver semVer
+}
+
+func OpenSemverLib(ls LkState) int {
+	ls.NewLib(semverLib)
+	return 1
+}
+
+// semver.parse(v)
+// a leading `v` is allowed: `v1.2.3-rc.1+build.5`
+// return {major, minor, patch, pre, build}, err
+func semverParse(ls LkState) int {
+	v, err := parseSemver(ls.CheckString(1))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	pushTable(ls, lkMap{
+		"major": v.nums[0],
+		"minor": v.nums[1],
+		"patch": v.nums[2],
+		"pre":   strings.Join(v.pre, "."),
+		"build": v.build,
+	})
+	ls.PushNil()
+	return 2
+}
+
+// semver.cmp(a, b)
+// build metadata is ignored
+// return -1, 0 or 1, err
+func semverCmp(ls LkState) int {
+	a, err := parseSemver(ls.CheckString(1))
+	if err == nil {
+		var b semVer
+		if b, err = parseSemver(ls.CheckString(2)); err == nil {
+			ls.PushInteger(int64(a.cmp(b)))
+			ls.PushNil()
+			return 2
+		}
+	}
+	ls.PushNil()
+	ls.PushString(err.Error())
+	return 2
+}
+
+// semver.satisfies(v, range)
+// range is npm style: `^1.2`, `~1.2.3`, `>=1.0 <2`, `1.x || 2.1 - 2.3`.
+// Pre-releases only match comparators with a pre-release on the same x.y.z.
+// return bool, err
+func semverSatisfies(ls LkState) int {
+	v, err := parseSemver(ls.CheckString(1))
+	if err == nil {
+		var sets [][]semverCmpr
+		if sets, err = parseSemverRange(ls.CheckString(2)); err == nil {
+			ok := false
+			for _, set := range sets {
+				if v.satisfies(set) {
+					ok = true
+					break
+				}
+			}
+			ls.PushBoolean(ok)
+			ls.PushNil()
+			return 2
+		}
+	}
+	ls.PushNil()
+	ls.PushString(err.Error())
+	return 2
+}
+
+func parseSemver(s string) (semVer, error) {
+	nums, n, v, err := parseSemverPartial(s)
+	if err == nil && n < 3 {
+		err = fmt.Errorf("invalid version '%s': need major.minor.patch", s)
+	}
+	v.nums = nums
+	return v, err
+}
+
+// parseSemverPartial parses `1`, `1.2`, `1.x`, `*` or a full version.
+// n is the number of parts before the first missing or wildcard one.
+func parseSemverPartial(s string) (nums [3]int64, n int, v semVer, err error) {
+	orig := s
+	s = strings.TrimPrefix(strings.TrimSpace(s), "v")
+	if i := strings.IndexByte(s, '+'); i >= 0 {
+		s, v.build = s[:i], s[i+1:]
+		if !_validIdents(v.build, false) {
+			return nums, 0, v, fmt.Errorf("invalid build '%s' in '%s'", v.build, orig)
+		}
+	}
+	if i := strings.IndexByte(s, '-'); i >= 0 {
+		var pre string
+		s, pre = s[:i], s[i+1:]
+		if !_validIdents(pre, true) {
+			return nums, 0, v, fmt.Errorf("invalid pre-release '%s' in '%s'", pre, orig)
+		}
+		v.pre = strings.Split(pre, ".")
+	}
+	parts := strings.Split(s, ".")
+	if len(parts) > 3 || s == "" {
+		return nums, 0, v, fmt.Errorf("invalid version '%s'", orig)
+	}
+	wild := false
+	for i, p := range parts {
+		if p == "x" || p == "X" || p == "*" {
+			wild = true
+			continue
+		}
+		if wild || !_isNumIdent(p) {
+			return nums, 0, v, fmt.Errorf("invalid version '%s'", orig)
+		}
+		nums[i], err = strconv.ParseInt(p, 10, 64)
+		if err != nil {
+			return nums, 0, v, fmt.Errorf("invalid version '%s'", orig)
+		}
+		n++
+	}
+	if n < 3 && v.pre != nil {
+		return nums, 0, v, fmt.Errorf("invalid version '%s': pre-release needs major.minor.patch", orig)
+	}
+	return nums, n, v, nil
+}
+
+func _isNumIdent(p string) bool {
+	if p == "" || (len(p) > 1 && p[0] == '0') {
+		return false
+	}
+	for i := 0; i < len(p); i++ {
+		if p[i] < '0' || p[i] > '9' {
+			return false
+		}
+	}
+	return true
+}
+
+func _validIdents(s string, pre bool) bool {
+	for _, id := range strings.Split(s, ".") {
+		if id == "" {
+			return false
+		}
+		numeric := true
+		for i := 0; i < len(id); i++ {
+			c := id[i]
+			isDigit := '0' <= c && c <= '9'
+			if !isDigit && c != '-' && !('a' <= c && c <= 'z') && !('A' <= c && c <= 'Z') {
+				return false
+			}
+			numeric = numeric && isDigit
+		}
+		if pre && numeric && !_isNumIdent(id) {
+			return false
+		}
+	}
+	return true
+}
+
+func (a semVer) cmp(b semVer) int {
+	for i := range a.nums {
+		if a.nums[i] != b.nums[i] {
+			if a.nums[i] < b.nums[i] {
+				return -1
+			}
+			return 1
+		}
+	}
+	/* a pre-release is lower than its release */
+	switch {
+	case len(a.pre) == 0 && len(b.pre) == 0:
+		return 0
+	case len(a.pre) == 0:
+		return 1
+	case len(b.pre) == 0:
+		return -1
+	}
+	for i := 0; i < len(a.pre) && i < len(b.pre); i++ {
+		if c := _cmpIdent(a.pre[i], b.pre[i]); c != 0 {
+			return c
+		}
+	}
+	switch {
+	case len(a.pre) < len(b.pre):
+		return -1
+	case len(a.pre) > len(b.pre):
+		return 1
+	}
+	return 0
+}
+
+// numeric identifiers are lower than alphanumeric ones
+func _cmpIdent(a, b string) int {
+	an, aErr := strconv.ParseUint(a, 10, 64)
+	bn, bErr := strconv.ParseUint(b, 10, 64)
+	switch {
+	case aErr == nil && bErr == nil:
+		if an == bn {
+			return 0
+		} else if an < bn {
+			return -1
+		}
+		return 1
+	case aErr == nil:
+		return -1
+	case bErr == nil:
+		return 1
+	}
+	return strings.Compare(a, b)
+}
+
+func (v semVer) satisfies(set []semverCmpr) bool {
+	for _, c := range set {
+		r := v.cmp(c.ver)
+		var ok bool
+		switch c.op {
+		case "<":
+			ok = r < 0
+		case "<=":
+			ok = r <= 0
+		case ">":
+			ok = r > 0
+		case ">=":
+			ok = r >= 0
+		default:
+			ok = r == 0
+		}
+		if !ok {
+			return false
+		}
+	}
+	if len(v.pre) == 0 {
+		return true
+	}
+	for _, c := range set {
+		if len(c.ver.pre) > 0 && c.ver.nums == v.nums {
+			return true
+		}
+	}
+	return false
+}
+
+// parseSemverRange returns comparator sets joined by `||`
+func parseSemverRange(r string) ([][]semverCmpr, error) {
+	sets := [][]semverCmpr{}
+	for _, part := range strings.Split(r, "||") {
+		fields := strings.Fields(part)
+		set := []semverCmpr{}
+		if len(fields) == 3 && fields[1] == "-" {
+			lo, err := _semverBound(">=", fields[0])
+			if err != nil {
+				return nil, err
+			}
+			hi, err := _semverBound("<=", fields[2])
+			if err != nil {
+				return nil, err
+			}
+			sets = append(sets, append(lo, hi...))
+			continue
+		}
+		for i := 0; i < len(fields); i++ {
+			f := fields[i]
+			/* allow a space after the op: `>= 1.2` */
+			if strings.Trim(f, "<>=~^") == "" && i+1 < len(fields) {
+				i++
+				f += fields[i]
+			}
+			op := f[:len(f)-len(strings.TrimLeft(f, "<>=~^"))]
+			cs, err := _semverBound(op, f[len(op):])
+			if err != nil {
+				return nil, err
+			}
+			set = append(set, cs...)
+		}
+		if len(set) == 0 {
+			set = append(set, semverCmpr{">=", semVer{}})
+		}
+		sets = append(sets, set)
+	}
+	return sets, nil
+}
+
+// lowest version of a bump, so pre-releases of it don't match
+var semverZeroPre = []string{"0"}
+
+// _semverBound turns `op partial` into primitive comparators
+func _semverBound(op, s string) ([]semverCmpr, error) {
+	nums, n, v, err := parseSemverPartial(s)
+	if err != nil {
+		return nil, err
+	}
+	v.nums = nums
+	v.build = ""
+	/* next version at the given precision, eg: 1.2 -> 1.3.0-0 */
+	bump := func(at int) semVer {
+		next := semVer{pre: semverZeroPre}
+		copy(next.nums[:], nums[:at+1])
+		next.nums[at]++
+		return next
+	}
+	switch op {
+	case "", "=":
+		if n == 3 {
+			return []semverCmpr{{"=", v}}, nil
+		}
+		if n == 0 {
+			return []semverCmpr{{">=", semVer{}}}, nil
+		}
+		return []semverCmpr{{">=", v}, {"<", bump(n - 1)}}, nil
+	case "~":
+		if n == 0 {
+			return []semverCmpr{{">=", semVer{}}}, nil
+		}
+		at := 1
+		if n == 1 {
+			at = 0
+		}
+		return []semverCmpr{{">=", v}, {"<", bump(at)}}, nil
+	case "^":
+		if n == 0 {
+			return []semverCmpr{{">=", semVer{}}}, nil
+		}
+		/* first non-zero part is fixed */
+		at := 0
+		for at < n-1 && nums[at] == 0 {
+			at++
+		}
+		return []semverCmpr{{">=", v}, {"<", bump(at)}}, nil
+	case ">", "<=":
+		if n == 3 {
+			return []semverCmpr{{op, v}}, nil
+		}
+		if n == 0 {
+			if op == ">" {
+				return []semverCmpr{{"<", semVer{}}}, nil /* nothing */
+			}
+			return []semverCmpr{{">=", semVer{}}}, nil
+		}
+		if op == ">" {
+			next := bump(n - 1)
+			next.pre = nil
+			return []semverCmpr{{">=", next}}, nil
+		}
+		return []semverCmpr{{"<", bump(n - 1)}}, nil
+	case ">=", "<":
+		if n == 0 {
+			if op == "<" {
+				return []semverCmpr{{"<", semVer{}}}, nil
+			}
+			return []semverCmpr{{">=", semVer{}}}, nil
+		}
+		if n < 3 && op == "<" {
+			v.pre = semverZeroPre
+		}
+		return []semverCmpr{{op, v}}, nil
+	}
+	return nil, errors.New("invalid range operator '" + op + "'")
+}
@@ -0,0 +1,172 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"bytes"
+	"errors"
+	"os/exec"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var shellLib = map[string]GoFunction{
+	"quote": shellQuote,
+	"join":  shellJoin,
+	"split": shellSplit,
+	"sh":    shellSh,
+}
+
+func OpenShellLib(ls LkState) int {
+	ls.NewLib(shellLib)
+	return 1
+}
+
+// shell.quote(arg)
+// return arg quoted for POSIX shells, eg: `it's` -> `'it'\''s'`
+func shellQuote(ls LkState) int {
+	ls.PushString(quoteShellArg(ls.CheckString(1)))
+	return 1
+}
+
+// shell.join(args)
+// return args quoted and joined by spaces
+func shellJoin(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	parts := []string{}
+	for i := int64(0); i < ls.Len2(1); i++ {
+		ls.GetI(1, i)
+		parts = append(parts, quoteShellArg(ls.ToString(-1)))
+		ls.Pop(1)
+	}
+	ls.PushString(strings.Join(parts, " "))
+	return 1
+}
+
+// shell.split(cmdline)
+// splits words like a POSIX shell: quotes and `\` escapes are handled,
+// but nothing is expanded.
+// return list, err
+func shellSplit(ls LkState) int {
+	words, err := splitShellWords(ls.CheckString(1))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	list := make([]any, len(words))
+	for i := range words {
+		list[i] = words[i]
+	}
+	pushList(ls, list)
+	ls.PushNil()
+	return 2
+}
+
+// shell.sh(cmd [, args])
+// runs cmd by `sh -c`, args are passed as `$1` `$2`... and never parsed
+// by the shell, so `shell.sh('ls -l "$1"', {dir})` is safe for any dir.
+// Never build cmd from untrusted text, use args or shell.quote.
+// return stdout, err. err is stderr (or the error) if cmd fails
+func shellSh(ls LkState) int {
+	argv := []string{"-c", ls.CheckString(1), "sh"}
+	if !ls.IsNoneOrNil(2) {
+		ls.CheckType(2, LK_TTABLE)
+		for i := int64(0); i < ls.Len2(2); i++ {
+			ls.GetI(2, i)
+			argv = append(argv, ls.ToString(-1))
+			ls.Pop(1)
+		}
+	}
+	cmd := exec.Command("sh", argv...)
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		msg := strings.TrimRight(stderr.String(), "\n")
+		if msg == "" {
+			msg = err.Error()
+		}
+		ls.PushString(stdout.String())
+		ls.PushString(msg)
+		return 2
+	}
+	ls.PushString(stdout.String())
+	ls.PushNil()
+	return 2
+}
+
+func quoteShellArg(s string) string {
+	if s == "" {
+		return "''"
+	}
+	safe := true
+	for _, c := range s {
+		if !(c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z' || c >= '0' && c <= '9' ||
+			strings.ContainsRune("@%+=:,./-_", c)) {
+			safe = false
+			break
+		}
+	}
+	if safe {
+		return s
+	}
+	return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
+}
+
+func splitShellWords(s string) ([]string, error) {
+	words := []string{}
+	var word strings.Builder
+	inWord := false
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case c == ' ' || c == '\t' || c == '\n':
+			if inWord {
+				words = append(words, word.String())
+				word.Reset()
+				inWord = false
+			}
+		case c == '\\':
+			inWord = true
+			if i+1 < len(s) {
+				i++
+				if s[i] != '\n' { /* `\` newline joins lines */
+					word.WriteByte(s[i])
+				}
+			}
+		case c == '\'':
+			inWord = true
+			end := strings.IndexByte(s[i+1:], '\'')
+			if end < 0 {
+				return nil, errors.New("unterminated ' quote")
+			}
+			word.WriteString(s[i+1 : i+1+end])
+			i += end + 1
+		case c == '"':
+			inWord = true
+			i++
+			for ; i < len(s) && s[i] != '"'; i++ {
+				/* only these are escaped inside double quotes */
+				if s[i] == '\\' && i+1 < len(s) && strings.IndexByte("\\\"$`\n", s[i+1]) >= 0 {
+					i++
+					if s[i] == '\n' {
+						continue
+					}
+				}
+				word.WriteByte(s[i])
+			}
+			if i >= len(s) {
+				return nil, errors.New(`unterminated " quote`)
+			}
+		default:
+			inWord = true
+			word.WriteByte(c)
+		}
+	}
+	if inWord {
+		words = append(words, word.String())
+	}
+	return words, nil
+}
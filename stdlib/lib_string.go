@@ -1,7 +1,6 @@
 package stdlib
 
 import (
-	"regexp"
 	"strconv"
 	"strings"
 
@@ -9,19 +8,22 @@ import (
 )
 
 var strLib = map[string]GoFunction{
-	"len":      strLen,
-	"repeat":   strRep,
-	"reverse":  strReverse,
-	"lower":    strLower,
-	"upper":    strUpper,
-	"sub":      strSub,
-	"bytes":    strByte,
-	"char":     strChar,
-	"split":    strSplit,
-	"join":     strJoin,
-	"contains": strContains,
-	"match":    strMatch,
-	"replace":  strReplace,
+	"len":       strLen,
+	"repeat":    strRep,
+	"reverse":   strReverse,
+	"lower":     strLower,
+	"upper":     strUpper,
+	"sub":       strSub,
+	"bytes":     strByte,
+	"char":      strChar,
+	"split":     strSplit,
+	"join":      strJoin,
+	"contains":  strContains,
+	"match":     strMatch,
+	"replace":   strReplace,
+	"diff":      strDiff,
+	"patch":     strPatch,
+	"word_diff": strWordDiff,
 }
 
 func OpenStringLib(ls LkState) int {
@@ -57,7 +59,7 @@ func strContains(ls LkState) int {
 func strMatch(ls LkState) int {
 	s := ls.CheckString(1)
 	pattern := ls.CheckString(2)
-	exp, err := regexp.Compile(pattern)
+	exp, err := compileRegex(pattern)
 	if err != nil {
 		ls.PushNil()
 		ls.PushString(err.Error())
@@ -0,0 +1,284 @@
+package stdlib
+
+import (
+	"fmt"
+	"regexp"
+	"strconv"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+const noNewlineMark = "\\ No newline at end of file\n"
+
+var (
+	reHunkHeader = regexp.MustCompile(`^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@`)
+	reWords      = regexp.MustCompile(`\s+|\S+`)
+)
+
+// one step of an edit script
+type diffOp struct {
+	kind byte // ' ', '-' or '+'
+	text string
+	// positions in a and b where the op happens
+	a, b int
+}
+
+// _myers returns the shortest edit script from a to b
+func _myers(a, b []string) []diffOp {
+	n, m := len(a), len(b)
+	max := n + m
+	off := max + 1
+	v := make([]int, 2*max+3)
+	trace := [][]int{}
+	for d := 0; d <= max; d++ {
+		trace = append(trace, append([]int{}, v...))
+		for k := -d; k <= d; k += 2 {
+			var x int
+			if k == -d || (k != d && v[off+k-1] < v[off+k+1]) {
+				x = v[off+k+1]
+			} else {
+				x = v[off+k-1] + 1
+			}
+			y := x - k
+			for x < n && y < m && a[x] == b[y] {
+				x++
+				y++
+			}
+			v[off+k] = x
+			if x >= n && y >= m {
+				return _myersBacktrack(trace, a, b, off)
+			}
+		}
+	}
+	return nil
+}
+
+func _myersBacktrack(trace [][]int, a, b []string, off int) []diffOp {
+	ops := []diffOp{}
+	x, y := len(a), len(b)
+	for d := len(trace) - 1; d >= 0; d-- {
+		v := trace[d]
+		k := x - y
+		prevK := k - 1
+		if k == -d || (k != d && v[off+k-1] < v[off+k+1]) {
+			prevK = k + 1
+		}
+		prevX := v[off+prevK]
+		prevY := prevX - prevK
+		for x > prevX && y > prevY {
+			x--
+			y--
+			ops = append(ops, diffOp{' ', a[x], x, y})
+		}
+		if d > 0 {
+			if x == prevX {
+				ops = append(ops, diffOp{'+', b[y-1], x, y - 1})
+			} else {
+				ops = append(ops, diffOp{'-', a[x-1], x - 1, y})
+			}
+		}
+		x, y = prevX, prevY
+	}
+	for i, j := 0, len(ops)-1; i < j; i, j = i+1, j-1 {
+		ops[i], ops[j] = ops[j], ops[i]
+	}
+	return ops
+}
+
+// _splitLines keeps `\n` on each line, so a missing last newline is a change
+func _splitLines(s string) []string {
+	lines := strings.SplitAfter(s, "\n")
+	if lines[len(lines)-1] == "" {
+		lines = lines[:len(lines)-1]
+	}
+	return lines
+}
+
+// _unifiedDiff formats ops as hunks with `context` lines around changes
+func _unifiedDiff(ops []diffOp, context int) string {
+	var sb strings.Builder
+	for i := 0; i < len(ops); {
+		if ops[i].kind == ' ' {
+			i++
+			continue
+		}
+		/* extend the hunk while the next change is close enough */
+		start := i - context
+		if start < 0 {
+			start = 0
+		}
+		end := i
+		for j := i; j < len(ops) && j <= end+2*context+1; j++ {
+			if ops[j].kind != ' ' {
+				end = j
+			}
+		}
+		stop := end + context + 1
+		if stop > len(ops) {
+			stop = len(ops)
+		}
+
+		hunk := ops[start:stop]
+		aLen, bLen := 0, 0
+		for _, op := range hunk {
+			if op.kind != '+' {
+				aLen++
+			}
+			if op.kind != '-' {
+				bLen++
+			}
+		}
+		aStart, bStart := hunk[0].a, hunk[0].b
+		if aLen > 0 {
+			aStart++
+		}
+		if bLen > 0 {
+			bStart++
+		}
+		fmt.Fprintf(&sb, "@@ -%d,%d +%d,%d @@\n", aStart, aLen, bStart, bLen)
+		for _, op := range hunk {
+			sb.WriteByte(op.kind)
+			sb.WriteString(op.text)
+			if !strings.HasSuffix(op.text, "\n") {
+				sb.WriteString("\n" + noNewlineMark)
+			}
+		}
+		i = stop
+	}
+	return sb.String()
+}
+
+// str.diff(a, b [, context])
+// return unified diff of lines, "" if equal. context defaults to 3
+func strDiff(ls LkState) int {
+	a := ls.CheckString(1)
+	b := ls.CheckString(2)
+	context := int(ls.OptInteger(3, 3))
+	ops := _myers(_splitLines(a), _splitLines(b))
+	hunks := _unifiedDiff(ops, context)
+	if hunks == "" {
+		ls.PushString("")
+		return 1
+	}
+	ls.PushString("--- a\n+++ b\n" + hunks)
+	return 1
+}
+
+// str.patch(original, diff)
+// applies a unified diff, every hunk must match exactly
+// return patched, err
+func strPatch(ls LkState) int {
+	out, err := _applyPatch(ls.CheckString(1), ls.CheckString(2))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	ls.PushString(out)
+	ls.PushNil()
+	return 2
+}
+
+func _applyPatch(original, diff string) (string, error) {
+	src := _splitLines(original)
+	lines := _splitLines(diff)
+	var out strings.Builder
+	pos := 0 /* next line of src to copy */
+	for i := 0; i < len(lines); i++ {
+		m := reHunkHeader.FindStringSubmatch(lines[i])
+		if m == nil {
+			/* file headers and other text between hunks */
+			continue
+		}
+		aStart, _ := strconv.Atoi(m[1])
+		aLen, bLen := 1, 1
+		if m[2] != "" {
+			aLen, _ = strconv.Atoi(m[2])
+		}
+		if m[4] != "" {
+			bLen, _ = strconv.Atoi(m[4])
+		}
+		if aLen > 0 {
+			aStart--
+		}
+		if aStart < pos || aStart > len(src) {
+			return "", fmt.Errorf("hunk at line %d is out of order", i+1)
+		}
+		for ; pos < aStart; pos++ {
+			out.WriteString(src[pos])
+		}
+
+		for aLen > 0 || bLen > 0 {
+			i++
+			if i >= len(lines) {
+				return "", fmt.Errorf("hunk ends early at line %d", i)
+			}
+			line := lines[i]
+			if line == "\n" {
+				/* some editors strip the space of empty context lines */
+				line = " \n"
+			}
+			text := line[1:]
+			if i+1 < len(lines) && lines[i+1] == noNewlineMark {
+				text = strings.TrimSuffix(text, "\n")
+				i++
+			}
+			switch line[0] {
+			case ' ', '-':
+				if pos >= len(src) || src[pos] != text {
+					return "", fmt.Errorf("hunk does not apply at line %d", pos+1)
+				}
+				pos++
+				aLen--
+				if line[0] == ' ' {
+					out.WriteString(text)
+					bLen--
+				}
+			case '+':
+				out.WriteString(text)
+				bLen--
+			default:
+				return "", fmt.Errorf("bad diff line %d: %q", i+1, strings.TrimSuffix(line, "\n"))
+			}
+		}
+	}
+	for ; pos < len(src); pos++ {
+		out.WriteString(src[pos])
+	}
+	return out.String(), nil
+}
+
+// str.word_diff(a, b)
+// return a with `[-removed-]` and `{+added+}` marks, words are split by spaces
+func strWordDiff(ls LkState) int {
+	a := reWords.FindAllString(ls.CheckString(1), -1)
+	b := reWords.FindAllString(ls.CheckString(2), -1)
+	var sb strings.Builder
+	var kind byte = ' '
+	for _, op := range _myers(a, b) {
+		if op.kind != kind {
+			sb.WriteString(_wordMark(kind, false))
+			sb.WriteString(_wordMark(op.kind, true))
+			kind = op.kind
+		}
+		sb.WriteString(op.text)
+	}
+	sb.WriteString(_wordMark(kind, false))
+	ls.PushString(sb.String())
+	return 1
+}
+
+func _wordMark(kind byte, open bool) string {
+	switch {
+	case kind == '-' && open:
+		return "[-"
+	case kind == '-':
+		return "-]"
+	case kind == '+' && open:
+		return "{+"
+	case kind == '+':
+		return "+}"
+	}
+	return ""
+}
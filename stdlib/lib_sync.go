@@ -10,6 +10,9 @@ var coFuncs = map[string]GoFunction{
 	"is_yieldable": coYieldable,
 	"running":      coRunning,
 	"wrap":         coWrap,
+	"local":        coLocal,
+	"get_local":    coGetLocal,
+	"scope":        coScope,
 }
 
 func OpenCoroutineLib(ls LkState) int {
@@ -124,6 +127,41 @@ func coRunning(ls LkState) int {
 
 // coroutine.wrap (f)
 // http://www.lua.org/manual/5.3/manual.html#pdf-coroutine.wrap
+// lua-5.3.4/src/lcorolib.c#luaB_cowrap()
 func coWrap(ls LkState) int {
-	panic("todo: coWrap!")
+	coCreate(ls)
+	ls.PushGoClosure(_auxWrap, 1)
+	return 1
+}
+
+// errors of the coroutine are raised in the caller
+func _auxWrap(ls LkState) int {
+	co := ls.ToThread(LkUpvalueIndex(1))
+	r := _auxResume(ls, co, ls.GetTop())
+	if r < 0 {
+		return ls.Error()
+	}
+	return r
+}
+
+// sync.local(key, value)
+// sets a value seen only by the running coroutine,
+// and coroutines created by it afterwards. Like a request id for logs.
+func coLocal(ls LkState) int {
+	ls.ArgCheck(!ls.IsNoneOrNil(1), 1, "key expected")
+	ls.CheckAny(2)
+	ls.PushLocals()
+	ls.Insert(1)
+	ls.SetTable(1)
+	return 0
+}
+
+// sync.get_local(key)
+// return value set by sync.local here or in a creator, nil if not set
+func coGetLocal(ls LkState) int {
+	ls.CheckAny(1)
+	ls.PushLocals()
+	ls.Insert(1)
+	ls.GetTable(1)
+	return 1
 }
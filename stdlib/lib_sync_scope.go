@@ -0,0 +1,81 @@
+package stdlib
+
+import . "github.com/lollipopkit/lk/api"
+
+type scopeTask struct {
+	co LkState
+	/* args of the first resume */
+	nargs   int
+	started bool
+	done    bool
+}
+
+// sync.scope(f)
+// calls f(s), `s.spawn(fn, ...)` adds a task, which can also spawn.
+// After f returns, tasks run in turns, each until it returns or yields.
+// The scope ends when every task is done, tasks never outlive it.
+// If f or a task fails, unfinished tasks are cancelled and the error is raised.
+// return list of the first result of each task, in the order they are spawned
+func coScope(ls LkState) int {
+	ls.CheckType(1, LK_TFUNCTION)
+	tasks := []*scopeTask{}
+	closed := false
+
+	ls.PushValue(1)
+	ls.NewTable()
+	ls.PushGoFunction(func(ls LkState) int {
+		if closed {
+			return ls.Error2("spawn in a closed scope")
+		}
+		ls.CheckType(1, LK_TFUNCTION)
+		n := ls.GetTop()
+		co := ls.NewThread()
+		ls.Pop(1)
+		ls.XMove(co, n) /* fn and its args */
+		tasks = append(tasks, &scopeTask{co: co, nargs: n - 1})
+		ls.PushInteger(int64(len(tasks) - 1))
+		return 1
+	})
+	ls.SetField(-2, "spawn")
+	if ls.PCall(1, 0, 0) != LK_OK {
+		closed = true
+		return ls.Error()
+	}
+
+	ls.NewTable()
+	results := ls.GetTop()
+	for pending := true; pending; {
+		pending = false
+		/* tasks spawned in this round run in it too */
+		for i := 0; i < len(tasks); i++ {
+			t := tasks[i]
+			if t.done {
+				continue
+			}
+			nargs := 0
+			if !t.started {
+				nargs, t.started = t.nargs, true
+			}
+			switch t.co.Resume(ls, nargs) {
+			case LK_YIELD:
+				t.co.Pop(t.co.GetTop())
+				pending = true
+			case LK_OK:
+				t.done = true
+				if t.co.GetTop() > 0 {
+					t.co.SetTop(1)
+					t.co.XMove(ls, 1)
+				} else {
+					ls.PushNil()
+				}
+				ls.SetI(results, int64(i))
+			default:
+				closed = true
+				t.co.XMove(ls, 1)
+				return ls.Error()
+			}
+		}
+	}
+	closed = true
+	return 1
+}
@@ -5,10 +5,12 @@ import (
 )
 
 var tableLib = map[string]GoFunction{
-	"len":      tableLen,
-	"keys":     tableKeys,
-	"values":   tableValues,
-	"contains": tableHave,
+	"len":           tableLen,
+	"keys":          tableKeys,
+	"values":        tableValues,
+	"contains":      tableHave,
+	"dedupe_report": tableDedupeReport,
+	"intern":        tableIntern,
 }
 
 func OpenTableLib(ls LkState) int {
@@ -0,0 +1,227 @@
+package stdlib
+
+import (
+	"crypto/sha256"
+	"fmt"
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+// Rough memory cost used to estimate savings, not exact Go sizes
+const (
+	_sizeTable = 64
+	_sizeEntry = 32
+	_sizeValue = 16
+)
+
+// deduper computes structural hashes of tables.
+// Tables are identified by ToPointer, hashes are memoized per table.
+type deduper struct {
+	ls     LkState
+	hashes map[any]string
+	sizes  map[any]int
+	busy   map[any]bool
+}
+
+type dupGroup struct {
+	size  int
+	paths []string
+}
+
+func newDeduper(ls LkState) *deduper {
+	return &deduper{
+		ls:     ls,
+		hashes: map[any]string{},
+		sizes:  map[any]int{},
+		busy:   map[any]bool{},
+	}
+}
+
+// hash returns the structural hash and estimated size of value at idx
+func (d *deduper) hash(idx int) (string, int) {
+	ls := d.ls
+	idx = ls.AbsIndex(idx)
+	switch ls.Type(idx) {
+	case LK_TTABLE:
+	case LK_TSTRING:
+		s := ls.ToString(idx)
+		return fmt.Sprintf("s%d:%s", len(s), s), _sizeValue + len(s)
+	case LK_TNUMBER:
+		if ls.IsInteger(idx) {
+			return fmt.Sprintf("i%d", ls.ToInteger(idx)), _sizeValue
+		}
+		return fmt.Sprintf("f%v", ls.ToNumber(idx)), _sizeValue
+	case LK_TBOOLEAN:
+		return fmt.Sprintf("b%v", ls.ToBoolean(idx)), _sizeValue
+	default:
+		/* fns and others are only equal to themselves */
+		return fmt.Sprintf("p%p", ls.ToPointer(idx)), _sizeValue
+	}
+
+	p := ls.ToPointer(idx)
+	if h, ok := d.hashes[p]; ok {
+		return h, d.sizes[p]
+	}
+	if d.busy[p] {
+		return fmt.Sprintf("c%p", p), 0
+	}
+	d.busy[p] = true
+	defer delete(d.busy, p)
+
+	entries := []string{}
+	size := _sizeTable
+	ls.PushNil()
+	for ls.Next(idx) {
+		kh, ks := d.hash(-2)
+		vh, vs := d.hash(-1)
+		entries = append(entries, fmt.Sprintf("%d:%s%s", len(kh), kh, vh))
+		size += _sizeEntry + ks + vs
+		ls.Pop(1)
+	}
+	sort.Strings(entries)
+	sum := sha256.Sum256([]byte(strings.Join(entries, ",")))
+	h := fmt.Sprintf("t%x", sum[:8])
+	d.hashes[p] = h
+	d.sizes[p] = size
+	return h, size
+}
+
+// walk registers tables under the table at idx.
+// Once a table is a duplicate, its children are not counted again.
+func (d *deduper) walk(idx int, path string, groups map[string]*dupGroup, seen map[any]bool) {
+	ls := d.ls
+	idx = ls.AbsIndex(idx)
+	ls.PushNil()
+	for ls.Next(idx) {
+		if !ls.IsTable(-1) {
+			ls.Pop(1)
+			continue
+		}
+		p := ls.ToPointer(-1)
+		if seen[p] {
+			/* already shared */
+			ls.Pop(1)
+			continue
+		}
+		seen[p] = true
+		h, size := d.hash(-1)
+		childPath := path + _pathKey(ls, -2)
+		if g, ok := groups[h]; ok {
+			g.paths = append(g.paths, childPath)
+		} else {
+			groups[h] = &dupGroup{size: size, paths: []string{childPath}}
+			d.walk(-1, childPath, groups, seen)
+		}
+		ls.Pop(1)
+	}
+}
+
+// intern replaces tables under idx with the first equal one in canon
+func (d *deduper) intern(idx, canon int) int {
+	ls := d.ls
+	idx = ls.AbsIndex(idx)
+	shared := 0
+	ls.PushNil()
+	for ls.Next(idx) {
+		if !ls.IsTable(-1) {
+			ls.Pop(1)
+			continue
+		}
+		h, _ := d.hash(-1)
+		if ls.GetField(canon, h) == LK_TTABLE {
+			if ls.ToPointer(-1) != ls.ToPointer(-2) {
+				ls.PushValue(-3)
+				ls.PushValue(-2)
+				ls.SetTable(idx)
+				shared++
+			}
+			ls.Pop(1)
+		} else {
+			ls.Pop(1)
+			ls.PushValue(-1)
+			ls.SetField(canon, h)
+			shared += d.intern(-1, canon)
+		}
+		ls.Pop(1)
+	}
+	return shared
+}
+
+func _pathKey(ls LkState, idx int) string {
+	switch ls.Type(idx) {
+	case LK_TSTRING:
+		return "." + ls.ToString(idx)
+	case LK_TNUMBER:
+		if ls.IsInteger(idx) {
+			return fmt.Sprintf("[%d]", ls.ToInteger(idx))
+		}
+		return fmt.Sprintf("[%v]", ls.ToNumber(idx))
+	}
+	return "[" + ls.TypeName2(idx) + "]"
+}
+
+// dedupe_report (t)
+// returns {tables, duplicates: [{paths, count, size, savings}], savings}
+// sizes are estimated bytes, duplicates are sorted by savings
+func tableDedupeReport(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	d := newDeduper(ls)
+	groups := map[string]*dupGroup{}
+	seen := map[any]bool{ls.ToPointer(1): true}
+	h, size := d.hash(1)
+	groups[h] = &dupGroup{size: size, paths: []string{"$"}}
+	d.walk(1, "$", groups, seen)
+
+	dups := []*dupGroup{}
+	for _, g := range groups {
+		if len(g.paths) > 1 {
+			dups = append(dups, g)
+		}
+	}
+	sort.Slice(dups, func(i, j int) bool {
+		si := dups[i].size * (len(dups[i].paths) - 1)
+		sj := dups[j].size * (len(dups[j].paths) - 1)
+		if si != sj {
+			return si > sj
+		}
+		return dups[i].paths[0] < dups[j].paths[0]
+	})
+
+	items := make([]any, len(dups))
+	savings := 0
+	for i, g := range dups {
+		saving := g.size * (len(g.paths) - 1)
+		savings += saving
+		items[i] = lkMap{
+			"paths":   g.paths,
+			"count":   len(g.paths),
+			"size":    g.size,
+			"savings": saving,
+		}
+	}
+	pushTable(ls, lkMap{
+		"tables":     len(seen),
+		"duplicates": items,
+		"savings":    savings,
+	})
+	return 1
+}
+
+// intern (t)
+// shares structurally equal subtables of t in place.
+// returns t and the number of subtables replaced
+func tableIntern(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	d := newDeduper(ls)
+	ls.CreateTable(0, 0)
+	canon := ls.GetTop()
+	h, _ := d.hash(1)
+	ls.PushValue(1)
+	ls.SetField(canon, h)
+	shared := d.intern(1, canon)
+	ls.PushValue(1)
+	ls.PushInteger(int64(shared))
+	return 2
+}
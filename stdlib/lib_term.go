@@ -7,7 +7,10 @@ import (
 )
 
 var termLib = map[string]GoFunction{
-	"input": termInput,
+	"input":        termInput,
+	"table":        termTable,
+	"select":       termSelect,
+	"multi_select": termMultiSelect,
 }
 
 func OpenTermLib(ls LkState) int {
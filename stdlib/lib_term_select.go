@@ -0,0 +1,307 @@
+package stdlib
+
+import (
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+	"unicode"
+
+	"atomicgo.dev/keyboard"
+	"atomicgo.dev/keyboard/keys"
+	"github.com/lollipopkit/gommon/res"
+	. "github.com/lollipopkit/lk/api"
+)
+
+// options shown at once, the list scrolls with the cursor
+const selectPageSize = 10
+
+type selector struct {
+	prompt  string
+	options []string
+	multi   bool
+	filter  []rune
+	cursor  int // index in matches()
+	checked map[int]bool
+	drawn   int // lines printed by the last render
+}
+
+// term.select(prompt, options [, default])
+// ↑ ↓ to move, type to filter fuzzily, `Enter` to choose, `Esc` to cancel.
+// return option, idx. nil if canceled
+func termSelect(ls LkState) int {
+	s := _newSelector(ls, false)
+	if s == nil {
+		return 2
+	}
+	if !ls.IsNoneOrNil(3) {
+		dft := int(ls.CheckInteger(3))
+		for i, idx := range s.matches() {
+			if idx == dft {
+				s.cursor = i
+			}
+		}
+	}
+	chosen, err := s.run()
+	if err != nil || chosen == nil {
+		ls.PushNil()
+		ls.PushNil()
+		return 2
+	}
+	ls.PushString(s.options[chosen[0]])
+	ls.PushInteger(int64(chosen[0]))
+	return 2
+}
+
+// term.multi_select(prompt, options [, selected])
+// like term.select, `Space` toggles an option, selected is a list of idx.
+// return options, idxs. nil if canceled
+func termMultiSelect(ls LkState) int {
+	s := _newSelector(ls, true)
+	if s == nil {
+		return 2
+	}
+	if ls.IsTable(3) {
+		for i := int64(0); i < ls.Len2(3); i++ {
+			ls.GetI(3, i)
+			s.checked[int(ls.ToInteger(-1))] = true
+			ls.Pop(1)
+		}
+	}
+	chosen, err := s.run()
+	if err != nil || chosen == nil {
+		ls.PushNil()
+		ls.PushNil()
+		return 2
+	}
+	opts := make([]any, len(chosen))
+	idxs := make([]any, len(chosen))
+	for i, idx := range chosen {
+		opts[i] = s.options[idx]
+		idxs[i] = int64(idx)
+	}
+	pushList(ls, opts)
+	pushList(ls, idxs)
+	return 2
+}
+
+// _newSelector reads prompt and options,
+// pushes nil, err and returns nil if stdin is not a terminal.
+func _newSelector(ls LkState, multi bool) *selector {
+	s := &selector{
+		prompt:  ls.CheckString(1),
+		options: []string{},
+		multi:   multi,
+		checked: map[int]bool{},
+	}
+	ls.CheckType(2, LK_TTABLE)
+	for i := int64(0); i < ls.Len2(2); i++ {
+		ls.GetI(2, i)
+		s.options = append(s.options, _cellStr(ls))
+	}
+	if stat, err := os.Stdin.Stat(); err != nil || stat.Mode()&os.ModeCharDevice == 0 {
+		ls.PushNil()
+		ls.PushString("stdin is not a terminal")
+		return nil
+	}
+	return s
+}
+
+// run reads keys until an option is chosen, nil if canceled
+func (s *selector) run() ([]int, error) {
+	var chosen []int
+	s.render()
+	err := keyboard.Listen(func(key keys.Key) (bool, error) {
+		done, canceled := s.handle(key)
+		if canceled {
+			s.clear()
+			return true, nil
+		}
+		if done {
+			chosen = s.result()
+			s.clear()
+			labels := make([]string, len(chosen))
+			for i, idx := range chosen {
+				labels[i] = s.options[idx]
+			}
+			fmt.Printf("%s %s\n", s.prompt, res.CYAN+strings.Join(labels, ", ")+res.NOCOLOR)
+			return true, nil
+		}
+		s.render()
+		return false, nil
+	})
+	return chosen, err
+}
+
+// handle updates state by key, returns whether selection is finished
+func (s *selector) handle(key keys.Key) (done, canceled bool) {
+	matches := s.matches()
+	switch key.Code {
+	case keys.Esc, keys.CtrlC:
+		return false, true
+	case keys.Enter:
+		return len(s.result()) > 0, false
+	case keys.Up, keys.CtrlP:
+		if s.cursor > 0 {
+			s.cursor--
+		}
+	case keys.Down, keys.CtrlN, keys.Tab:
+		if s.cursor < len(matches)-1 {
+			s.cursor++
+		}
+	case keys.Space:
+		if s.multi {
+			if s.cursor < len(matches) {
+				idx := matches[s.cursor]
+				s.checked[idx] = !s.checked[idx]
+			}
+		} else {
+			s.setFilter(append(s.filter, ' '))
+		}
+	case keys.Backspace:
+		if len(s.filter) > 0 {
+			s.setFilter(s.filter[:len(s.filter)-1])
+		}
+	case keys.RuneKey:
+		if _isPrintable(key.Runes) {
+			s.setFilter(append(s.filter, key.Runes...))
+		}
+	}
+	return false, false
+}
+
+func (s *selector) setFilter(filter []rune) {
+	s.filter = filter
+	s.cursor = 0
+}
+
+// result is the option under cursor, or checked ones in order
+func (s *selector) result() []int {
+	if !s.multi {
+		if matches := s.matches(); s.cursor < len(matches) {
+			return []int{matches[s.cursor]}
+		}
+		return []int{}
+	}
+	chosen := []int{}
+	for i := range s.options {
+		if s.checked[i] {
+			chosen = append(chosen, i)
+		}
+	}
+	return chosen
+}
+
+// matches are idxs of options matching the filter, best first
+func (s *selector) matches() []int {
+	type match struct{ idx, score int }
+	ms := []match{}
+	for i, opt := range s.options {
+		if score, ok := fuzzyScore(string(s.filter), opt); ok {
+			ms = append(ms, match{i, score})
+		}
+	}
+	sort.SliceStable(ms, func(i, j int) bool {
+		return ms[i].score < ms[j].score
+	})
+	idxs := make([]int, len(ms))
+	for i, m := range ms {
+		idxs[i] = m.idx
+	}
+	return idxs
+}
+
+// fuzzyScore matches chars of pattern in order, case insensitive.
+// Lower score is better: the span of the match plus where it starts.
+func fuzzyScore(pattern, s string) (int, bool) {
+	p := []rune(strings.ToLower(pattern))
+	if len(p) == 0 {
+		return 0, true
+	}
+	rs := []rune(strings.ToLower(s))
+	best := -1
+	/* try every start, so `ab` in `a-xab` scores the tight `ab` */
+	for start := range rs {
+		if rs[start] != p[0] {
+			continue
+		}
+		j := 1
+		end := start
+		for i := start + 1; i < len(rs) && j < len(p); i++ {
+			if rs[i] == p[j] {
+				j++
+				end = i
+			}
+		}
+		if j < len(p) {
+			break
+		}
+		if score := (end-start)*len(rs) + start; best < 0 || score < best {
+			best = score
+		}
+	}
+	return best, best >= 0
+}
+
+func (s *selector) render() {
+	s.clear()
+	matches := s.matches()
+	var b strings.Builder
+	hint := "↑↓ move, type to filter, enter to choose"
+	if s.multi {
+		hint = "↑↓ move, space to toggle, type to filter, enter to confirm"
+	}
+	b.WriteString(res.GREEN + "? " + res.NOCOLOR + s.prompt + " ")
+	if len(s.filter) > 0 {
+		b.WriteString(string(s.filter))
+	} else {
+		b.WriteString(res.CYAN + "(" + hint + ")" + res.NOCOLOR)
+	}
+	b.WriteString("\n")
+	lines := 1
+
+	/* scroll to keep cursor on the page */
+	start := 0
+	if s.cursor >= selectPageSize {
+		start = s.cursor - selectPageSize + 1
+	}
+	for i := start; i < len(matches) && i < start+selectPageSize; i++ {
+		idx := matches[i]
+		line := "  "
+		if i == s.cursor {
+			line = res.CYAN + "> "
+		}
+		if s.multi {
+			if s.checked[idx] {
+				line += "[x] "
+			} else {
+				line += "[ ] "
+			}
+		}
+		b.WriteString(line + s.options[idx] + res.NOCOLOR + "\n")
+		lines++
+	}
+	if len(matches) == 0 {
+		b.WriteString("  " + res.YELLOW + "no match" + res.NOCOLOR + "\n")
+		lines++
+	}
+	fmt.Print(b.String())
+	s.drawn = lines
+}
+
+// clear erases lines of the last render
+func (s *selector) clear() {
+	if s.drawn > 0 {
+		fmt.Printf("\x1b[%dA\x1b[J", s.drawn)
+		s.drawn = 0
+	}
+}
+
+func _isPrintable(rs []rune) bool {
+	for _, r := range rs {
+		if !unicode.IsPrint(r) {
+			return false
+		}
+	}
+	return true
+}
@@ -0,0 +1,53 @@
+package stdlib
+
+import (
+	"testing"
+
+	"atomicgo.dev/keyboard/keys"
+)
+
+func TestFuzzyScore(t *testing.T) {
+	if _, ok := fuzzyScore("dpl", "deploy"); !ok {
+		t.Error("`dpl` should match `deploy`")
+	}
+	if _, ok := fuzzyScore("xd", "deploy"); ok {
+		t.Error("`xd` should not match `deploy`")
+	}
+	tight, _ := fuzzyScore("db", "db-backup")
+	loose, _ := fuzzyScore("db", "deploy-web")
+	if tight >= loose {
+		t.Errorf("tight match %d should score lower than %d", tight, loose)
+	}
+}
+
+func TestSelectorHandle(t *testing.T) {
+	s := &selector{
+		options: []string{"deploy", "rollback", "db-backup"},
+		checked: map[int]bool{},
+	}
+	s.handle(keys.Key{Code: keys.RuneKey, Runes: []rune("b")})
+	s.handle(keys.Key{Code: keys.RuneKey, Runes: []rune("a")})
+	m := s.matches()
+	if len(m) != 2 || m[0] == 0 || m[1] == 0 {
+		t.Fatalf("matches of `ba` = %v", m)
+	}
+	s.handle(keys.Key{Code: keys.Down})
+	if done, _ := s.handle(keys.Key{Code: keys.Enter}); !done || s.result()[0] != m[1] {
+		t.Errorf("want %d, got %v", m[1], s.result())
+	}
+
+	s = &selector{options: []string{"a", "b", "c"}, multi: true, checked: map[int]bool{}}
+	if done, _ := s.handle(keys.Key{Code: keys.Enter}); done {
+		t.Error("multi select needs a checked option")
+	}
+	s.handle(keys.Key{Code: keys.Down})
+	s.handle(keys.Key{Code: keys.Space})
+	s.handle(keys.Key{Code: keys.Down})
+	s.handle(keys.Key{Code: keys.Space})
+	if r := s.result(); len(r) != 2 || r[0] != 1 || r[1] != 2 {
+		t.Errorf("result = %v", r)
+	}
+	if _, canceled := s.handle(keys.Key{Code: keys.Esc}); !canceled {
+		t.Error("esc should cancel")
+	}
+}
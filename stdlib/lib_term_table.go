@@ -0,0 +1,247 @@
+package stdlib
+
+import (
+	"regexp"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/lollipopkit/gommon/res"
+	. "github.com/lollipopkit/lk/api"
+)
+
+var reAnsi = regexp.MustCompile(`\x1b\[[0-9;]*[A-Za-z]`)
+
+// chars of a table border: left, mid, right, line for each row kind
+type tableBorder struct {
+	top, sep, bottom [4]string
+	row              [3]string // around and between cells
+	ellipsis         string
+}
+
+var tableBorders = map[string]tableBorder{
+	"unicode": {
+		top:      [4]string{"┌", "┬", "┐", "─"},
+		sep:      [4]string{"├", "┼", "┤", "─"},
+		bottom:   [4]string{"└", "┴", "┘", "─"},
+		row:      [3]string{"│ ", " │ ", " │"},
+		ellipsis: "…",
+	},
+	"ascii": {
+		top:      [4]string{"+", "+", "+", "-"},
+		sep:      [4]string{"+", "+", "+", "-"},
+		bottom:   [4]string{"+", "+", "+", "-"},
+		row:      [3]string{"| ", " | ", " |"},
+		ellipsis: "...",
+	},
+	"plain": {
+		row:      [3]string{"", "  ", ""},
+		ellipsis: "...",
+	},
+}
+
+// term.table(rows [, opts])
+// rows is a list of lists, or a list of maps with opts.headers as keys.
+// opts:
+//   - headers: list of column titles
+//   - align: `l` `r` or `c` for all columns, or a list of them
+//   - max_width: longer cells are cut with `…`
+//   - style: `unicode` (default), `ascii` or `plain`
+//   - color: color of headers, eg: term.cyan
+//
+// Colors in cells are kept and not counted in widths.
+// return str
+func termTable(ls LkState) int {
+	ls.CheckType(1, LK_TTABLE)
+	headers := []string{}
+	aligns := []string{}
+	align, style, color := "l", "unicode", ""
+	maxWidth := 0
+	if !ls.IsNoneOrNil(2) {
+		ls.CheckType(2, LK_TTABLE)
+		headers = _strListField(ls, 2, "headers")
+		if ls.GetField(2, "align") == LK_TSTRING {
+			align = ls.ToString(-1)
+		}
+		ls.Pop(1)
+		aligns = _strListField(ls, 2, "align")
+		maxWidth = int(_optIntField(ls, 2, "max_width", 0))
+		if ls.GetField(2, "style") == LK_TSTRING {
+			style = ls.ToString(-1)
+		}
+		ls.Pop(1)
+		if ls.GetField(2, "color") == LK_TSTRING {
+			color = ls.ToString(-1)
+		}
+		ls.Pop(1)
+	}
+	border, ok := tableBorders[style]
+	if !ok {
+		return ls.ArgError(2, "unknown style: "+style)
+	}
+
+	rows := [][]string{}
+	for i := int64(0); i < ls.Len2(1); i++ {
+		ls.GetI(1, i)
+		if !ls.IsTable(-1) {
+			return ls.ArgError(1, "rows must be tables")
+		}
+		rows = append(rows, _tableRow(ls, headers))
+		ls.Pop(1)
+	}
+
+	/* column widths */
+	cols := len(headers)
+	for _, r := range rows {
+		if len(r) > cols {
+			cols = len(r)
+		}
+	}
+	all := rows
+	if len(headers) > 0 {
+		all = append([][]string{headers}, rows...)
+	}
+	widths := make([]int, cols)
+	for _, r := range all {
+		for c := range r {
+			if maxWidth > 0 {
+				r[c] = _truncCell(r[c], maxWidth, border.ellipsis)
+			}
+			if w := _displayWidth(r[c]); w > widths[c] {
+				widths[c] = w
+			}
+		}
+	}
+
+	lines := []string{}
+	line := func(chars [4]string) {
+		if chars[3] == "" {
+			return
+		}
+		parts := make([]string, cols)
+		for c, w := range widths {
+			parts[c] = strings.Repeat(chars[3], w+2)
+		}
+		lines = append(lines, chars[0]+strings.Join(parts, chars[1])+chars[2])
+	}
+	row := func(r []string, isHeader bool) {
+		cells := make([]string, cols)
+		for c, w := range widths {
+			cell := ""
+			if c < len(r) {
+				cell = r[c]
+			}
+			a := align
+			if c < len(aligns) {
+				a = aligns[c]
+			}
+			if isHeader && color != "" {
+				cells[c] = color + _padCell(cell, w, a) + res.NOCOLOR
+			} else {
+				cells[c] = _padCell(cell, w, a)
+			}
+		}
+		s := border.row[0] + strings.Join(cells, border.row[1]) + border.row[2]
+		lines = append(lines, strings.TrimRight(s, " "))
+	}
+
+	line(border.top)
+	if len(headers) > 0 {
+		row(headers, true)
+		line(border.sep)
+	}
+	for _, r := range rows {
+		row(r, false)
+	}
+	line(border.bottom)
+	ls.PushString(strings.Join(lines, "\n"))
+	return 1
+}
+
+// _tableRow reads cells of the row at stack top
+func _tableRow(ls LkState, headers []string) []string {
+	cells := []string{}
+	n := ls.Len2(-1)
+	if n == 0 && len(headers) > 0 {
+		/* a map row */
+		for _, h := range headers {
+			ls.GetField(-1, h)
+			cells = append(cells, _cellStr(ls))
+		}
+		return cells
+	}
+	for i := int64(0); i < n; i++ {
+		ls.GetI(-1, i)
+		cells = append(cells, _cellStr(ls))
+	}
+	return cells
+}
+
+func _padCell(s string, width int, align string) string {
+	pad := width - _displayWidth(s)
+	switch align {
+	case "r":
+		return strings.Repeat(" ", pad) + s
+	case "c":
+		return strings.Repeat(" ", pad/2) + s + strings.Repeat(" ", pad-pad/2)
+	}
+	return s + strings.Repeat(" ", pad)
+}
+
+// _truncCell cuts s to max columns, color codes are kept
+func _truncCell(s string, max int, ellipsis string) string {
+	if _displayWidth(s) <= max {
+		return s
+	}
+	limit := max - _displayWidth(ellipsis)
+	var b strings.Builder
+	w := 0
+	colored := false
+	for len(s) > 0 {
+		if loc := reAnsi.FindStringIndex(s); s[0] == 0x1b && loc != nil && loc[0] == 0 {
+			b.WriteString(s[:loc[1]])
+			s = s[loc[1]:]
+			colored = true
+			continue
+		}
+		r, size := utf8.DecodeRuneInString(s)
+		if w+_runeWidth(r) > limit {
+			break
+		}
+		w += _runeWidth(r)
+		b.WriteRune(r)
+		s = s[size:]
+	}
+	b.WriteString(ellipsis)
+	if colored {
+		b.WriteString(res.NOCOLOR)
+	}
+	return b.String()
+}
+
+// _displayWidth is the number of terminal columns of s
+func _displayWidth(s string) int {
+	w := 0
+	for _, r := range reAnsi.ReplaceAllString(s, "") {
+		w += _runeWidth(r)
+	}
+	return w
+}
+
+// _runeWidth is 2 for east asian wide chars and emojis
+func _runeWidth(r rune) int {
+	switch {
+	case r < 0x1100:
+		return 1
+	case r <= 0x115f,
+		0x2e80 <= r && r <= 0xa4cf && r != 0x303f,
+		0xac00 <= r && r <= 0xd7a3,
+		0xf900 <= r && r <= 0xfaff,
+		0xfe30 <= r && r <= 0xfe4f,
+		0xff00 <= r && r <= 0xff60,
+		0xffe0 <= r && r <= 0xffe6,
+		0x1f300 <= r && r <= 0x1faff,
+		0x20000 <= r && r <= 0x3fffd:
+		return 2
+	}
+	return 1
+}
@@ -0,0 +1,164 @@
+//go:build !lk_minimal
+
+package stdlib
+
+import (
+	"errors"
+	"net"
+	"strconv"
+	"sync"
+	"time"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+var udpLib = map[string]GoFunction{
+	"bind": udpBind,
+}
+
+var udpMethods = map[string]GoFunction{
+	"send_to":   udpSendTo,
+	"recv_from": udpRecvFrom,
+	"broadcast": udpBroadcast,
+	"close":     udpClose,
+}
+
+// max size of a udp payload
+const udpBufSize = 65535
+
+var (
+	udpConns   = map[int64]*net.UDPConn{}
+	udpConnsMu sync.Mutex
+	udpNextId  int64
+)
+
+func OpenUdpLib(ls LkState) int {
+	ls.NewLib(udpLib)
+	return 1
+}
+
+// udp.bind([addr])
+// addr like `0.0.0.0:9000`, `:0` (default) picks a free port.
+// Sending to broadcast addresses is allowed.
+// return sock, err. sock.addr is the bound address
+func udpBind(ls LkState) int {
+	addr, err := net.ResolveUDPAddr("udp", ls.OptString(1, ":0"))
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+	conn, err := net.ListenUDP("udp", addr)
+	if err != nil {
+		ls.PushNil()
+		ls.PushString(err.Error())
+		return 2
+	}
+
+	udpConnsMu.Lock()
+	udpNextId++
+	id := udpNextId
+	udpConns[id] = conn
+	udpConnsMu.Unlock()
+	_trackOpen(ls, "udp socket", conn.LocalAddr())
+
+	ls.NewLib(udpMethods)
+	ls.PushInteger(id)
+	ls.SetField(-2, "id")
+	ls.PushString(conn.LocalAddr().String())
+	ls.SetField(-2, "addr")
+	ls.PushNil()
+	return 2
+}
+
+func _udpSelf(ls LkState) *net.UDPConn {
+	ls.CheckType(1, LK_TTABLE)
+	ls.GetField(1, "id")
+	id := ls.ToInteger(-1)
+	ls.Pop(1)
+	udpConnsMu.Lock()
+	defer udpConnsMu.Unlock()
+	conn, ok := udpConns[id]
+	if !ok {
+		ls.Error2("udp socket is closed")
+	}
+	return conn
+}
+
+func _udpSend(ls LkState, conn *net.UDPConn, addr, data string) int {
+	to, err := net.ResolveUDPAddr("udp", addr)
+	if err == nil {
+		_, err = conn.WriteToUDP([]byte(data), to)
+	}
+	if err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
+
+// sock:send_to(addr, data)
+// return err
+func udpSendTo(ls LkState) int {
+	conn := _udpSelf(ls)
+	return _udpSend(ls, conn, ls.CheckString(2), ls.CheckString(3))
+}
+
+// sock:broadcast(port, data)
+// sends to 255.255.255.255:port
+// return err
+func udpBroadcast(ls LkState) int {
+	conn := _udpSelf(ls)
+	port := ls.CheckInteger(2)
+	return _udpSend(ls, conn, net.JoinHostPort(net.IPv4bcast.String(), strconv.FormatInt(port, 10)), ls.CheckString(3))
+}
+
+// sock:recv_from([timeout])
+// timeout is in ms, 0 (default) waits forever
+// return data, addr, err. err is `timeout` if nothing arrived
+func udpRecvFrom(ls LkState) int {
+	conn := _udpSelf(ls)
+	timeout := ls.OptInteger(2, 0)
+	var deadline time.Time
+	if timeout > 0 {
+		deadline = time.Now().Add(time.Duration(timeout) * time.Millisecond)
+	}
+	conn.SetReadDeadline(deadline)
+
+	buf := make([]byte, udpBufSize)
+	n, from, err := conn.ReadFromUDP(buf)
+	if err != nil {
+		ls.PushNil()
+		ls.PushNil()
+		var netErr net.Error
+		if errors.As(err, &netErr) && netErr.Timeout() {
+			ls.PushString("timeout")
+		} else {
+			ls.PushString(err.Error())
+		}
+		return 3
+	}
+	ls.PushString(string(buf[:n]))
+	ls.PushString(from.String())
+	ls.PushNil()
+	return 3
+}
+
+// sock:close()
+// return err
+func udpClose(ls LkState) int {
+	conn := _udpSelf(ls)
+	ls.GetField(1, "id")
+	udpConnsMu.Lock()
+	delete(udpConns, ls.ToInteger(-1))
+	udpConnsMu.Unlock()
+	ls.Pop(1)
+	_trackClose("udp socket", conn.LocalAddr())
+	if err := conn.Close(); err != nil {
+		ls.PushString(err.Error())
+		return 1
+	}
+	ls.PushNil()
+	return 1
+}
@@ -0,0 +1,290 @@
+package stdlib
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
+
+type ArgType int
+
+const (
+	ArgAny ArgType = iota
+	ArgStr
+	ArgInt
+	ArgNum
+	ArgBool
+	ArgTable
+	ArgFn
+)
+
+var argTypeNames = [...]string{"any", "str", "int", "num", "bool", "table", "fn"}
+
+func (t ArgType) String() string {
+	return argTypeNames[t]
+}
+
+type fnParam struct {
+	name     string
+	tp       ArgType
+	dft      any
+	optional bool
+}
+
+func (p fnParam) String() string {
+	if !p.optional {
+		return p.name + ": " + p.tp.String()
+	}
+	switch dft := p.dft.(type) {
+	case nil:
+		return p.name + "?: " + p.tp.String()
+	case string:
+		return fmt.Sprintf("%s: %s = '%s'", p.name, p.tp, dft)
+	default:
+		return fmt.Sprintf("%s: %s = %v", p.name, p.tp, dft)
+	}
+}
+
+// NativeFn declares the signature of a Go function called by lk code:
+//
+//	NewFn("net.connect").Pos("host", ArgStr).Named("timeout", ArgInt, 30).Build(f)
+//
+// Named args are passed in a table after positional ones:
+// net.connect('a.com', {'timeout': 5}).
+// Args are checked before f is called, missing ones get their defaults.
+type NativeFn struct {
+	name  string
+	doc   string
+	pos   []fnParam
+	named []fnParam
+
+	// hint of the replacement, set if deprecated
+	deprecated string
+}
+
+// nativeFns are the built ones, by name
+var nativeFns = map[string]*NativeFn{}
+
+func NewFn(name string) *NativeFn {
+	return &NativeFn{name: name}
+}
+
+// Pos adds a required positional arg
+func (f *NativeFn) Pos(name string, tp ArgType) *NativeFn {
+	f.pos = append(f.pos, fnParam{name: name, tp: tp})
+	return f
+}
+
+// Opt adds an optional positional arg, it must follow required ones
+func (f *NativeFn) Opt(name string, tp ArgType, dft any) *NativeFn {
+	f.pos = append(f.pos, fnParam{name, tp, dft, true})
+	return f
+}
+
+// Named adds an optional arg passed by name
+func (f *NativeFn) Named(name string, tp ArgType, dft any) *NativeFn {
+	f.named = append(f.named, fnParam{name, tp, dft, true})
+	return f
+}
+
+func (f *NativeFn) Doc(doc string) *NativeFn {
+	f.doc = doc
+	return f
+}
+
+// Deprecated makes calls warn with hint, see DenyDeprecated
+func (f *NativeFn) Deprecated(hint string) *NativeFn {
+	f.deprecated = hint
+	return f
+}
+
+func (f *NativeFn) Name() string {
+	return f.name
+}
+
+// String is the signature, eg: `net.connect(host: str, {timeout: int = 30})`
+func (f *NativeFn) String() string {
+	params := make([]string, 0, len(f.pos)+1)
+	for _, p := range f.pos {
+		params = append(params, p.String())
+	}
+	if len(f.named) > 0 {
+		named := make([]string, len(f.named))
+		for i, p := range f.named {
+			named[i] = p.String()
+		}
+		params = append(params, "{"+strings.Join(named, ", ")+"}")
+	}
+	return f.name + "(" + strings.Join(params, ", ") + ")"
+}
+
+// Help is the signature followed by doc
+func (f *NativeFn) Help() string {
+	help := f.String()
+	if f.deprecated != "" {
+		help += "\ndeprecated: " + f.deprecated
+	}
+	if f.doc != "" {
+		help += "\n" + f.doc
+	}
+	return help
+}
+
+// LookupFn returns the signature of a built fn, eg: `num.currency`
+func LookupFn(name string) (*NativeFn, bool) {
+	f, ok := nativeFns[name]
+	return f, ok
+}
+
+// NativeFnNames are names of all built fns, sorted
+func NativeFnNames() []string {
+	names := make([]string, 0, len(nativeFns))
+	for name := range nativeFns {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// FnArgs reads args checked by NativeFn.
+// Positional ones are at 1..n on the stack, named ones follow in order.
+type FnArgs struct {
+	ls  LkState
+	idx map[string]int
+}
+
+// Idx is the stack index of arg name
+func (a *FnArgs) Idx(name string) int {
+	idx, ok := a.idx[name]
+	if !ok {
+		panic("unknown arg: " + name)
+	}
+	return idx
+}
+
+func (a *FnArgs) Str(name string) string {
+	return a.ls.ToString(a.Idx(name))
+}
+
+func (a *FnArgs) Int(name string) int64 {
+	return a.ls.ToInteger(a.Idx(name))
+}
+
+func (a *FnArgs) Num(name string) float64 {
+	return a.ls.ToNumber(a.Idx(name))
+}
+
+func (a *FnArgs) Bool(name string) bool {
+	return a.ls.ToBoolean(a.Idx(name))
+}
+
+// IsNil reports whether arg name is not given and has no default
+func (a *FnArgs) IsNil(name string) bool {
+	return a.ls.IsNil(a.Idx(name))
+}
+
+// Build wraps fn with checks of args, and registers the signature
+func (f *NativeFn) Build(fn func(ls LkState, args *FnArgs) int) GoFunction {
+	nativeFns[f.name] = f
+	return func(ls LkState) int {
+		if f.deprecated != "" {
+			_warnDeprecated(ls, f.name, f.deprecated)
+		}
+		args := &FnArgs{ls, make(map[string]int, len(f.pos)+len(f.named))}
+		f.checkPos(ls, args)
+		f.checkNamed(ls, args)
+		return fn(ls, args)
+	}
+}
+
+func (f *NativeFn) checkPos(ls LkState, args *FnArgs) {
+	n := len(f.pos)
+	max := n
+	if len(f.named) > 0 {
+		max++
+	}
+	if top := ls.GetTop(); top > max {
+		ls.Error2("%s: %d args expected, got %d", f.name, max, top)
+	}
+	ls.SetTop(max)
+	for i, p := range f.pos {
+		idx := i + 1
+		args.idx[p.name] = idx
+		if ls.IsNoneOrNil(idx) {
+			if !p.optional {
+				ls.ArgError(idx, fmt.Sprintf("%s: %s expected", p.name, p.tp))
+			}
+			pushValue(ls, p.dft)
+			ls.Replace(idx)
+			continue
+		}
+		if !_argIs(ls, idx, p.tp) {
+			ls.ArgError(idx, fmt.Sprintf("%s: %s expected, got %s", p.name, p.tp, ls.TypeName2(idx)))
+		}
+	}
+}
+
+// checkNamed replaces the table of named args with their values
+func (f *NativeFn) checkNamed(ls LkState, args *FnArgs) {
+	if len(f.named) == 0 {
+		return
+	}
+	optsIdx := len(f.pos) + 1
+	if ls.IsNil(optsIdx) {
+		ls.NewTable()
+		ls.Replace(optsIdx)
+	}
+	if !ls.IsTable(optsIdx) {
+		ls.ArgError(optsIdx, "table of named args expected, got "+ls.TypeName2(optsIdx))
+	}
+	ls.PushNil()
+	for ls.Next(optsIdx) {
+		ls.Pop(1)
+		name := ls.ToString2(-1)
+		ls.Pop(1)
+		if !f.hasNamed(name) {
+			ls.Error2("%s: unknown named arg '%s'", f.name, name)
+		}
+	}
+	for i, p := range f.named {
+		if ls.GetField(optsIdx, p.name) == LK_TNIL {
+			ls.Pop(1)
+			pushValue(ls, p.dft)
+		} else if !_argIs(ls, -1, p.tp) {
+			ls.Error2("%s: named arg '%s': %s expected, got %s", f.name, p.name, p.tp, ls.TypeName2(-1))
+		}
+		/* the table is removed below */
+		args.idx[p.name] = optsIdx + i
+	}
+	ls.Remove(optsIdx)
+}
+
+func (f *NativeFn) hasNamed(name string) bool {
+	for _, p := range f.named {
+		if p.name == name {
+			return true
+		}
+	}
+	return false
+}
+
+func _argIs(ls LkState, idx int, tp ArgType) bool {
+	switch tp {
+	case ArgStr:
+		return ls.IsString(idx)
+	case ArgInt:
+		_, ok := ls.ToIntegerX(idx)
+		return ok && ls.IsNumber(idx)
+	case ArgNum:
+		return ls.IsNumber(idx)
+	case ArgBool:
+		return ls.IsBoolean(idx)
+	case ArgTable:
+		return ls.IsTable(idx)
+	case ArgFn:
+		return ls.IsFunction(idx)
+	}
+	return true
+}
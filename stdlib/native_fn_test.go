@@ -0,0 +1,19 @@
+package stdlib
+
+import "testing"
+
+func TestNativeFnString(t *testing.T) {
+	f := NewFn("net.connect").
+		Pos("host", ArgStr).
+		Opt("port", ArgInt, 80).
+		Named("timeout", ArgNum, 1.5).
+		Named("proxy", ArgStr, nil)
+	want := "net.connect(host: str, port: int = 80, {timeout: num = 1.5, proxy?: str})"
+	if got := f.String(); got != want {
+		t.Errorf("got %s, want %s", got, want)
+	}
+
+	if f, ok := LookupFn("num.currency"); !ok || f.String() != "num.currency(amount: any, code: str, {locale: str = 'en-US'})" {
+		t.Errorf("num.currency: %v", f)
+	}
+}
@@ -71,12 +71,13 @@ func _fmtArg(tag string, ls LkState, argIdx int) string {
 		return fmt.Sprintf(tag, uint(ls.ToInteger(argIdx)))
 	case 'x', 'X': // hex integer
 		return fmt.Sprintf(tag, uint(ls.ToInteger(argIdx)))
-	case 'f': // float
+	case 'f', 'e', 'E', 'g', 'G': // float
 		return fmt.Sprintf(tag, ls.ToNumber(argIdx))
 	case 's', 'q': // string
 		return fmt.Sprintf(tag, ls.ToString2(argIdx))
 	default:
-		panic("todo! tag=" + tag)
+		ls.Error2("fmt: invalid conversion '%s'", tag)
+		return ""
 	}
 }
 
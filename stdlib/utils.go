@@ -137,3 +137,35 @@ func getFunc(ls LkState, idx int) GoFunction {
 	ls.CheckType(idx, LK_TFUNCTION)
 	return ls.ToGoFunction(idx)
 }
+
+func _optIntField(ls LkState, idx int, key string, dft int64) int64 {
+	defer ls.Pop(1)
+	if ls.GetField(idx, key) == LK_TNUMBER {
+		return ls.ToInteger(-1)
+	}
+	return dft
+}
+
+// _cellStr pops the value at stack top as str, nil is empty
+func _cellStr(ls LkState) string {
+	defer ls.Pop(1)
+	if ls.IsNil(-1) {
+		return ""
+	}
+	s := ls.ToString2(-1)
+	ls.Pop(1)
+	return s
+}
+
+func _strListField(ls LkState, idx int, key string) []string {
+	defer ls.Pop(1)
+	list := []string{}
+	if ls.GetField(idx, key) != LK_TTABLE {
+		return list
+	}
+	for i := int64(0); i < ls.Len2(-1); i++ {
+		ls.GetI(-1, i)
+		list = append(list, _cellStr(ls))
+	}
+	return list
+}
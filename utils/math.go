@@ -32,6 +32,32 @@ func IFloorDiv(a, b int64) int64 {
 	}
 }
 
+// AddOverflows reports whether a + b overflows int64
+func AddOverflows(a, b int64) bool {
+	s := a + b
+	return (a^s)&(b^s) < 0
+}
+
+// SubOverflows reports whether a - b overflows int64
+func SubOverflows(a, b int64) bool {
+	d := a - b
+	return (a^b)&(a^d) < 0
+}
+
+// MulOverflows reports whether a * b overflows int64
+func MulOverflows(a, b int64) bool {
+	if a == 0 || b == 0 {
+		return false
+	}
+	p := a * b
+	return p/b != a || a == math.MinInt64 && b == -1
+}
+
+// IFloorDivOverflows reports whether a // b overflows int64
+func IFloorDivOverflows(a, b int64) bool {
+	return a == math.MinInt64 && b == -1
+}
+
 func FFloorDiv(a, b float64) float64 {
 	return math.Floor(a / b)
 }
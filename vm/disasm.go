@@ -0,0 +1,153 @@
+package vm
+
+import (
+	"fmt"
+	"io"
+	"sort"
+	"strings"
+
+	"github.com/lollipopkit/lk/binchunk"
+)
+
+// Disasm writes a readable listing of proto and its children:
+// instructions with jump targets as labels and operands resolved
+// to constants or upvalue names, then constants, locals and upvalues.
+func Disasm(w io.Writer, proto *binchunk.Prototype) {
+	disasmProto(w, proto, "main")
+}
+
+func disasmProto(w io.Writer, p *binchunk.Prototype, name string) {
+	fmt.Fprintf(w, "fn %s <%s:%d,%d> params=%d vararg=%d stack=%d, %d instructions\n",
+		name, p.Source, p.LineDefined, p.LastLineDefined,
+		p.NumParams, p.IsVararg, p.MaxStackSize, len(p.Code))
+
+	labels := jumpLabels(p)
+	for pc, code := range p.Code {
+		if label, ok := labels[pc]; ok {
+			fmt.Fprintf(w, "L%d:\n", label)
+		}
+		i := Instruction(code)
+		line := uint32(0)
+		if pc < len(p.LineInfo) {
+			line = p.LineInfo[pc]
+		}
+		args, comment := disasmArgs(p, pc, labels, name)
+		if comment != "" {
+			comment = "\t; " + comment
+		}
+		fmt.Fprintf(w, "\t%d\t[%d]\t%s %s%s\n", pc, line, i.OpName(), args, comment)
+	}
+
+	fmt.Fprintf(w, "constants (%d)\n", len(p.Constants))
+	for idx, k := range p.Constants {
+		fmt.Fprintf(w, "\t%d\t%s\n", idx, constStr(k))
+	}
+	fmt.Fprintf(w, "locals (%d)\n", len(p.LocVars))
+	for idx, v := range p.LocVars {
+		fmt.Fprintf(w, "\t%d\t%s\t%d-%d\n", idx, v.VarName, v.StartPC, v.EndPC)
+	}
+	fmt.Fprintf(w, "upvalues (%d)\n", len(p.Upvalues))
+	for idx, uv := range p.Upvalues {
+		fmt.Fprintf(w, "\t%d\t%s\tinstack=%d idx=%d\n", idx, upvalName(p, idx), uv.Instack, uv.Idx)
+	}
+	for idx, sub := range p.Protos {
+		fmt.Fprintln(w)
+		disasmProto(w, sub, fmt.Sprintf("%s.%d", name, idx))
+	}
+}
+
+// jumpTarget is the pc jumped to by the instruction at pc, -1 if it doesn't jump
+func jumpTarget(p *binchunk.Prototype, pc int) int {
+	i := Instruction(p.Code[pc])
+	switch i.Opcode() {
+	case OP_JMP, OP_FORLOOP, OP_FORPREP, OP_TFORLOOP:
+		_, sbx := i.AsBx()
+		return pc + 1 + sbx
+	}
+	return -1
+}
+
+// jumpLabels numbers jump targets in order: target pc -> label
+func jumpLabels(p *binchunk.Prototype) map[int]int {
+	targets := []int{}
+	seen := map[int]bool{}
+	for pc := range p.Code {
+		if t := jumpTarget(p, pc); t >= 0 && !seen[t] {
+			seen[t] = true
+			targets = append(targets, t)
+		}
+	}
+	sort.Ints(targets)
+	labels := make(map[int]int, len(targets))
+	for idx, t := range targets {
+		labels[t] = idx + 1
+	}
+	return labels
+}
+
+func disasmArgs(p *binchunk.Prototype, pc int, labels map[int]int, name string) (args, comment string) {
+	i := Instruction(p.Code[pc])
+	comments := []string{}
+	rk := func(arg int, mode byte) {
+		if mode == OpArgK && arg > 0xFF {
+			comments = append(comments, constAt(p, arg&0xFF))
+		}
+	}
+	switch i.OpMode() {
+	case IABC:
+		a, b, c := i.ABC()
+		args = fmt.Sprintf("%d %d %d", a, b, c)
+		switch i.Opcode() {
+		case OP_GETUPVAL, OP_SETUPVAL:
+			comments = append(comments, upvalName(p, b))
+		case OP_GETTABUP:
+			comments = append(comments, upvalName(p, b))
+		case OP_SETTABUP:
+			comments = append(comments, upvalName(p, a))
+		}
+		rk(b, i.BMode())
+		rk(c, i.CMode())
+	case IABx:
+		a, bx := i.ABx()
+		args = fmt.Sprintf("%d %d", a, bx)
+		switch i.Opcode() {
+		case OP_LOADK, OP_LOADDEC:
+			comments = append(comments, constAt(p, bx))
+		case OP_CLOSURE:
+			comments = append(comments, fmt.Sprintf("fn %s.%d", name, bx))
+		}
+	case IAsBx:
+		a, sbx := i.AsBx()
+		args = fmt.Sprintf("%d %d", a, sbx)
+		if t := jumpTarget(p, pc); t >= 0 {
+			comments = append(comments, fmt.Sprintf("to L%d", labels[t]))
+		}
+	case IAx:
+		args = fmt.Sprintf("%d", i.Ax())
+	}
+	return args, strings.Join(comments, " ")
+}
+
+func constAt(p *binchunk.Prototype, idx int) string {
+	if idx < 0 || idx >= len(p.Constants) {
+		return "?"
+	}
+	return constStr(p.Constants[idx])
+}
+
+func constStr(k any) string {
+	if s, ok := k.(string); ok {
+		return fmt.Sprintf("%q", s)
+	}
+	if k == nil {
+		return "nil"
+	}
+	return fmt.Sprintf("%v", k)
+}
+
+func upvalName(p *binchunk.Prototype, idx int) string {
+	if idx < len(p.UpvalueNames) {
+		return p.UpvalueNames[idx]
+	}
+	return "-"
+}
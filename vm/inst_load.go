@@ -1,6 +1,9 @@
 package vm
 
-import . "github.com/lollipopkit/lk/api"
+import (
+	. "github.com/lollipopkit/lk/api"
+	"github.com/lollipopkit/lk/decimal"
+)
 
 // R(A), R(A+1), ..., R(A+B) := nil
 func loadNil(i Instruction, vm LkVM) {
@@ -46,3 +49,18 @@ func loadKx(i Instruction, vm LkVM) {
 	vm.GetConst(ax)
 	vm.Replace(a)
 }
+
+// R(A) := decimal(Kst(Bx))
+func loadDec(i Instruction, vm LkVM) {
+	a, bx := i.ABx()
+	a += 1
+
+	vm.GetConst(bx)
+	d, err := decimal.Parse(vm.ToString(-1))
+	if err != nil {
+		vm.Error2("malformed decimal %s", vm.ToString(-1))
+	}
+	vm.Pop(1)
+	vm.Push(d)
+	vm.Replace(a)
+}
@@ -1,6 +1,10 @@
 package vm
 
-import . "github.com/lollipopkit/lk/api"
+import (
+	"strings"
+
+	. "github.com/lollipopkit/lk/api"
+)
 
 /* arith */
 
@@ -105,3 +109,19 @@ func length(i Instruction, vm LkVM) {
 	vm.Len(b)
 	vm.Replace(a)
 }
+
+// R(A) := str(R(B)) + ... + str(R(C)), for interpolated strs
+func concat(i Instruction, vm LkVM) {
+	a, b, c := i.ABC()
+	a += 1
+	b += 1
+	c += 1
+
+	var sb strings.Builder
+	for r := b; r <= c; r++ {
+		sb.WriteString(vm.ToString2(r))
+		vm.Pop(1)
+	}
+	vm.PushString(sb.String())
+	vm.Replace(a)
+}
@@ -67,6 +67,8 @@ const (
 	OP_CLOSURE
 	OP_VARARG
 	OP_EXTRAARG
+	OP_LOADDEC
+	OP_CONCAT
 )
 
 type opcode struct {
@@ -129,4 +131,6 @@ var opcodes = []opcode{
 	{0, 1, OpArgU, OpArgN, IABx /* */, "CLOSURE ", closure},  // R(A) := closure(KPROTO[Bx])
 	{0, 1, OpArgU, OpArgN, IABC /* */, "VARARG  ", vararg},   // R(A), R(A+1), ..., R(A+B-2) = vararg
 	{0, 0, OpArgU, OpArgU, IAx /*  */, "EXTRAARG", nil},      // extra (larger) argument for previous opcode
+	{0, 1, OpArgK, OpArgN, IABx /* */, "LOADDEC ", loadDec},  // R(A) := decimal(Kst(Bx))
+	{0, 1, OpArgR, OpArgR, IABC /* */, "CONCAT  ", concat},   // R(A) := str(R(B)) + ... + str(R(C))
 }
@@ -0,0 +1,292 @@
+package vm
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/lollipopkit/lk/binchunk"
+)
+
+// Kinds of VerifyError
+const (
+	VerifyFormat   = "format"
+	VerifyOpcode   = "opcode"
+	VerifyRegister = "register"
+	VerifyConstant = "constant"
+	VerifyUpvalue  = "upvalue"
+	VerifyProto    = "proto"
+	VerifyJump     = "jump"
+)
+
+// VerifyError tells where and why a chunk is rejected by Verify
+type VerifyError struct {
+	// Path of the prototype, eg: main.fn0.fn1
+	Proto string
+	// -1 if it's not about an instruction
+	PC   int
+	Op   string
+	Kind string
+	Msg  string
+}
+
+func (e *VerifyError) Error() string {
+	if e.PC < 0 {
+		return fmt.Sprintf("%s: %s", e.Proto, e.Msg)
+	}
+	if e.Op == "" {
+		return fmt.Sprintf("%s: pc %d: %s", e.Proto, e.PC, e.Msg)
+	}
+	return fmt.Sprintf("%s: pc %d: %s: %s", e.Proto, e.PC, e.Op, e.Msg)
+}
+
+// Verify checks operands of every instruction in proto and its children:
+// registers, constants, upvalues and protos are in range, jumps land in code.
+// Chunks loaded from bytes are verified, so a broken one fails on load
+// instead of somewhere in the middle of running.
+// The error is a *VerifyError.
+func Verify(proto *binchunk.Prototype) error {
+	return verifyProto(proto, nil, "main")
+}
+
+func verifyProto(p, parent *binchunk.Prototype, where string) error {
+	bad := func(kind, format string, a ...any) error {
+		return &VerifyError{Proto: where, PC: -1, Kind: kind, Msg: fmt.Sprintf(format, a...)}
+	}
+	if p == nil {
+		return bad(VerifyFormat, "no prototype")
+	}
+	if len(p.Code) == 0 {
+		return bad(VerifyFormat, "no code")
+	}
+	if len(p.LineInfo) != 0 && len(p.LineInfo) != len(p.Code) {
+		return bad(VerifyFormat, "%d lines for %d instructions", len(p.LineInfo), len(p.Code))
+	}
+	if Instruction(p.Code[len(p.Code)-1]).Opcode() != OP_RETURN {
+		return bad(VerifyFormat, "code doesn't end with RETURN")
+	}
+	for i, k := range p.Constants {
+		switch k.(type) {
+		case nil, bool, int64, float64, string:
+		default:
+			return bad(VerifyConstant, "constant %d has invalid type %T", i, k)
+		}
+	}
+	if parent != nil {
+		for i, uv := range p.Upvalues {
+			if uv.Instack == 1 && int(uv.Idx) >= int(parent.MaxStackSize) ||
+				uv.Instack != 1 && int(uv.Idx) >= len(parent.Upvalues) {
+				return bad(VerifyUpvalue, "upvalue %d out of range", i)
+			}
+		}
+	}
+	for pc := range p.Code {
+		if err := verifyInst(p, pc, where); err != nil {
+			return err
+		}
+	}
+	for i, sub := range p.Protos {
+		if err := verifyProto(sub, p, fmt.Sprintf("%s.fn%d", where, i)); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func verifyInst(p *binchunk.Prototype, pc int, where string) error {
+	i := Instruction(p.Code[pc])
+	op := i.Opcode()
+	if op >= len(opcodes) {
+		return &VerifyError{Proto: where, PC: pc, Kind: VerifyOpcode, Msg: fmt.Sprintf("invalid opcode %d", op)}
+	}
+	bad := func(kind, format string, a ...any) error {
+		return &VerifyError{Proto: where, PC: pc, Op: strings.TrimSpace(i.OpName()), Kind: kind, Msg: fmt.Sprintf(format, a...)}
+	}
+	reg := func(r int) error {
+		if r < 0 || r >= int(p.MaxStackSize) {
+			return bad(VerifyRegister, "register %d out of range", r)
+		}
+		return nil
+	}
+	rk := func(x int) error {
+		if x > 0xFF {
+			if x&0xFF >= len(p.Constants) {
+				return bad(VerifyConstant, "constant %d out of range", x&0xFF)
+			}
+			return nil
+		}
+		return reg(x)
+	}
+	upval := func(idx int) error {
+		if idx >= len(p.Upvalues) {
+			return bad(VerifyUpvalue, "upvalue %d out of range", idx)
+		}
+		return nil
+	}
+	jump := func(sbx int) error {
+		if to := pc + 1 + sbx; to < 0 || to >= len(p.Code) {
+			return bad(VerifyJump, "jump to %d out of code", to)
+		}
+		return nil
+	}
+	arg := func(mode byte, x int) error {
+		switch mode {
+		case OpArgR:
+			return reg(x)
+		case OpArgK:
+			return rk(x)
+		}
+		return nil
+	}
+	extra := func() (int, error) {
+		if pc+1 >= len(p.Code) || Instruction(p.Code[pc+1]).Opcode() != OP_EXTRAARG {
+			return 0, bad(VerifyOpcode, "no EXTRAARG after it")
+		}
+		return Instruction(p.Code[pc+1]).Ax(), nil
+	}
+
+	if opcodes[op].testFlag == 1 {
+		if pc+1 >= len(p.Code) || Instruction(p.Code[pc+1]).Opcode() != OP_JMP {
+			return bad(VerifyJump, "not followed by JMP")
+		}
+	}
+
+	switch opcodes[op].opMode {
+	case IABx:
+		a, bx := i.ABx()
+		if err := reg(a); err != nil {
+			return err
+		}
+		switch op {
+		case OP_LOADK:
+			if bx >= len(p.Constants) {
+				return bad(VerifyConstant, "constant %d out of range", bx)
+			}
+		case OP_LOADDEC:
+			if bx >= len(p.Constants) {
+				return bad(VerifyConstant, "constant %d out of range", bx)
+			}
+			if _, ok := p.Constants[bx].(string); !ok {
+				return bad(VerifyConstant, "constant %d is not a str", bx)
+			}
+		case OP_LOADKX:
+			ax, err := extra()
+			if err != nil {
+				return err
+			}
+			if ax >= len(p.Constants) {
+				return bad(VerifyConstant, "constant %d out of range", ax)
+			}
+		case OP_CLOSURE:
+			if bx >= len(p.Protos) {
+				return bad(VerifyProto, "proto %d out of range", bx)
+			}
+		}
+		return nil
+	case IAsBx:
+		a, sbx := i.AsBx()
+		switch op {
+		case OP_JMP:
+			/* A > 0 closes upvalues >= R(A-1) */
+			if a > 0 {
+				if err := reg(a - 1); err != nil {
+					return err
+				}
+			}
+		case OP_FORLOOP, OP_FORPREP:
+			if err := reg(a + 3); err != nil {
+				return err
+			}
+		default:
+			if err := reg(a + 1); err != nil {
+				return err
+			}
+		}
+		return jump(sbx)
+	case IAx:
+		/* only read by the op before it */
+		if pc == 0 {
+			return bad(VerifyOpcode, "at start of code")
+		}
+		prev := Instruction(p.Code[pc-1])
+		_, _, c := prev.ABC()
+		if prev.Opcode() == OP_LOADKX || prev.Opcode() == OP_SETLIST && c == 0 {
+			return nil
+		}
+		return bad(VerifyOpcode, "after %s", prev.OpName())
+	}
+
+	a, b, c := i.ABC()
+	switch op {
+	case OP_GETUPVAL, OP_SETUPVAL:
+		if err := upval(b); err != nil {
+			return err
+		}
+		return reg(a)
+	case OP_GETTABUP:
+		if err := upval(b); err != nil {
+			return err
+		}
+		if err := reg(a); err != nil {
+			return err
+		}
+		return rk(c)
+	case OP_SETTABUP:
+		if err := upval(a); err != nil {
+			return err
+		}
+		if err := rk(b); err != nil {
+			return err
+		}
+		return rk(c)
+	case OP_CONCAT:
+		if b > c {
+			return bad(VerifyRegister, "registers %d..%d out of order", b, c)
+		}
+	case OP_EQ, OP_LT, OP_LE:
+		/* A is the expected result */
+		if err := rk(b); err != nil {
+			return err
+		}
+		return rk(c)
+	case OP_LOADBOOL:
+		if c != 0 && pc+2 >= len(p.Code) {
+			return bad(VerifyJump, "skips out of code")
+		}
+		return reg(a)
+	case OP_LOADNIL, OP_SETLIST:
+		if op == OP_SETLIST && c == 0 {
+			if _, err := extra(); err != nil {
+				return err
+			}
+		}
+		return reg(a + b)
+	case OP_SELF:
+		if err := reg(a + 1); err != nil {
+			return err
+		}
+	case OP_CALL, OP_TAILCALL:
+		if b > 0 {
+			return reg(a + b - 1)
+		}
+		return reg(a)
+	case OP_RETURN, OP_VARARG:
+		if b > 1 {
+			return reg(a + b - 2)
+		}
+		if op == OP_RETURN && b == 1 {
+			/* returns nothing, A isn't read */
+			return nil
+		}
+		return reg(a)
+	case OP_TFORCALL:
+		return reg(a + 2 + c)
+	}
+
+	if err := reg(a); err != nil {
+		return err
+	}
+	if err := arg(opcodes[op].argBMode, b); err != nil {
+		return err
+	}
+	return arg(opcodes[op].argCMode, c)
+}